@@ -0,0 +1,133 @@
+//! GitHub alerts, like `> [!WARNING]`, turned into markup rustdoc shows as intended
+//!
+//! GitHub renders a blockquote that opens with `[!NOTE]`, `[!TIP]`, `[!IMPORTANT]`, `[!WARNING]`
+//! or `[!CAUTION]` as a titled callout, but rustdoc shows the marker as text. With
+//! `admonitions = "rustdoc"` warnings and cautions become rustdoc's `<div class="warning">` block
+//! and the others a blockquote with a bold title, or an `admonition-template` gives the HTML for
+//! all of them. Only the injected docs change, so the doc files (and restore) keep GitHub's syntax.
+
+use crate::config::Admonitions;
+
+/// The kinds of alert GitHub has
+const KINDS: [&str; 5] = ["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"];
+
+/// One alert in the markdown: where it is, its kind (like `WARNING`) and its unquoted text
+struct Alert {
+    range: std::ops::Range<usize>,
+    kind: &'static str,
+    body: String,
+}
+
+/// Whether the markdown has any GitHub alerts
+pub fn has_alerts(markdown: &str) -> bool {
+    !alerts(markdown).is_empty()
+}
+
+/// Rewrites each GitHub alert in the markdown as `style` says
+pub fn render_alerts(markdown: &str, style: &Admonitions) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for alert in alerts(markdown) {
+        let kind = alert.kind.to_lowercase();
+        let title = format!("{}{}", &alert.kind[..1], &kind[1..]);
+        output.push_str(&markdown[rest..alert.range.start]);
+        match style {
+            Admonitions::Keep => output.push_str(&markdown[alert.range.clone()]),
+            Admonitions::Rustdoc if matches!(alert.kind, "WARNING" | "CAUTION") => {
+                output.push_str(&format!(
+                    "<div class=\"warning\">\n\n**{}**\n\n{}\n\n</div>\n",
+                    title, alert.body
+                ));
+            }
+            Admonitions::Rustdoc => {
+                output.push_str(&format!("> **{}**\n>\n", title));
+                for line in alert.body.lines() {
+                    output.push_str(format!("> {}", line).trim_end());
+                    output.push('\n');
+                }
+            }
+            Admonitions::Template(template) => {
+                output.push_str(
+                    &template
+                        .replace("{kind}", &kind)
+                        .replace("{title}", &title)
+                        .replace("{body}", &format!("\n\n{}\n\n", alert.body)),
+                );
+                output.push('\n');
+            }
+        }
+        rest = alert.range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    output
+}
+
+/// Each alert in the markdown, outside of code blocks
+///
+/// An alert is a blockquote whose first line is only the `[!KIND]` marker (in any case), and it
+/// runs for as long as the lines are quoted.
+fn alerts(markdown: &str) -> Vec<Alert> {
+    let mut found: Vec<Alert> = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    let mut current: Option<Alert> = None;
+    // Whether the line before was quoted, so a marker there would be inside another blockquote
+    let mut in_quote = false;
+    for line in markdown.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+
+        if let Some(alert) = current.as_mut() {
+            if let Some(quoted) = trimmed.strip_prefix('>') {
+                let quoted = quoted.trim_end_matches(['\n', '\r']);
+                alert
+                    .body
+                    .push_str(quoted.strip_prefix(' ').unwrap_or(quoted));
+                alert.body.push('\n');
+                alert.range.end = offset;
+                continue;
+            }
+            found.extend(current.take());
+        }
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let was_in_quote = std::mem::replace(&mut in_quote, trimmed.starts_with('>'));
+        let marker = trimmed
+            .strip_prefix('>')
+            .filter(|_| !was_in_quote)
+            .map(str::trim)
+            .and_then(|quoted| quoted.strip_prefix("[!"))
+            .and_then(|quoted| quoted.strip_suffix(']'));
+        if let Some(kind) = marker.and_then(|marker| {
+            KINDS
+                .into_iter()
+                .find(|kind| kind.eq_ignore_ascii_case(marker))
+        }) {
+            current = Some(Alert {
+                range: start..offset,
+                kind,
+                body: String::new(),
+            });
+        }
+    }
+    found.extend(current);
+    for alert in &mut found {
+        alert.body = alert.body.trim_matches('\n').to_string();
+    }
+    found
+}
+
+#[cfg(test)]
+#[path = "tests/admonition.rs"]
+mod admonition_tests;
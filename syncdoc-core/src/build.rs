@@ -0,0 +1,162 @@
+//! Rebuilding a crate when its docs change, for calling from its build script
+//!
+//! Cargo only rebuilds a crate when its own sources change, so without this a doc file added or
+//! edited after the last build isn't picked up (and a `missing = "warn"` warning keeps being
+//! replayed). With `syncdoc-core` as a build dependency, a `build.rs` whose `main` does
+//!
+//! ```no_run
+//! syncdoc_core::build::track_docs().unwrap();
+//! ```
+//!
+//! reruns the build whenever anything under the configured docs-path (in every locale), a file
+//! it quotes in a snippet, an image it embeds or a variable its placeholders read changes.
+//! Docs that `module_doc!()` processes need this most, as they are given to the compiler as text
+//! rather than as a file it can watch.
+//! With `locales` set, it also builds the docs in the locale named by the `SYNCDOC_LOCALE`
+//! variable, as in `SYNCDOC_LOCALE=ja cargo doc`, by setting the `syncdoc_locale` cfg that items'
+//! docs are gated behind and telling `module_doc!()` which locale's docs to read.
+
+use crate::config::{SyncdocConfig, LOCALE_CFG};
+use crate::doc_injector::beside;
+use crate::images::local_images;
+use crate::path_utils::doc_files;
+use crate::snippet::snippet_files;
+use crate::template::env_vars;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// The variable that picks the locale docs are built in, the default one if it isn't set
+pub const LOCALE_ENV: &str = "SYNCDOC_LOCALE";
+
+/// The variable [`track_docs`] passes the picked locale to `module_doc!()` in
+pub(crate) const ACTIVE_LOCALE_ENV: &str = "SYNCDOC_ACTIVE_LOCALE";
+
+/// Tells Cargo to rebuild the crate being built when its docs or its syncdoc config change
+pub fn track_docs() -> Result<(), Box<dyn Error>> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or("CARGO_MANIFEST_DIR isn't set, so this isn't being run from a build script")?;
+    let locale = std::env::var(LOCALE_ENV).ok();
+    for directive in directives(Path::new(&manifest_dir), locale.as_deref())? {
+        println!("{}", directive);
+    }
+    Ok(())
+}
+
+/// The `cargo:` lines for the crate whose manifest is in `manifest_dir`, built in `locale`
+pub(crate) fn directives(
+    manifest_dir: &Path,
+    locale: Option<&str>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let config = SyncdocConfig::read(&manifest_path)?.unwrap_or_default();
+    let docs_path = config.docs_path.as_deref().ok_or_else(|| {
+        format!(
+            "{} has no docs-path in [package.metadata.syncdoc]",
+            manifest_path.display()
+        )
+    })?;
+
+    let docs_paths = if docs_path.contains("{locale}") {
+        let locales = config
+            .locales
+            .as_ref()
+            .ok_or("docs-path has a {locale} in it, but no locales are set")?;
+        locales
+            .all
+            .iter()
+            .map(|locale| docs_path.replace("{locale}", locale))
+            .collect()
+    } else {
+        vec![docs_path.to_string()]
+    };
+
+    let mut directives = vec!["cargo:rerun-if-changed=Cargo.toml".to_string()];
+    if let Some(locales) = &config.locales {
+        let values: Vec<String> = locales
+            .all
+            .iter()
+            .map(|locale| format!("{:?}", locale))
+            .collect();
+        directives.push(format!(
+            "cargo:rustc-check-cfg=cfg({}, values({}))",
+            LOCALE_CFG,
+            values.join(", ")
+        ));
+        directives.push(format!("cargo:rerun-if-env-changed={}", LOCALE_ENV));
+        match locale.filter(|locale| !locale.is_empty()) {
+            Some(locale) if !locales.all.iter().any(|known| known == locale) => {
+                return Err(format!(
+                    "unknown locale `{}` in {}, expected one of {}",
+                    locale,
+                    LOCALE_ENV,
+                    locales.all.join(", ")
+                )
+                .into());
+            }
+            Some(locale) => {
+                if locale != locales.default {
+                    directives.push(format!("cargo:rustc-cfg={}={:?}", LOCALE_CFG, locale));
+                }
+                directives.push(format!("cargo:rustc-env={}={}", ACTIVE_LOCALE_ENV, locale));
+            }
+            None => {}
+        }
+    }
+    // Snippets, embedded images outside the docs directory and variables are read by the docs too
+    let mut files = BTreeSet::new();
+    let mut vars = BTreeSet::new();
+    for docs_path in &docs_paths {
+        let docs_root = manifest_dir.join(docs_path);
+        let docs = doc_files(&docs_root).unwrap_or_default();
+        for doc in docs.iter().filter(|file| file.ends_with(".md")) {
+            let Ok(markdown) = fs::read_to_string(docs_root.join(doc)) else {
+                continue;
+            };
+            files.extend(snippet_files(&markdown).into_iter().filter_map(normalised));
+            vars.extend(env_vars(&markdown));
+            if config.embed_images.is_some() {
+                let docs_dir = format!("{}/", normalised(docs_path).unwrap_or_default());
+                files.extend(
+                    local_images(&markdown)
+                        .into_iter()
+                        .filter_map(|image| {
+                            normalised(&format!("{}/{}", docs_path, beside(doc, image)))
+                        })
+                        .filter(|image| !image.starts_with(&docs_dir)),
+                );
+            }
+        }
+    }
+    directives.extend(
+        docs_paths
+            .iter()
+            .chain(&files)
+            .map(|path| format!("cargo:rerun-if-changed={}", path)),
+    );
+    directives.extend(
+        vars.iter()
+            .map(|name| format!("cargo:rerun-if-env-changed={}", name)),
+    );
+    Ok(directives)
+}
+
+/// A `/`-separated path without `.` or `..` segments, `None` if it leaves the manifest's directory
+fn normalised(path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+#[cfg(test)]
+#[path = "tests/build.rs"]
+mod build_tests;
@@ -0,0 +1,167 @@
+//! cfg predicates, like the `all(doc, not(test))` a `cfg-attr` setting can gate docs behind
+//!
+//! The setting, and the `cfg_attr = "..."` macro argument, are strings holding a predicate as it
+//! would be written in `#[cfg(...)]`. They are parsed up front, so a malformed one is reported
+//! where it was given rather than as a confusing error in the generated `#[cfg_attr]`.
+
+use proc_macro2::{Delimiter, Literal, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use std::fmt;
+use std::str::FromStr;
+
+/// A predicate, as in `#[cfg(...)]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgPredicate {
+    /// A bare option, like `doc` or `docsrs`
+    Option(String),
+    /// An option with a value, like `feature = "serde"`
+    KeyValue(String, String),
+    /// `all(...)`, which holds if every one of its predicates does
+    All(Vec<CfgPredicate>),
+    /// `any(...)`, which holds if one of its predicates does
+    Any(Vec<CfgPredicate>),
+    /// `not(...)`, which holds if its predicate doesn't
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    /// Parses a predicate from its tokens, which must be exactly one predicate
+    pub fn parse(tokens: TokenStream) -> Result<Self, String> {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut predicates = parse_list(&tokens)?;
+        match predicates.len() {
+            1 => Ok(predicates.remove(0)),
+            0 => Err("expected a cfg predicate, like `doc` or `all(doc, not(test))`".to_string()),
+            _ => {
+                Err("expected one cfg predicate, combine them with `all(...)` or `any(...)`".into())
+            }
+        }
+    }
+}
+
+impl FromStr for CfgPredicate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let tokens = TokenStream::from_str(s).map_err(|_| {
+            format!(
+                "`{}` isn't a cfg predicate, its brackets or quotes don't match",
+                s
+            )
+        })?;
+        CfgPredicate::parse(tokens).map_err(|e| format!("`{}` isn't a cfg predicate: {}", s, e))
+    }
+}
+
+/// Parses comma-separated predicates, allowing a trailing comma as rustc does
+fn parse_list(tokens: &[TokenTree]) -> Result<Vec<CfgPredicate>, String> {
+    let mut predicates = Vec::new();
+    let mut rest = tokens;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+            .unwrap_or(rest.len());
+        predicates.push(parse_one(&rest[..end])?);
+        rest = rest.get(end + 1..).unwrap_or_default();
+    }
+    Ok(predicates)
+}
+
+fn parse_one(tokens: &[TokenTree]) -> Result<CfgPredicate, String> {
+    match tokens {
+        [TokenTree::Ident(name)] => Ok(CfgPredicate::Option(name.to_string())),
+        [TokenTree::Ident(key), TokenTree::Punct(eq), value] if eq.as_char() == '=' => {
+            match plain_string(value) {
+                Some(value) => Ok(CfgPredicate::KeyValue(key.to_string(), value)),
+                None => Err(format!(
+                    "expected a string after `{} =`, found `{}`",
+                    key, value
+                )),
+            }
+        }
+        [TokenTree::Ident(name), TokenTree::Group(args)]
+            if args.delimiter() == Delimiter::Parenthesis =>
+        {
+            let args: Vec<TokenTree> = args.stream().into_iter().collect();
+            let mut predicates = parse_list(&args)?;
+            match name.to_string().as_str() {
+                "all" => Ok(CfgPredicate::All(predicates)),
+                "any" => Ok(CfgPredicate::Any(predicates)),
+                "not" if predicates.len() == 1 => {
+                    Ok(CfgPredicate::Not(Box::new(predicates.remove(0))))
+                }
+                "not" => Err("`not(...)` takes exactly one predicate".to_string()),
+                other => Err(format!(
+                    "expected `all`, `any` or `not`, found `{}(...)`",
+                    other
+                )),
+            }
+        }
+        [] => Err("expected a cfg predicate between commas".to_string()),
+        [first, rest @ ..] => {
+            // After an option's name, it's what follows the name that's out of place
+            let found = match first {
+                TokenTree::Ident(_) => rest.first().unwrap_or(first),
+                _ => first,
+            };
+            Err(format!(
+                "expected a cfg option, like `doc` or `feature = \"serde\"`, found `{}`",
+                found
+            ))
+        }
+    }
+}
+
+/// The value of a `"..."` literal without escapes, which is all an option's value needs
+fn plain_string(token: &TokenTree) -> Option<String> {
+    let TokenTree::Literal(literal) = token else {
+        return None;
+    };
+    let text = literal.to_string();
+    let value = text.strip_prefix('"')?.strip_suffix('"')?;
+    (!value.contains(['\\', '"'])).then(|| value.to_string())
+}
+
+/// Writes the predicate as it would be written by hand, like `all(doc, not(test))`
+impl fmt::Display for CfgPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, predicates: &[CfgPredicate]| {
+            write!(f, "{}(", name)?;
+            for (i, predicate) in predicates.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", predicate)?;
+            }
+            write!(f, ")")
+        };
+        match self {
+            CfgPredicate::Option(name) => write!(f, "{}", name),
+            CfgPredicate::KeyValue(key, value) => write!(f, "{} = \"{}\"", key, value),
+            CfgPredicate::All(predicates) => list(f, "all", predicates),
+            CfgPredicate::Any(predicates) => list(f, "any", predicates),
+            CfgPredicate::Not(predicate) => write!(f, "not({})", predicate),
+        }
+    }
+}
+
+impl ToTokens for CfgPredicate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = |name: &str| proc_macro2::Ident::new(name, proc_macro2::Span::call_site());
+        tokens.extend(match self {
+            CfgPredicate::Option(name) => ident(name).into_token_stream(),
+            CfgPredicate::KeyValue(key, value) => {
+                let (key, value) = (ident(key), Literal::string(value));
+                quote! { #key = #value }
+            }
+            CfgPredicate::All(predicates) => quote! { all(#(#predicates),*) },
+            CfgPredicate::Any(predicates) => quote! { any(#(#predicates),*) },
+            CfgPredicate::Not(predicate) => quote! { not(#predicate) },
+        });
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/cfg.rs"]
+mod cfg_tests;
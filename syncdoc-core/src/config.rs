@@ -1,52 +1,300 @@
+use crate::cfg::CfgPredicate;
 use crate::path_utils::find_manifest_dir;
 use crate::syncdoc_debug;
-use ropey::Rope;
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use textum::{Boundary, BoundaryMode, Snippet, Target};
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+/// The keys `[package.metadata.syncdoc]` can have
+const KEYS: &[&str] = &[
+    "docs-path",
+    "cfg-attr",
+    "missing",
+    "merge",
+    "strict",
+    "layout",
+    "strip-title",
+    "admonitions",
+    "admonition-template",
+    "embed-images",
+    "max-image-size",
+    "rewrite-links",
+    "locales",
+    "default-locale",
+];
+
+/// The settings in a crate's `[package.metadata.syncdoc]`, each at its default if not set
+///
+/// The macros and the migrator both read the config through this, so it means the same to
+/// them. It can be written in any form TOML allows, such as an inline table or dotted keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncdocConfig {
+    /// Where the doc files are, relative to the manifest, with `{locale}` for the locale
+    pub docs_path: Option<String>,
+    /// The cfg predicate docs are gated behind
+    pub cfg_attr: Option<CfgPredicate>,
+    pub missing: MissingDocs,
+    pub merge: MergeDocs,
+    pub strict: bool,
+    pub layout: DocLayout,
+    pub strip_title: bool,
+    pub admonitions: Admonitions,
+    /// The most bytes an image can have to be embedded, `None` when images aren't embedded
+    pub embed_images: Option<usize>,
+    /// Whether links between doc files become intra-doc links to the items they document
+    pub rewrite_links: bool,
+    pub locales: Option<Locales>,
+}
 
-/// Get a specified attribute from the current crate's Cargo.toml, relative to the source file
-fn get_attribute_from_cargo_toml(
-    cargo_toml_path: &str,
-    attribute: &str,
-) -> Result<Option<String>, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(cargo_toml_path)?;
-    let rope = Rope::from_str(&content);
+/// A mistake in a Cargo.toml's syncdoc config, and where it is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /// The Cargo.toml the mistake is in
+    pub path: PathBuf,
+    /// The line the mistake is on, from 1
+    pub line: usize,
+    /// The column the mistake starts at, in characters from 1
+    pub column: usize,
+    pub message: String,
+}
 
-    // Try to find the section text
-    let section_text = if let Ok(resolution) = (Snippet::Between {
-        start: Boundary::new(
-            Target::Literal("[package.metadata.syncdoc]".to_string()),
-            BoundaryMode::Exclude,
-        ),
-        end: Boundary::new(Target::Literal("[".to_string()), BoundaryMode::Exclude),
-    })
-    .resolve(&rope)
-    {
-        rope.slice(resolution.start..resolution.end).to_string()
-    } else {
-        let snippet = Snippet::From(Boundary::new(
-            Target::Literal("[package.metadata.syncdoc]".to_string()),
-            BoundaryMode::Exclude,
-        ));
-        match snippet.resolve(&rope) {
-            Ok(resolution) => rope.slice(resolution.start..resolution.end).to_string(),
-            Err(_) => return Ok(None), // No syncdoc section, return None
+impl ConfigError {
+    fn new(path: &Path, content: &str, span: Range<usize>, message: String) -> Self {
+        let before = &content[..span.start.min(content.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ConfigError {
+            path: path.to_path_buf(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message,
         }
-    };
+    }
+}
 
-    // Parse the specified attribute's value
-    for line in section_text.lines() {
-        let line = line.trim();
-        if line.starts_with(attribute) {
-            if let Some(value) = line.split('=').nth(1) {
-                let cleaned = value.trim().trim_matches('"').to_string();
-                return Ok(Some(cleaned));
-            }
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A mistake in the config, before it's placed in the file
+type Mistake = (Range<usize>, String);
+
+impl SyncdocConfig {
+    /// Reads the config from a Cargo.toml's content, `None` if it has no syncdoc section
+    ///
+    /// `manifest_path` is only for saying where a mistake is.
+    pub fn parse(content: &str, manifest_path: &Path) -> Result<Option<Self>, ConfigError> {
+        let locate =
+            |(span, message): Mistake| ConfigError::new(manifest_path, content, span, message);
+        let document = DeTable::parse(content)
+            .map_err(|e| locate((e.span().unwrap_or_default(), e.message().to_string())))?;
+        let Some(section) = syncdoc_section(document.get_ref()).map_err(locate)? else {
+            return Ok(None);
+        };
+        Self::from_section(section).map(Some).map_err(locate)
+    }
+
+    /// Reads the config from the Cargo.toml at `manifest_path`, `None` if it has no syncdoc section
+    pub fn read(manifest_path: &Path) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(manifest_path)?;
+        Ok(Self::parse(&content, manifest_path)?)
+    }
+
+    /// Reads the config of the crate a source file is in, relative to the current directory
+    pub fn load(source_file: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let manifest_dir = manifest_dir_of(source_file)?;
+        Ok(Self::read(&manifest_dir.join("Cargo.toml"))?.unwrap_or_default())
+    }
+
+    fn from_section(section: &DeTable<'_>) -> Result<Self, Mistake> {
+        // The first unknown key in the file, rather than in the table's order
+        if let Some(key) = section
+            .keys()
+            .filter(|key| !KEYS.contains(&key.get_ref().as_ref()))
+            .min_by_key(|key| key.span().start)
+        {
+            return Err((
+                key.span(),
+                format!(
+                    "unknown key `{}` in [package.metadata.syncdoc], expected one of {}",
+                    key.get_ref(),
+                    KEYS.join(", ")
+                ),
+            ));
         }
+
+        let admonitions = match get_str(section, "admonition-template")? {
+            Some((template, _)) => Admonitions::Template(template.to_string()),
+            None => get_choice(section, "admonitions")?,
+        };
+        let embed_images = match get_bool(section, "embed-images")? {
+            Some(true) => {
+                Some(get_size(section, "max-image-size")?.unwrap_or(DEFAULT_MAX_IMAGE_SIZE))
+            }
+            _ => None,
+        };
+
+        Ok(SyncdocConfig {
+            docs_path: get_str(section, "docs-path")?.map(|(path, _)| path.to_string()),
+            cfg_attr: get_cfg_predicate(section, "cfg-attr")?,
+            missing: get_choice(section, "missing")?,
+            merge: get_choice(section, "merge")?,
+            strict: get_bool(section, "strict")?.unwrap_or(false),
+            layout: get_choice(section, "layout")?,
+            strip_title: get_bool(section, "strip-title")?.unwrap_or(false),
+            admonitions,
+            embed_images,
+            rewrite_links: get_bool(section, "rewrite-links")?.unwrap_or(false),
+            locales: get_locales_setting(section)?,
+        })
+    }
+}
+
+/// The `[package.metadata.syncdoc]` table, however it was written, if there is one
+fn syncdoc_section<'a, 'i>(document: &'a DeTable<'i>) -> Result<Option<&'a DeTable<'i>>, Mistake> {
+    let metadata = document
+        .get("package")
+        .and_then(|package| package.get_ref().as_table())
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get_ref().as_table());
+    let Some(section) = metadata.and_then(|metadata| metadata.get("syncdoc")) else {
+        return Ok(None);
+    };
+    match section.get_ref().as_table() {
+        Some(table) => Ok(Some(table)),
+        None => Err(wrong_type("package.metadata.syncdoc", "a table", section)),
+    }
+}
+
+fn wrong_type(key: &str, expected: &str, value: &Spanned<DeValue<'_>>) -> Mistake {
+    let found = value.get_ref().type_str();
+    let article = if found.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    };
+    (
+        value.span(),
+        format!(
+            "{} should be {}, found {} {}",
+            key, expected, article, found
+        ),
+    )
+}
+
+/// A string setting, with where its value is
+fn get_str<'a>(
+    section: &'a DeTable<'_>,
+    key: &str,
+) -> Result<Option<(&'a str, Range<usize>)>, Mistake> {
+    let Some(value) = section.get(key) else {
+        return Ok(None);
+    };
+    match value.get_ref().as_str() {
+        Some(s) => Ok(Some((s, value.span()))),
+        None => Err(wrong_type(key, "a string", value)),
     }
+}
 
-    Ok(None) // Attribute not found, return None
+/// A setting holding a cfg predicate, as it would be written in `#[cfg(...)]`
+fn get_cfg_predicate(section: &DeTable<'_>, key: &str) -> Result<Option<CfgPredicate>, Mistake> {
+    get_str(section, key)?
+        .map(|(predicate, span)| {
+            predicate
+                .parse()
+                .map_err(|e| (span, format!("invalid {}, {}", key, e)))
+        })
+        .transpose()
+}
+
+fn get_bool(section: &DeTable<'_>, key: &str) -> Result<Option<bool>, Mistake> {
+    let Some(value) = section.get(key) else {
+        return Ok(None);
+    };
+    match value.get_ref().as_bool() {
+        Some(b) => Ok(Some(b)),
+        None => Err(wrong_type(key, "true or false", value)),
+    }
+}
+
+/// A setting with a fixed set of string values, parsed by its `FromStr`
+fn get_choice<T>(section: &DeTable<'_>, key: &str) -> Result<T, Mistake>
+where
+    T: Default + core::str::FromStr<Err = String>,
+{
+    match get_str(section, key)? {
+        Some((value, span)) => value.parse().map_err(|e| (span, e)),
+        None => Ok(T::default()),
+    }
+}
+
+/// A number of bytes, which TOML can write with underscores like `50_000`
+fn get_size(section: &DeTable<'_>, key: &str) -> Result<Option<usize>, Mistake> {
+    let Some(value) = section.get(key) else {
+        return Ok(None);
+    };
+    let Some(integer) = value.get_ref().as_integer() else {
+        return Err(wrong_type(key, "a number of bytes", value));
+    };
+    usize::from_str_radix(integer.as_str(), integer.radix())
+        .map(Some)
+        .map_err(|_| {
+            (
+                value.span(),
+                format!("invalid {} = {}, expected a number of bytes", key, integer),
+            )
+        })
+}
+
+fn get_locales_setting(section: &DeTable<'_>) -> Result<Option<Locales>, Mistake> {
+    let default = get_str(section, "default-locale")?;
+    let Some(value) = section.get("locales") else {
+        return match default {
+            Some((_, span)) => Err((span, "default-locale is set, but locales isn't".to_string())),
+            None => Ok(None),
+        };
+    };
+    let expected = "a list of locales like [\"en\", \"ja\"]";
+    let Some(list) = value.get_ref().as_array() else {
+        return Err(wrong_type("locales", expected, value));
+    };
+    let all = list
+        .iter()
+        .map(|locale| match locale.get_ref().as_str() {
+            Some(locale) => Ok(locale.to_string()),
+            None => Err(wrong_type("each of locales", "a string", locale)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let default = match default {
+        Some((default, _)) if all.iter().any(|locale| locale == default) => default.to_string(),
+        Some((default, span)) => {
+            return Err((
+                span,
+                format!(
+                    "default-locale = \"{}\" isn't one of the locales, {}",
+                    default,
+                    all.join(", ")
+                ),
+            ))
+        }
+        None => match all.first() {
+            Some(first) => first.clone(),
+            None => return Err((value.span(), "locales is empty".to_string())),
+        },
+    };
+    Ok(Some(Locales { all, default }))
 }
 
 /// Resolve a source file path to an absolute path, handling both absolute and relative paths
@@ -63,21 +311,278 @@ fn resolve_source_path(source_file: &str) -> Result<PathBuf, Box<dyn std::error:
     Ok(source_path)
 }
 
-/// Get the cfg-attr from the current crate's Cargo.toml, relative to the source file
-pub fn get_cfg_attr(source_file: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+/// The directory of the Cargo.toml for the crate a source file is in
+fn manifest_dir_of(source_file: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let source_path = resolve_source_path(source_file)?;
     let source_dir = source_path
         .parent()
         .ok_or("Source file has no parent directory")?;
 
-    let manifest_dir = find_manifest_dir(source_dir).ok_or("Could not find Cargo.toml")?;
+    Ok(find_manifest_dir(source_dir).ok_or("Could not find Cargo.toml")?)
+}
 
-    let cargo_toml_path = manifest_dir.join("Cargo.toml");
-    get_attribute_from_cargo_toml(cargo_toml_path.to_str().unwrap(), "cfg-attr")
+/// Get the cfg-attr from the current crate's Cargo.toml, relative to the source file
+pub fn get_cfg_attr(source_file: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(SyncdocConfig::load(source_file)?
+        .cfg_attr
+        .map(|cfg_attr| cfg_attr.to_string()))
+}
+
+/// What to do about an item whose markdown file does not exist
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingDocs {
+    /// Leave the item undocumented
+    Ignore,
+    /// Leave the item undocumented and emit a warning pointing at it
+    Warn,
+    /// Fail the build, as `include_str!` does
+    #[default]
+    Error,
+}
+
+impl core::str::FromStr for MissingDocs {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ignore" => Ok(MissingDocs::Ignore),
+            "warn" => Ok(MissingDocs::Warn),
+            "error" => Ok(MissingDocs::Error),
+            other => Err(format!(
+                "invalid missing = \"{}\", expected \"ignore\", \"warn\" or \"error\"",
+                other
+            )),
+        }
+    }
+}
+
+/// What to do about an item that has inline `///` docs as well as a markdown file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeDocs {
+    /// Keep both, the file's docs first
+    #[default]
+    Concat,
+    /// Drop the inline docs
+    PreferFile,
+    /// Keep the inline docs and leave out the file
+    PreferInline,
+    /// Fail the build, pointing at the inline docs
+    Error,
+}
+
+impl core::str::FromStr for MergeDocs {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "concat" => Ok(MergeDocs::Concat),
+            "prefer-file" => Ok(MergeDocs::PreferFile),
+            "prefer-inline" => Ok(MergeDocs::PreferInline),
+            "error" => Ok(MergeDocs::Error),
+            other => Err(format!(
+                "invalid merge = \"{}\", expected \"concat\", \"prefer-file\", \"prefer-inline\" or \"error\"",
+                other
+            )),
+        }
+    }
+}
+
+/// How GitHub alerts like `> [!WARNING]` in doc files are shown in rustdoc
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Admonitions {
+    /// Leave them as they are, which rustdoc shows as a plain blockquote
+    #[default]
+    Keep,
+    /// Warnings and cautions become rustdoc's warning block, others a blockquote with a title
+    Rustdoc,
+    /// Each becomes the HTML template, with `{kind}`, `{title}` and `{body}` filled in
+    Template(String),
+}
+
+impl core::str::FromStr for Admonitions {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keep" => Ok(Admonitions::Keep),
+            "rustdoc" => Ok(Admonitions::Rustdoc),
+            other => Err(format!(
+                "invalid admonitions = \"{}\", expected \"keep\" or \"rustdoc\"",
+                other
+            )),
+        }
+    }
+}
+
+/// How a module's docs are split across markdown files
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DocLayout {
+    /// A file for every item, such as `docs/types/Config/port.md`
+    #[default]
+    PerItem,
+    /// A file for every module, with a heading for each item (see [`crate::sections`])
+    Sections,
+}
+
+impl core::str::FromStr for DocLayout {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "per-item" => Ok(DocLayout::PerItem),
+            "sections" => Ok(DocLayout::Sections),
+            other => Err(format!(
+                "invalid layout = \"{}\", expected \"per-item\" or \"sections\"",
+                other
+            )),
+        }
+    }
+}
+
+impl core::fmt::Display for DocLayout {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DocLayout::PerItem => f.write_str("per-item"),
+            DocLayout::Sections => f.write_str("sections"),
+        }
+    }
+}
+
+/// The most bytes an image can have to be embedded, unless `max-image-size` says otherwise
+pub const DEFAULT_MAX_IMAGE_SIZE: usize = 256 * 1024;
+
+/// Get a string from the `[package]` table of a Cargo.toml, such as its `version`
+fn get_package_value_from_cargo_toml(
+    cargo_toml_path: &str,
+    key: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(cargo_toml_path)?;
+    let document = DeTable::parse(&content).map_err(|e| {
+        ConfigError::new(
+            Path::new(cargo_toml_path),
+            &content,
+            e.span().unwrap_or_default(),
+            e.message().to_string(),
+        )
+    })?;
+    // Only a plain string, not `version.workspace = true`
+    Ok(document
+        .get_ref()
+        .get("package")
+        .and_then(|package| package.get_ref().as_table())
+        .and_then(|package| package.get(key))
+        .and_then(|value| value.get_ref().as_str())
+        .map(str::to_string))
+}
+
+/// Get a string from the `[package]` table of the current crate's Cargo.toml, like its `name`
+///
+/// A value the manifest inherits from the workspace comes from the `CARGO_PKG_*` variable
+/// Cargo sets while building the crate instead.
+pub fn get_package_value(
+    source_file: &str,
+    key: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let cargo_toml_path = manifest_dir_of(source_file)?.join("Cargo.toml");
+    match get_package_value_from_cargo_toml(cargo_toml_path.to_str().unwrap(), key)? {
+        Some(value) => Ok(Some(value)),
+        None => Ok(std::env::var(format!("CARGO_PKG_{}", key.to_uppercase())).ok()),
+    }
+}
+
+/// The cfg option that picks the locale docs are built in, which the build helper sets
+pub const LOCALE_CFG: &str = "syncdoc_locale";
+
+/// The languages a crate's docs are written in, for a `docs-path` with `{locale}` in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locales {
+    /// Every locale, in the order the config lists them
+    pub all: Vec<String>,
+    /// The locale docs fall back to when they aren't translated, the first unless set
+    pub default: String,
+}
+
+impl Locales {
+    /// The locales docs are translated into, that is all but the default
+    pub fn translations(&self) -> impl Iterator<Item = &str> {
+        self.all
+            .iter()
+            .map(String::as_str)
+            .filter(|locale| *locale != self.default)
+    }
+
+    /// The predicate docs in `locale` are gated behind
+    ///
+    /// The default locale's docs are built unless another locale is picked, so they need no cfg.
+    pub fn cfg(&self, locale: &str) -> CfgPredicate {
+        let picks =
+            |locale: &str| CfgPredicate::KeyValue(LOCALE_CFG.to_string(), locale.to_string());
+        if locale == self.default {
+            CfgPredicate::Not(Box::new(CfgPredicate::Any(
+                self.translations().map(picks).collect(),
+            )))
+        } else {
+            picks(locale)
+        }
+    }
+}
+
+impl SyncdocConfig {
+    /// The docs-path relative to the source file, in `locale` or else the default one
+    pub fn docs_path_from(
+        &self,
+        source_file: &str,
+        locale: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let docs_path = self.docs_path.as_deref().ok_or("docs-path not found")?;
+        syncdoc_debug!("  docs_path from toml: {}", docs_path);
+        let docs_path = if docs_path.contains("{locale}") {
+            let locales = self
+                .locales
+                .as_ref()
+                .ok_or("docs-path has a {locale} in it, but no locales are set")?;
+            docs_path.replace("{locale}", locale.unwrap_or(&locales.default))
+        } else {
+            docs_path.to_string()
+        };
+        resolve_docs_path(source_file, &docs_path)
+    }
+
+    /// Each translation's docs-path relative to the source file, by locale
+    ///
+    /// Empty when the docs-path has no `{locale}`, as then there is only one copy of the docs.
+    pub fn translated_docs_paths_from(
+        &self,
+        source_file: &str,
+    ) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+        let (Some(locales), Some(docs_path)) = (&self.locales, &self.docs_path) else {
+            return Ok(Vec::new());
+        };
+        if !docs_path.contains("{locale}") {
+            return Ok(Vec::new());
+        }
+        locales
+            .translations()
+            .map(|locale| {
+                let path = self.docs_path_from(source_file, Some(locale))?;
+                Ok((locale.to_string(), path))
+            })
+            .collect()
+    }
 }
 
 /// Get the docs-path from the current crate's Cargo.toml, relative to the source file
+///
+/// A `{locale}` in it is the default locale.
 pub fn get_docs_path(source_file: &str) -> Result<String, Box<dyn std::error::Error>> {
+    SyncdocConfig::load(source_file)?.docs_path_from(source_file, None)
+}
+
+/// `docs_path`, relative to the manifest, made relative to the source file
+fn resolve_docs_path(
+    source_file: &str,
+    docs_path: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
     syncdoc_debug!("get_docs_path called:");
     syncdoc_debug!("  source_file: {}", source_file);
 
@@ -90,11 +595,6 @@ pub fn get_docs_path(source_file: &str) -> Result<String, Box<dyn std::error::Er
     let manifest_dir = find_manifest_dir(source_dir).ok_or("Could not find Cargo.toml")?;
     syncdoc_debug!("  manifest_dir: {}", manifest_dir.display());
 
-    let cargo_toml_path = manifest_dir.join("Cargo.toml");
-    let docs_path = get_attribute_from_cargo_toml(cargo_toml_path.to_str().unwrap(), "docs-path")?
-        .ok_or("docs-path not found")?;
-    syncdoc_debug!("  docs_path from toml: {}", docs_path);
-
     let manifest_path = manifest_dir.canonicalize()?;
     syncdoc_debug!("  manifest_path (canonical): {}", manifest_path.display());
 
@@ -124,12 +624,379 @@ pub fn get_docs_path(source_file: &str) -> Result<String, Box<dyn std::error::Er
         result.push("..");
     }
 
-    result.push(&docs_path);
+    result.push(docs_path);
     let result_str = result.to_string_lossy().to_string();
     syncdoc_debug!("  final result: {}", result_str);
     Ok(result_str)
 }
 
+#[cfg(test)]
+mod syncdoc_config_tests {
+    use super::*;
+
+    fn config_for(content: &str) -> Result<Option<SyncdocConfig>, ConfigError> {
+        SyncdocConfig::parse(content, Path::new("Cargo.toml"))
+    }
+
+    #[test]
+    fn test_config_as_an_inline_table() {
+        let content = r#"
+[package]
+name = "myproject"
+metadata.syncdoc = { docs-path = "docs", layout = "sections" }
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert_eq!(config.docs_path.as_deref(), Some("docs"));
+        assert_eq!(config.layout, DocLayout::Sections);
+    }
+
+    #[test]
+    fn test_config_with_dotted_keys() {
+        let content = r#"
+[package.metadata]
+syncdoc.docs-path = "docs"
+syncdoc.strict = true
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert_eq!(config.docs_path.as_deref(), Some("docs"));
+        assert!(config.strict);
+    }
+
+    #[test]
+    fn test_config_with_comments_and_equals_signs() {
+        let content = r#"
+[package.metadata.syncdoc] # the docs live outside src
+docs-path = "docs/a=b" # not "docs/a"
+# missing = "warn"
+cfg-attr = 'feature = "docs"'
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert_eq!(config.docs_path.as_deref(), Some("docs/a=b"));
+        assert_eq!(config.missing, MissingDocs::Error);
+        assert_eq!(
+            config.cfg_attr.map(|cfg| cfg.to_string()).as_deref(),
+            Some("feature = \"docs\"")
+        );
+    }
+
+    #[test]
+    fn test_no_config_section() {
+        let content = r#"
+[package]
+name = "myproject"
+
+[package.metadata.docs.rs]
+all-features = true
+"#;
+        assert_eq!(config_for(content).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unknown_key_is_located_and_lists_the_keys() {
+        let content = r#"
+[package.metadata.syncdoc]
+docs-path = "docs"
+layuot = "sections"
+docs = "docs"
+"#;
+        let error = config_for(content).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(
+            error.to_string(),
+            "Cargo.toml:4:1: unknown key `layuot` in [package.metadata.syncdoc], expected one of \
+            docs-path, cfg-attr, missing, merge, strict, layout, strip-title, admonitions, \
+            admonition-template, embed-images, max-image-size, rewrite-links, locales, default-locale"
+        );
+    }
+
+    #[test]
+    fn test_wrong_types_are_located_at_the_value() {
+        let content = r#"
+[package.metadata.syncdoc]
+docs-path = ["docs"]
+"#;
+        assert_eq!(
+            config_for(content).unwrap_err().to_string(),
+            "Cargo.toml:3:13: docs-path should be a string, found an array"
+        );
+
+        let content = r#"
+[package.metadata.syncdoc]
+layout = "flat"
+"#;
+        assert_eq!(
+            config_for(content).unwrap_err().to_string(),
+            "Cargo.toml:3:10: invalid layout = \"flat\", expected \"per-item\" or \"sections\""
+        );
+
+        let content = r#"
+[package.metadata]
+syncdoc = "docs"
+"#;
+        assert_eq!(
+            config_for(content).unwrap_err().to_string(),
+            "Cargo.toml:3:11: package.metadata.syncdoc should be a table, found a string"
+        );
+    }
+
+    #[test]
+    fn test_invalid_toml_is_located() {
+        let content = "[package.metadata.syncdoc]\ndocs-path = \"docs\"\ndocs-path = \"api\"\n";
+        let error = config_for(content).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(error.message.contains("duplicate key"), "{}", error);
+    }
+
+    fn setting<T>(content: &str, get: impl FnOnce(SyncdocConfig) -> T) -> Result<T, String> {
+        let config = config_for(content).map_err(|e| e.message)?;
+        Ok(get(config.unwrap_or_default()))
+    }
+
+    #[test]
+    fn test_settings_default_when_not_set() {
+        let content = r#"
+[package.metadata.syncdoc]
+docs-path = "docs"
+
+[lints.rust]
+missing_docs = "deny"
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert_eq!(config.cfg_attr, None);
+        assert_eq!(config.missing, MissingDocs::Error);
+        assert_eq!(config.merge, MergeDocs::Concat);
+        assert!(!config.strict);
+        assert_eq!(config.layout, DocLayout::PerItem);
+        assert!(!config.strip_title);
+        assert_eq!(config.admonitions, Admonitions::Keep);
+        assert_eq!(config.embed_images, None);
+        assert_eq!(config.locales, None);
+    }
+
+    #[test]
+    fn test_cfg_attr_is_a_predicate() {
+        let cfg_attr = |content| setting(content, |config| config.cfg_attr);
+        let content = r#"
+[package.metadata.syncdoc]
+cfg-attr = "doc"
+"#;
+        assert_eq!(
+            cfg_attr(content).unwrap(),
+            Some(CfgPredicate::Option("doc".to_string()))
+        );
+
+        let content = r#"
+[package.metadata.syncdoc]
+cfg-attr = "all(doc,not(test))"
+"#;
+        let predicate = cfg_attr(content).unwrap().unwrap();
+        assert_eq!(predicate.to_string(), "all(doc, not(test))");
+    }
+
+    #[test]
+    fn test_invalid_cfg_attr_is_located() {
+        let content = r#"
+[package.metadata.syncdoc]
+docs-path = "docs"
+cfg-attr = "a-custom-attr"
+"#;
+        let error = config_for(content).unwrap_err();
+        assert_eq!((error.line, error.column), (4, 12));
+        assert!(
+            error
+                .message
+                .starts_with("invalid cfg-attr, `a-custom-attr` isn't a cfg predicate: "),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_missing_and_merge_settings() {
+        let content = r#"
+[package.metadata.syncdoc]
+missing = "warn"
+merge = "prefer-file"
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert_eq!(config.missing, MissingDocs::Warn);
+        assert_eq!(config.merge, MergeDocs::PreferFile);
+
+        let content = r#"
+[package.metadata.syncdoc]
+missing = "sometimes"
+"#;
+        assert_eq!(
+            setting(content, |config| config.missing).unwrap_err(),
+            "invalid missing = \"sometimes\", expected \"ignore\", \"warn\" or \"error\""
+        );
+
+        let content = r#"
+[package.metadata.syncdoc]
+merge = "prefer-both"
+"#;
+        assert_eq!(
+            setting(content, |config| config.merge).unwrap_err(),
+            "invalid merge = \"prefer-both\", expected \"concat\", \"prefer-file\", \"prefer-inline\" or \"error\""
+        );
+    }
+
+    #[test]
+    fn test_boolean_settings() {
+        let content = r#"
+[package.metadata.syncdoc]
+strict = true
+strip-title = true
+rewrite-links = true
+"#;
+        let config = config_for(content).unwrap().unwrap();
+        assert!(config.strict);
+        assert!(config.strip_title);
+        assert!(config.rewrite_links);
+
+        let content = r#"
+[package.metadata.syncdoc]
+strict = "yes"
+"#;
+        assert_eq!(
+            setting(content, |config| config.strict).unwrap_err(),
+            "strict should be true or false, found a string"
+        );
+
+        let content = r#"
+[package.metadata.syncdoc]
+strip-title = 1
+"#;
+        assert_eq!(
+            setting(content, |config| config.strip_title).unwrap_err(),
+            "strip-title should be true or false, found an integer"
+        );
+    }
+
+    #[test]
+    fn test_layout_setting() {
+        let content = r#"
+[package.metadata.syncdoc]
+layout = "sections"
+"#;
+        assert_eq!(
+            setting(content, |config| config.layout).unwrap(),
+            DocLayout::Sections
+        );
+    }
+
+    #[test]
+    fn test_admonitions_settings() {
+        let admonitions = |content| setting(content, |config| config.admonitions);
+        let content = r#"
+[package.metadata.syncdoc]
+admonitions = "rustdoc"
+"#;
+        assert_eq!(admonitions(content).unwrap(), Admonitions::Rustdoc);
+
+        // The template takes precedence
+        let content = r#"
+[package.metadata.syncdoc]
+admonitions = "rustdoc"
+admonition-template = '<div class="alert {kind}">{body}</div>'
+"#;
+        assert_eq!(
+            admonitions(content).unwrap(),
+            Admonitions::Template("<div class=\"alert {kind}\">{body}</div>".to_string())
+        );
+
+        let content = r#"
+[package.metadata.syncdoc]
+admonitions = "github"
+"#;
+        assert_eq!(
+            admonitions(content).unwrap_err(),
+            "invalid admonitions = \"github\", expected \"keep\" or \"rustdoc\""
+        );
+    }
+
+    #[test]
+    fn test_embed_images_settings() {
+        let embed_images = |content| setting(content, |config| config.embed_images);
+        let content = r#"
+[package.metadata.syncdoc]
+max-image-size = 1000
+"#;
+        assert_eq!(embed_images(content).unwrap(), None);
+
+        let content = r#"
+[package.metadata.syncdoc]
+embed-images = true
+"#;
+        assert_eq!(embed_images(content).unwrap(), Some(DEFAULT_MAX_IMAGE_SIZE));
+
+        let content = r#"
+[package.metadata.syncdoc]
+embed-images = true
+max-image-size = 50_000
+"#;
+        assert_eq!(embed_images(content).unwrap(), Some(50_000));
+
+        let content = r#"
+[package.metadata.syncdoc]
+embed-images = true
+max-image-size = "1MB"
+"#;
+        assert_eq!(
+            embed_images(content).unwrap_err(),
+            "max-image-size should be a number of bytes, found a string"
+        );
+    }
+
+    #[test]
+    fn test_locales_default_to_the_first() {
+        let content = r#"
+[package.metadata.syncdoc]
+docs-path = "docs/{locale}"
+locales = ["en", "ja"]
+
+[dependencies]
+"#;
+        let locales = setting(content, |config| config.locales).unwrap().unwrap();
+        assert_eq!(locales.all, ["en", "ja"]);
+        assert_eq!(locales.default, "en");
+    }
+
+    #[test]
+    fn test_default_locale_must_be_listed() {
+        let locales = |content| setting(content, |config| config.locales);
+        let content = r#"
+[package.metadata.syncdoc]
+locales = ["en", "ja"]
+default-locale = "ja"
+"#;
+        assert_eq!(locales(content).unwrap().unwrap().default, "ja");
+
+        let content = r#"
+[package.metadata.syncdoc]
+locales = ["en", "ja"]
+default-locale = "fr"
+"#;
+        assert_eq!(
+            locales(content).unwrap_err(),
+            "default-locale = \"fr\" isn't one of the locales, en, ja"
+        );
+    }
+
+    #[test]
+    fn test_locales_are_picked_by_cfg() {
+        let locales = Locales {
+            all: vec!["en".into(), "ja".into(), "fr".into()],
+            default: "en".into(),
+        };
+        assert_eq!(
+            locales.cfg("en").to_string(),
+            "not(any(syncdoc_locale = \"ja\", syncdoc_locale = \"fr\"))"
+        );
+        assert_eq!(locales.cfg("ja").to_string(), "syncdoc_locale = \"ja\"");
+    }
+}
+
 #[cfg(test)]
 mod docs_path_tests {
     use super::*;
@@ -139,9 +1006,8 @@ mod docs_path_tests {
     fn get_docs_path_from_file(
         cargo_toml_path: &str,
     ) -> Result<String, Box<dyn std::error::Error>> {
-        let docs_path = get_attribute_from_cargo_toml(cargo_toml_path, "docs-path")?
-            .ok_or("docs-path not found")?;
-        Ok(docs_path)
+        let config = SyncdocConfig::read(Path::new(cargo_toml_path))?.unwrap_or_default();
+        Ok(config.docs_path.ok_or("docs-path not found")?)
     }
 
     #[test]
@@ -205,8 +1071,9 @@ docs-path = docs
         write!(temp, "{}", content).unwrap();
         temp.flush().unwrap();
 
-        let result = get_docs_path_from_file(temp.path().to_str().unwrap()).unwrap();
-        assert_eq!(result, "docs");
+        // Not a TOML string, so Cargo would reject the manifest too
+        let result = get_docs_path_from_file(temp.path().to_str().unwrap());
+        assert!(result.unwrap_err().to_string().contains(":3:13: "));
     }
 
     #[test]
@@ -227,7 +1094,7 @@ name = "myproject"
     fn test_missing_docs_path_field() {
         let content = r#"
 [package.metadata.syncdoc]
-other-field = "value"
+layout = "sections"
 "#;
         let mut temp = NamedTempFile::new().unwrap();
         write!(temp, "{}", content).unwrap();
@@ -245,9 +1112,9 @@ other-field = "value"
     fn test_docs_path_with_multiple_fields() {
         let content = r#"
 [package.metadata.syncdoc]
-enable = true
+strict = true
 docs-path = "api-docs"
-output-format = "markdown"
+merge = "prefer-file"
 
 [dependencies]
 "#;
@@ -362,68 +1229,34 @@ docs-path = "documentation"
 }
 
 #[cfg(test)]
-mod cfg_attr_tests {
+mod package_value_tests {
     use super::*;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
-    fn get_cfg_attr_from_file(cargo_toml_path: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let cfg_attr = get_attribute_from_cargo_toml(cargo_toml_path, "cfg-attr")?
-            .ok_or("cfg-attr not found")?;
-        Ok(cfg_attr)
-    }
-
     #[test]
-    fn test_cfg_attr_not_set() {
+    fn test_package_value_only_from_package_table() {
         let content = r#"
 [package]
 name = "myproject"
+version = "1.4.0"
+edition.workspace = true
 
 [package.metadata.syncdoc]
-"#;
-        let mut temp = NamedTempFile::new().unwrap();
-        write!(temp, "{}", content).unwrap();
-        temp.flush().unwrap();
-
-        let result = get_cfg_attr_from_file(temp.path().to_str().unwrap());
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("cfg-attr not found"));
-    }
-
-    #[test]
-    fn test_cfg_attr_set_as_doc() {
-        let content = r#"
-[package]
-name = "myproject"
-
-[package.metadata.syncdoc]
-cfg-attr = "doc"
-"#;
-        let mut temp = NamedTempFile::new().unwrap();
-        write!(temp, "{}", content).unwrap();
-        temp.flush().unwrap();
-
-        let result = get_cfg_attr_from_file(temp.path().to_str().unwrap()).unwrap();
-        assert_eq!(result, "doc");
-    }
-
-    #[test]
-    fn test_cfg_attr_set_as_custom() {
-        let content = r#"
-[package]
-name = "myproject"
+docs-path = "docs"
 
-[package.metadata.syncdoc]
-cfg-attr = "a-custom-attr"
+[dependencies]
+version = "9.9.9"
 "#;
         let mut temp = NamedTempFile::new().unwrap();
         write!(temp, "{}", content).unwrap();
         temp.flush().unwrap();
 
-        let result = get_cfg_attr_from_file(temp.path().to_str().unwrap()).unwrap();
-        assert_eq!(result, "a-custom-attr");
+        let path = temp.path().to_str().unwrap();
+        let value = |key| get_package_value_from_cargo_toml(path, key).unwrap();
+        assert_eq!(value("name"), Some("myproject".to_string()));
+        assert_eq!(value("version"), Some("1.4.0".to_string()));
+        assert_eq!(value("edition"), None);
+        assert_eq!(value("docs-path"), None);
     }
 }
@@ -2,21 +2,40 @@
 
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::path::PathBuf;
 use unsynn::*;
 
+use crate::admonition::{has_alerts, render_alerts};
+use crate::cfg::CfgPredicate;
+use crate::config::{Admonitions, DocLayout, SyncdocConfig};
+use crate::features::{feature_doc_path, find_feature_docs};
+use crate::front_matter::{has_front_matter, FrontMatter};
+use crate::images::{embed_images, has_local_images};
+use crate::include::{has_includes, resolve_includes};
+use crate::links::{has_doc_links, rewrite_links, DocItems};
 use crate::parse::{SyncDocArg, SyncDocInner};
 use crate::path_utils::make_manifest_relative_path;
+use crate::sections::Sections;
+use crate::snippet::{has_snippets, resolve_snippets};
+use crate::template::{expand, has_placeholders, TemplateVars};
+use crate::title::strip_title;
 
 /// Injects a doc attribute without parsing the item structure
-pub fn omnidoc_impl(doc_path: String, cfg_attr: Option<String>, item: TokenStream) -> TokenStream {
+pub fn omnidoc_impl(
+    doc_path: String,
+    cfg_attr: Option<CfgPredicate>,
+    item: TokenStream,
+) -> TokenStream {
     // Get the call site's file path if there might be config we could use there
     let call_site = proc_macro2::Span::call_site();
-    let local_file = call_site.local_file().expect("Could not find local file");
-    let rel_doc_path = make_manifest_relative_path(&doc_path, &local_file);
-    if let Some(cfg_value) = cfg_attr {
-        let cfg_ident = proc_macro2::Ident::new(&cfg_value, proc_macro2::Span::call_site());
+    // Outside of a compiler-driven expansion there is no source file to be relative to
+    let rel_doc_path = match call_site.local_file() {
+        Some(local_file) => make_manifest_relative_path(&doc_path, &local_file),
+        None => doc_path,
+    };
+    if let Some(cfg) = cfg_attr {
         quote! {
-            #[cfg_attr(#cfg_ident, doc = include_str!(#rel_doc_path))]
+            #[cfg_attr(#cfg, doc = include_str!(#rel_doc_path))]
             #item
         }
     } else {
@@ -27,10 +46,203 @@ pub fn omnidoc_impl(doc_path: String, cfg_attr: Option<String>, item: TokenStrea
     }
 }
 
+/// Injects docs already read from a file, as a string literal
+pub(crate) fn literal_doc_impl(
+    doc: &str,
+    cfg_attr: Option<CfgPredicate>,
+    item: TokenStream,
+) -> TokenStream {
+    if let Some(cfg) = cfg_attr {
+        quote! {
+            #[cfg_attr(#cfg, doc = #doc)]
+            #item
+        }
+    } else {
+        quote! {
+            #[doc = #doc]
+            #item
+        }
+    }
+}
+
+/// Docs from the companions of the doc file at `doc_path`, each only with its feature on
+///
+/// They go after the item's own docs, as written. Under a `cfg_attr` setting the docs need that
+/// too. Like a missing doc file, a companion added later is only picked up on the next rebuild.
+pub(crate) fn feature_doc_attrs(doc_path: &str, cfg_attr: Option<&CfgPredicate>) -> TokenStream {
+    let Some(resolved) = resolve_doc_path(doc_path) else {
+        return TokenStream::new();
+    };
+    let mut output = TokenStream::new();
+    for (feature, _) in find_feature_docs(&resolved) {
+        let companion = feature_doc_path(doc_path, &feature);
+        let rel_path = match proc_macro2::Span::call_site().local_file() {
+            Some(local_file) => make_manifest_relative_path(&companion, &local_file),
+            None => companion,
+        };
+        output.extend(match cfg_attr {
+            Some(cfg) => quote! {
+                #[cfg_attr(all(#cfg, feature = #feature), doc = include_str!(#rel_path))]
+            },
+            None => quote! {
+                #[cfg_attr(feature = #feature, doc = include_str!(#rel_path))]
+            },
+        });
+    }
+    output
+}
+
+/// A tool attribute including `doc_path`, so the crate is rebuilt when the file changes
+///
+/// The attribute does nothing else, and unlike an unused `const` it's allowed on any item, even
+/// one in an impl or trait.
+pub(crate) fn track_doc_file(doc_path: &str) -> TokenStream {
+    let rel_doc_path = match proc_macro2::Span::call_site().local_file() {
+        Some(local_file) => make_manifest_relative_path(doc_path, &local_file),
+        None => doc_path.to_string(),
+    };
+    quote! {
+        #[rustfmt::skip = include_str!(#rel_doc_path)]
+    }
+}
+
+/// A tool attribute including the image at `path`, as [`track_doc_file`] does, if it's text
+///
+/// `None` for a binary image, which `include_str!` can't read; see [`track_binary_asset`].
+pub(crate) fn track_text_asset(path: &str) -> Option<TokenStream> {
+    let bytes = read_doc_bytes(path)?.ok()?;
+    std::str::from_utf8(&bytes).ok()?;
+    Some(track_doc_file(path))
+}
+
+/// An unused `include_bytes!` of an image, so the crate is rebuilt when it changes
+///
+/// An attribute's value has to expand to a string, which `include_bytes!` doesn't, so this is a
+/// `const` item, which can only go after an item outside of an impl or trait.
+pub(crate) fn track_binary_asset(path: &str) -> TokenStream {
+    let rel_path = match proc_macro2::Span::call_site().local_file() {
+        Some(local_file) => make_manifest_relative_path(path, &local_file),
+        None => path.to_string(),
+    };
+    quote! {
+        const _: &[u8] = include_bytes!(#rel_path);
+    }
+}
+
+/// A tool attribute reading `name`, so the crate is rebuilt when the variable changes
+///
+/// The variable must be set, as placeholders' variables are, since `env!` fails otherwise.
+pub(crate) fn track_env_var(name: &str) -> TokenStream {
+    quote! {
+        #[rustfmt::skip = env!(#name)]
+    }
+}
+
+/// The values for a doc file's placeholders, with the crate's from its manifest
+pub(crate) fn template_vars(item_name: String, item_path: String) -> TemplateVars {
+    let package_value = |key| {
+        proc_macro2::Span::call_site()
+            .local_file()
+            .and_then(|local_file| {
+                crate::config::get_package_value(&local_file.to_string_lossy(), key).ok()
+            })
+            .flatten()
+            .unwrap_or_default()
+    };
+    TemplateVars {
+        crate_name: package_value("name"),
+        crate_version: package_value("version"),
+        item_name,
+        item_path,
+    }
+}
+
+/// Whether a doc file has to be read and processed, rather than handed to `include_str!`
+///
+/// That is, whether it has front matter to leave out, or fragments, placeholders or snippets to
+/// fill in.
+pub(crate) fn needs_processing(markdown: &str) -> bool {
+    has_front_matter(markdown)
+        || has_includes(markdown)
+        || has_placeholders(markdown)
+        || has_snippets(markdown)
+}
+
+/// Where the file `omnidoc_impl` would include for `doc_path` is
+///
+/// Returns `None` outside of a compiler-driven expansion, where there is no call site to
+/// resolve the path against.
+fn resolve_doc_path(doc_path: &str) -> Option<PathBuf> {
+    let local_file = proc_macro2::Span::call_site().local_file()?;
+    let rel_doc_path = make_manifest_relative_path(doc_path, &local_file);
+    Some(local_file.parent()?.join(rel_doc_path))
+}
+
+/// The contents of the file `omnidoc_impl` would include for `doc_path`
+pub(crate) fn read_doc_file(doc_path: &str) -> Option<std::io::Result<String>> {
+    Some(std::fs::read_to_string(resolve_doc_path(doc_path)?))
+}
+
+/// The bytes of a file next to the docs, like an image, resolved as [`read_doc_file`] does
+pub(crate) fn read_doc_bytes(path: &str) -> Option<std::io::Result<Vec<u8>>> {
+    Some(std::fs::read(resolve_doc_path(path)?))
+}
+
+/// The path of `target`, written relative to the doc file `file`
+pub(crate) fn beside(file: &str, target: &str) -> String {
+    match file.rsplit_once('/') {
+        Some((dir, _)) => format!("{}/{}", dir, target),
+        None => target.to_string(),
+    }
+}
+
+/// The doc file at `doc_path`, or the default locale's copy of it if it isn't translated
+///
+/// `doc_path` is under `docs_root`, and `fallback_root` is the default locale's docs directory
+/// when docs are being built in another locale. A file missing from both stays at `doc_path`.
+pub(crate) fn localised(doc_path: &str, docs_root: &str, fallback_root: Option<&str>) -> String {
+    let Some(fallback_root) = fallback_root else {
+        return doc_path.to_string();
+    };
+    let Some(rel_path) = doc_path.strip_prefix(&format!("{}/", docs_root)) else {
+        return doc_path.to_string();
+    };
+    let fallback = format!("{}/{}", fallback_root, rel_path);
+    match (read_doc_file(doc_path), read_doc_file(&fallback)) {
+        (Some(Err(_)), Some(Ok(_))) => fallback,
+        _ => doc_path.to_string(),
+    }
+}
+
+/// The docs-path in the locale the build helper picked, and the default's if another
+///
+/// A module's docs are an expression rather than attributes, so unlike items' they can't have each
+/// locale's docs for the compiler to pick between.
+fn active_docs_paths(
+    config: &SyncdocConfig,
+    source_file: &str,
+) -> core::result::Result<(String, Option<String>), Box<dyn std::error::Error>> {
+    let default = config.docs_path_from(source_file, None)?;
+    let Some(locales) = &config.locales else {
+        return Ok((default, None));
+    };
+    let locale = std::env::var(crate::build::ACTIVE_LOCALE_ENV)
+        .ok()
+        .filter(|locale| locales.all.contains(locale))
+        .unwrap_or_else(|| locales.default.clone());
+    let active = config.docs_path_from(source_file, Some(&locale))?;
+    Ok(if active == default {
+        (default, None)
+    } else {
+        (active, Some(default))
+    })
+}
+
 /// Implementation for the module_doc!() macro
 ///
 /// Generates an include_str!() call with the automatically resolved path
-/// to the module's markdown documentation file.
+/// to the module's markdown documentation file. Docs that need processing are given as text
+/// instead, which only the [build helper](crate::build) rebuilds when the files change.
 pub fn module_doc_impl(args: TokenStream) -> core::result::Result<TokenStream, TokenStream> {
     let call_site = proc_macro2::Span::call_site();
     let source_file = call_site
@@ -42,18 +254,24 @@ pub fn module_doc_impl(args: TokenStream) -> core::result::Result<TokenStream, T
         .to_string_lossy()
         .to_string();
 
-    // Parse the arguments to get base_path if provided
-    let base_path = if args.is_empty() {
+    let config = SyncdocConfig::load(&source_file).map_err(|e| {
+        let error = format!("Failed to read syncdoc config: {}", e);
+        quote! { compile_error!(#error) }
+    })?;
+
+    // Parse the arguments to get base_path (and a name override) if provided
+    let (base_path, name, fallback_root) = if args.is_empty() {
         // No args provided, get from config
-        crate::config::get_docs_path(&source_file).map_err(|e| {
+        let (base_path, fallback_root) = active_docs_paths(&config, &source_file).map_err(|e| {
             let error = format!("Failed to get docs path from config: {}", e);
             quote! { compile_error!(#error) }
-        })?
+        })?;
+        (base_path, None, fallback_root)
     } else {
         // Parse args to extract path
         let mut args_iter = args.into_token_iter();
-        match parse_syncdoc_args(&mut args_iter) {
-            Ok(parsed_args) => parsed_args.base_path,
+        match parse_syncdoc_args(&mut args_iter, &config, &source_file) {
+            Ok(parsed_args) => (parsed_args.base_path, parsed_args.name, None),
             Err(e) => {
                 let error = format!("Failed to parse module_doc args: {}", e);
                 return Err(quote! { compile_error!(#error) });
@@ -63,16 +281,28 @@ pub fn module_doc_impl(args: TokenStream) -> core::result::Result<TokenStream, T
 
     // Extract module path and construct full doc path
     let module_path = crate::path_utils::extract_module_path(&source_file);
-    let doc_path = if module_path.is_empty() {
+    let module_path = if module_path.is_empty() {
         // For lib.rs or main.rs, use the file stem
-        let file_stem = std::path::Path::new(&source_file)
+        std::path::Path::new(&source_file)
             .file_stem()
             .and_then(|s| s.to_str())
-            .unwrap_or("module");
-        format!("{}/{}.md", base_path, file_stem)
+            .unwrap_or("module")
+            .to_string()
     } else {
-        format!("{}/{}.md", base_path, module_path)
+        module_path
+    };
+    // A name override replaces the last segment, keeping the file in its module's directory
+    let module_path = match name {
+        Some(name) => match module_path.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/{}", parent, name),
+            None => name,
+        },
+        None => module_path,
     };
+    // An untranslated module's docs come from the default locale
+    let doc_path = format!("{}/{}.md", base_path, module_path);
+    let localised = |path: &str| localised(path, &base_path, fallback_root.as_deref());
+    let doc_path = localised(&doc_path);
 
     // Make path relative to call site
     let local_file = call_site.local_file().ok_or_else(|| {
@@ -81,6 +311,103 @@ pub fn module_doc_impl(args: TokenStream) -> core::result::Result<TokenStream, T
     })?;
     let rel_doc_path = make_manifest_relative_path(&doc_path, &local_file);
 
+    // In the sections layout the file also holds the items' docs, which come after the module's,
+    // front matter is left out of the docs, fragments, placeholders and snippets are filled in,
+    // and titles, alerts, images and links to other doc files are handled as configured
+    let SyncdocConfig {
+        layout,
+        strip_title: strip,
+        admonitions,
+        embed_images: embed,
+        rewrite_links: rewrite,
+        ..
+    } = config;
+    let mut doc_items = rewrite.then(|| DocItems::of_crate(&local_file)).flatten();
+    let mut link_items = |markdown: String, doc_file: &str| match &mut doc_items {
+        Some(items) => rewrite_links(&markdown, doc_file, items),
+        None => markdown,
+    };
+    if let Some(Ok(markdown)) = read_doc_file(&doc_path) {
+        // The crate root's item is the crate itself
+        let item_path = crate::path_utils::rust_module_path(&source_file);
+        let vars = match item_path.rsplit("::").next() {
+            Some(name) if !name.is_empty() => template_vars(name.to_string(), item_path.clone()),
+            _ => {
+                let vars = template_vars(String::new(), "crate".to_string());
+                TemplateVars {
+                    item_name: vars.crate_name.clone(),
+                    ..vars
+                }
+            }
+        };
+        let markdown = match layout {
+            DocLayout::Sections => Sections::parse(&markdown).preamble,
+            DocLayout::PerItem => markdown,
+        };
+        // A title names the module, or the crate at its root
+        let untitled = strip
+            .then(|| strip_title(&markdown, &vars.item_name))
+            .flatten();
+        let restyled = (admonitions != Admonitions::Keep && has_alerts(&markdown))
+            || (embed.is_some() && has_local_images(&markdown))
+            || (rewrite && has_doc_links(&markdown));
+        if layout == DocLayout::PerItem
+            && untitled.is_none()
+            && !restyled
+            && !needs_processing(&markdown)
+        {
+            return Ok(quote! { include_str!(#rel_doc_path) });
+        }
+        let markdown = untitled.unwrap_or(markdown);
+        // `module_doc!()` only gives the docs, so there is nothing to put front matter's attributes on
+        let (front_matter, body) = FrontMatter::parse(&markdown).map_err(|e| {
+            let error = format!("{} in `{}`", e, doc_path);
+            quote! { compile_error!(#error) }
+        })?;
+        if !front_matter.is_empty() {
+            let error = format!(
+                "module docs can't have front matter, as `module_doc!()` has no item to put it on (in `{}`)",
+                doc_path
+            );
+            return Err(quote! { compile_error!(#error) });
+        }
+        // Images are relative to the file they're in
+        let inline_images = |file: &str, markdown: &str| match embed {
+            Some(max_size) => embed_images(markdown, max_size, &mut |path| {
+                read_doc_bytes(&beside(file, path))?.ok()
+            }),
+            None => Ok(markdown.to_string()),
+        };
+        let mut image_error = None;
+        let body = inline_images(&doc_path, body)
+            .map(|body| link_items(body, &format!("{}.md", module_path)))
+            .and_then(|body| {
+                resolve_includes(&body, &mut |path| {
+                    let fragment_path = localised(&format!("{}/{}", base_path, path));
+                    let fragment = read_doc_file(&fragment_path)?.ok()?;
+                    let fragment = inline_images(&fragment_path, &fragment).unwrap_or_else(|e| {
+                        image_error.get_or_insert(format!("{} (from `{}`)", e, path));
+                        fragment
+                    });
+                    Some(link_items(fragment, path))
+                })
+            })
+            .and_then(|body| match image_error.take() {
+                Some(e) => Err(e),
+                None => Ok(body),
+            })
+            .and_then(|body| expand(&body, &vars))
+            .and_then(|body| resolve_snippets(&body, &mut |path| read_doc_file(path)?.ok()))
+            .map_err(|e| {
+                let error = format!("{} in `{}`", e, doc_path);
+                quote! { compile_error!(#error) }
+            })?;
+        let body = render_alerts(&body, &admonitions);
+        // The compiler can't watch the files text like this came from, so without the build
+        // helper the docs are only rebuilt with the crate's sources
+        return Ok(quote! { #body });
+    }
+
     // Generate include_str!() call
     Ok(quote! {
         include_str!(#rel_doc_path)
@@ -94,7 +421,11 @@ struct SyncDocArgs {
     cfg_attr: Option<String>,
 }
 
-fn parse_syncdoc_args(input: &mut TokenIter) -> core::result::Result<SyncDocArgs, String> {
+fn parse_syncdoc_args(
+    input: &mut TokenIter,
+    config: &SyncdocConfig,
+    source_file: &str,
+) -> core::result::Result<SyncDocArgs, String> {
     match input.parse::<SyncDocInner>() {
         Ok(parsed) => {
             let mut args = SyncDocArgs {
@@ -115,43 +446,23 @@ fn parse_syncdoc_args(input: &mut TokenIter) -> core::result::Result<SyncDocArgs
                         SyncDocArg::CfgAttr(cfg_attr_arg) => {
                             args.cfg_attr = Some(cfg_attr_arg.value.as_str().to_string());
                         }
+                        // A module's own doc file is always included, and never parsed
+                        SyncDocArg::Optional(_) | SyncDocArg::Strict(_) | SyncDocArg::Merge(_) => {}
                     }
                 }
             }
 
-            if args.base_path.is_empty() || args.cfg_attr.is_none() {
-                // If macro path and TOML docs-path both unset, we don't know where to find the docs
-                if args.base_path.is_empty() {
-                    // Get the call site's file path if there might be config we could use there
-                    let call_site = proc_macro2::Span::call_site();
-                    let source_file = call_site
-                        .local_file()
-                        .ok_or("Could not determine source file location")?
-                        .to_string_lossy()
-                        .to_string();
-
-                    let base_path = crate::config::get_docs_path(&source_file)
-                        .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
-
-                    // Extract module path and prepend to base_path
-                    let module_path = crate::path_utils::extract_module_path(&source_file);
-                    args.base_path = if module_path.is_empty() {
-                        base_path
-                    } else {
-                        format!("{}/{}", base_path, module_path)
-                    };
-                }
+            // If macro path and TOML docs-path both unset, we don't know where to find the docs
+            if args.base_path.is_empty() {
+                // The module path is appended by module_doc_impl, not here
+                args.base_path = config
+                    .docs_path_from(source_file, None)
+                    .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
+            }
 
-                // We don't error on unconfigured cfg_attr, it's optional
-                if args.cfg_attr.is_none() {
-                    let call_site = proc_macro2::Span::call_site();
-                    if let Some(source_path) = call_site.local_file() {
-                        let source_file = source_path.to_string_lossy().to_string();
-                        if let Ok(cfg) = crate::config::get_cfg_attr(&source_file) {
-                            args.cfg_attr = cfg;
-                        }
-                    }
-                }
+            // We don't error on unconfigured cfg_attr, it's optional
+            if args.cfg_attr.is_none() {
+                args.cfg_attr = config.cfg_attr.as_ref().map(|cfg| cfg.to_string());
             }
 
             Ok(args)
@@ -0,0 +1,49 @@
+//! Companion doc files for Cargo features, like `Config.feature-serde.md` next to `Config.md`
+//!
+//! A companion holds docs that only apply with its feature on. They are appended to the item's
+//! docs under `#[cfg_attr(feature = "serde", doc = ...)]`, so rustdoc shows them when the docs
+//! are built with the feature. In the sections layout a companion sits where the item's own
+//! file would be.
+
+use std::path::{Path, PathBuf};
+
+/// What comes between a doc file's stem and the feature in its companions' names
+const MARKER: &str = ".feature-";
+
+/// The path of the companion to the doc file at `doc_path` for `feature`
+pub fn feature_doc_path(doc_path: &str, feature: &str) -> String {
+    let stem = doc_path.strip_suffix(".md").unwrap_or(doc_path);
+    format!("{}{}{}.md", stem, MARKER, feature)
+}
+
+/// The feature a doc file is the companion for, if it is one
+pub fn feature_of(doc_path: &Path) -> Option<String> {
+    let stem = doc_path.file_name()?.to_str()?.strip_suffix(".md")?;
+    let (_, feature) = stem.split_once(MARKER)?;
+    (!feature.is_empty()).then(|| feature.to_string())
+}
+
+/// The companions of the doc file at `doc_path` that exist, with their features, by feature
+pub fn find_feature_docs(doc_path: &Path) -> Vec<(String, PathBuf)> {
+    let (Some(dir), Some(stem)) = (doc_path.parent(), doc_path.file_stem()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}{}", stem.to_string_lossy(), MARKER);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut companions: Vec<_> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let feature = name.strip_prefix(&prefix)?.strip_suffix(".md")?;
+            (!feature.is_empty() && path.is_file()).then(|| (feature.to_string(), path.clone()))
+        })
+        .collect();
+    companions.sort();
+    companions
+}
+
+#[cfg(test)]
+#[path = "tests/features.rs"]
+mod features_tests;
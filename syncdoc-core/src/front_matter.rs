@@ -0,0 +1,205 @@
+//! Front matter at the top of a doc file, for attributes that belong with the prose
+//!
+//! A doc file can open with a block of `key: value` (or `key = value`) lines between `---` or
+//! `+++` fences, setting `deprecated`, `since`, `aliases` and `hidden`. Lists are `[a, "b"]` or
+//! YAML's `- a` lines. The block is left out of the rendered docs, and the macro puts the
+//! matching `#[deprecated]`, `#[doc(alias)]` and `#[doc(hidden)]` attributes on the item.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The attributes a doc file's front matter asks for
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrontMatter {
+    /// Whether the item is deprecated
+    pub deprecated: bool,
+    /// Why the item is deprecated, or what to use instead
+    pub note: Option<String>,
+    /// The version the item was deprecated in
+    pub since: Option<String>,
+    /// Other names to find the item by in rustdoc's search
+    pub aliases: Vec<String>,
+    /// Whether to hide the item from the docs
+    pub hidden: bool,
+}
+
+impl FrontMatter {
+    /// Splits a doc file into its front matter and the markdown after it
+    ///
+    /// A file without a (closed) front matter block is all markdown, with empty front matter.
+    pub fn parse(markdown: &str) -> Result<(Self, &str), String> {
+        let Some((block, body)) = split_block(markdown) else {
+            return Ok((FrontMatter::default(), markdown));
+        };
+
+        let mut front_matter = FrontMatter::default();
+        let mut lines = block.lines().peekable();
+        while let Some(line) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once([':', '=']).ok_or_else(|| {
+                format!("expected `key: value` in front matter, found `{}`", line)
+            })?;
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "deprecated" => match value {
+                    "true" => front_matter.deprecated = true,
+                    "false" => front_matter.deprecated = false,
+                    note => {
+                        front_matter.deprecated = true;
+                        front_matter.note = Some(unquote(note));
+                    }
+                },
+                "since" => front_matter.since = Some(unquote(value)),
+                "aliases" => {
+                    front_matter.aliases = if value.is_empty() {
+                        // A YAML block list, one `- alias` per line
+                        let mut aliases = Vec::new();
+                        while let Some(item) =
+                            lines.peek().and_then(|next| next.trim().strip_prefix('-'))
+                        {
+                            aliases.push(unquote(item.trim()));
+                            lines.next();
+                        }
+                        aliases
+                    } else {
+                        parse_list(value)
+                    }
+                }
+                "hidden" => {
+                    front_matter.hidden = value.parse().map_err(|_| {
+                        format!("invalid hidden = {}, expected true or false", value)
+                    })?
+                }
+                other => {
+                    return Err(format!(
+                    "unknown front matter key `{}`, expected deprecated, since, aliases or hidden",
+                    other
+                ))
+                }
+            }
+        }
+
+        if front_matter.since.is_some() && !front_matter.deprecated {
+            return Err(
+                "front matter `since` is the version the item was deprecated in, so it needs `deprecated` too"
+                    .to_string(),
+            );
+        }
+        Ok((front_matter, body))
+    }
+
+    /// Whether there are no attributes to add
+    pub fn is_empty(&self) -> bool {
+        *self == FrontMatter::default()
+    }
+
+    /// The attributes to put on the item, leaving out `#[deprecated]` if it already has one
+    pub fn attributes(&self, already_deprecated: bool) -> TokenStream {
+        let mut output = TokenStream::new();
+        if self.deprecated && !already_deprecated {
+            let args = self
+                .since
+                .iter()
+                .map(|since| quote! { since = #since })
+                .chain(self.note.iter().map(|note| quote! { note = #note }));
+            output.extend(if self.since.is_none() && self.note.is_none() {
+                quote! { #[deprecated] }
+            } else {
+                quote! { #[deprecated(#(#args),*)] }
+            });
+        }
+        for alias in &self.aliases {
+            output.extend(quote! { #[doc(alias = #alias)] });
+        }
+        if self.hidden {
+            output.extend(quote! { #[doc(hidden)] });
+        }
+        output
+    }
+
+    /// Writes the front matter as a YAML block, ready to go before the markdown
+    pub fn render(&self) -> String {
+        let mut lines = vec!["---".to_string()];
+        match &self.note {
+            Some(note) if self.deprecated => lines.push(format!("deprecated: {}", quote_str(note))),
+            _ if self.deprecated => lines.push("deprecated: true".to_string()),
+            _ => {}
+        }
+        if let Some(since) = &self.since {
+            lines.push(format!("since: {}", quote_str(since)));
+        }
+        if !self.aliases.is_empty() {
+            let aliases: Vec<String> = self.aliases.iter().map(|a| quote_str(a)).collect();
+            lines.push(format!("aliases: [{}]", aliases.join(", ")));
+        }
+        if self.hidden {
+            lines.push("hidden: true".to_string());
+        }
+        lines.push("---".to_string());
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Whether a doc file opens with a front matter block
+pub fn has_front_matter(markdown: &str) -> bool {
+    split_block(markdown).is_some()
+}
+
+/// The text between a `---` or `+++` line at the very start and the matching closing line
+fn split_block(markdown: &str) -> Option<(&str, &str)> {
+    let fence = ["---", "+++"]
+        .into_iter()
+        .find(|fence| markdown.lines().next().map(str::trim_end) == Some(*fence))?;
+    let after_open = markdown.find('\n')? + 1;
+
+    let mut offset = after_open;
+    for line in markdown[after_open..].split_inclusive('\n') {
+        if line.trim_end() == fence {
+            let body = &markdown[offset + line.len()..];
+            return Some((&markdown[after_open..offset], body.trim_start_matches('\n')));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Strips one pair of matching quotes, as YAML and TOML strings have, undoing [`quote_str`]
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        return inner.replace("\\\"", "\"").replace("\\\\", "\\");
+    }
+    value
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .unwrap_or(value)
+        .to_string()
+}
+
+/// Parses a flow list like `[fetch, "get"]`, or a single value as a list of one
+fn parse_list(value: &str) -> Vec<String> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .unwrap_or(value);
+    inner
+        .split(',')
+        .map(|item| unquote(item.trim()))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Quotes a string for YAML, escaping what would end it early
+fn quote_str(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+#[path = "tests/front_matter.rs"]
+mod front_matter_tests;
@@ -0,0 +1,215 @@
+//! Per-item `#[syncdoc(...)]` helper attributes
+//!
+//! Inside an `#[omnidoc]` scope every documentable item gets a doc include. The helper
+//! attribute lets individual items opt out (`#[syncdoc(skip)]`) or read their docs from
+//! another file (`#[syncdoc(path = "shared/ctor.md")]`, relative to the docs root).
+//! The attribute is inert: the macro strips it from its output.
+
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+use unsynn::*;
+
+use crate::parse::{Attribute, ModuleItem, SyncDocHelperArg, SyncDocHelperInner};
+
+/// What a helper attribute asks of the item it is attached to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemDirective {
+    /// Leave the item, and anything nested in it, without doc includes
+    Skip,
+    /// Read the item's own docs from this path, relative to the docs root
+    Path(String),
+}
+
+/// A malformed helper attribute, reported at the attribute's span
+#[derive(Debug)]
+pub struct HelperAttrError {
+    /// Span of the attribute's argument list
+    pub span: Span,
+    /// What was wrong with it
+    pub message: String,
+}
+
+impl HelperAttrError {
+    /// A `compile_error!` invocation pointing at the malformed attribute
+    pub fn to_compile_error(&self) -> TokenStream {
+        let message = &self.message;
+        quote_spanned! {self.span=> compile_error!(#message); }
+    }
+}
+
+/// Returns the argument group if `attr` is `#[syncdoc(...)]`
+///
+/// `#[syncdoc::omnidoc]` also starts with `syncdoc` but is followed by a path separator,
+/// so only a parenthesised group directly after the ident counts.
+fn helper_args(attr: &Attribute) -> Option<Group> {
+    let mut ts = TokenStream::new();
+    unsynn::ToTokens::to_tokens(&attr.content, &mut ts);
+    let content = match ts.into_iter().next() {
+        Some(TokenTree::Group(g)) => g.stream(),
+        _ => return None,
+    };
+
+    let mut tokens = content.into_iter();
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(args)), None)
+            if ident == "syncdoc" && args.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(args)
+        }
+        _ => None,
+    }
+}
+
+/// Checks if an attribute is a `#[syncdoc(...)]` helper attribute
+pub fn is_helper_attr(attr: &Attribute) -> bool {
+    helper_args(attr).is_some()
+}
+
+fn parse_directive(args: &Group) -> core::result::Result<ItemDirective, HelperAttrError> {
+    let error = |message: &str| HelperAttrError {
+        span: args.span(),
+        message: message.to_string(),
+    };
+
+    let parsed = args
+        .stream()
+        .into_token_iter()
+        .parse_all::<SyncDocHelperInner>()
+        .map_err(|_| error("expected `#[syncdoc(skip)]` or `#[syncdoc(path = \"...\")]`"))?;
+
+    let mut directive = None;
+    for arg in parsed.args.0 {
+        let next = match arg.value {
+            SyncDocHelperArg::Skip(_) => ItemDirective::Skip,
+            SyncDocHelperArg::Path(path_arg) => {
+                ItemDirective::Path(path_arg.value.as_str().to_string())
+            }
+        };
+        if directive.is_some() {
+            return Err(error(
+                "`skip` and `path` cannot be combined in one #[syncdoc(...)]",
+            ));
+        }
+        directive = Some(next);
+    }
+
+    directive.ok_or_else(|| error("#[syncdoc(...)] needs `skip` or `path = \"...\"`"))
+}
+
+/// Removes any helper attributes from `attrs`, returning the directive they carry
+pub fn take_item_directive(
+    attrs: &mut Option<Many<Attribute>>,
+) -> core::result::Result<Option<ItemDirective>, HelperAttrError> {
+    let Some(attr_list) = attrs.as_mut() else {
+        return Ok(None);
+    };
+
+    let mut directive = None;
+    let mut result = Ok(());
+    attr_list.0.retain(|attr| match helper_args(&attr.value) {
+        Some(args) => {
+            match parse_directive(&args) {
+                Ok(d) => directive = Some(d),
+                Err(e) => result = Err(e),
+            }
+            false
+        }
+        None => true,
+    });
+
+    if attr_list.0.is_empty() {
+        *attrs = None;
+    }
+    result.map(|_| directive)
+}
+
+/// Removes every helper attribute from a token stream, including inside nested groups
+///
+/// Skipped items are emitted as written, but helper attributes on their fields or members
+/// still have to go, since `syncdoc` is not a real attribute outside of the macro.
+pub fn strip_helper_attrs(tokens: TokenStream) -> TokenStream {
+    let mut output = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                if let Some(TokenTree::Group(g)) = tokens.peek() {
+                    let attr = std::iter::once(TokenTree::Punct(p.clone()))
+                        .chain(std::iter::once(TokenTree::Group(g.clone())))
+                        .collect::<TokenStream>();
+                    let is_helper = attr
+                        .into_token_iter()
+                        .parse::<Attribute>()
+                        .is_ok_and(|attr| is_helper_attr(&attr));
+                    if is_helper {
+                        tokens.next();
+                        continue;
+                    }
+                }
+                output.push(TokenTree::Punct(p));
+            }
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), strip_helper_attrs(g.stream()));
+                group.set_span(g.span());
+                output.push(TokenTree::Group(group));
+            }
+            other => output.push(other),
+        }
+    }
+    output.into_iter().collect()
+}
+
+/// Reads the directive from `attrs` without removing it, ignoring malformed helpers
+pub fn find_item_directive(attrs: &Option<Many<Attribute>>) -> Option<ItemDirective> {
+    attrs
+        .as_ref()?
+        .0
+        .iter()
+        .rev()
+        .filter_map(|attr| helper_args(&attr.value))
+        .find_map(|args| parse_directive(&args).ok())
+}
+
+/// The outer attributes of a module item, if it has any
+pub fn item_attributes(item: &ModuleItem) -> Option<&Option<Many<Attribute>>> {
+    match item {
+        ModuleItem::TraitMethod(sig) => Some(&sig.attributes),
+        ModuleItem::Function(sig) => Some(&sig.attributes),
+        ModuleItem::ImplBlock(sig) => Some(&sig.attributes),
+        ModuleItem::Module(sig) => Some(&sig.attributes),
+        ModuleItem::Trait(sig) => Some(&sig.attributes),
+        ModuleItem::Enum(sig) => Some(&sig.attributes),
+        ModuleItem::Struct(sig) => Some(&sig.attributes),
+        ModuleItem::Union(sig) => Some(&sig.attributes),
+        ModuleItem::TypeAlias(sig) => Some(&sig.attributes),
+        ModuleItem::Const(sig) => Some(&sig.attributes),
+        ModuleItem::Static(sig) => Some(&sig.attributes),
+        ModuleItem::MacroRules(sig) => Some(&sig.attributes),
+        ModuleItem::ExternBlock(sig) => Some(&sig.attributes),
+        ModuleItem::Other(_) => None,
+    }
+}
+
+/// Mutable access to the outer attributes of a module item, if it has any
+pub fn item_attributes_mut(item: &mut ModuleItem) -> Option<&mut Option<Many<Attribute>>> {
+    match item {
+        ModuleItem::TraitMethod(sig) => Some(&mut sig.attributes),
+        ModuleItem::Function(sig) => Some(&mut sig.attributes),
+        ModuleItem::ImplBlock(sig) => Some(&mut sig.attributes),
+        ModuleItem::Module(sig) => Some(&mut sig.attributes),
+        ModuleItem::Trait(sig) => Some(&mut sig.attributes),
+        ModuleItem::Enum(sig) => Some(&mut sig.attributes),
+        ModuleItem::Struct(sig) => Some(&mut sig.attributes),
+        ModuleItem::Union(sig) => Some(&mut sig.attributes),
+        ModuleItem::TypeAlias(sig) => Some(&mut sig.attributes),
+        ModuleItem::Const(sig) => Some(&mut sig.attributes),
+        ModuleItem::Static(sig) => Some(&mut sig.attributes),
+        ModuleItem::MacroRules(sig) => Some(&mut sig.attributes),
+        ModuleItem::ExternBlock(sig) => Some(&mut sig.attributes),
+        ModuleItem::Other(_) => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/helper_attr.rs"]
+mod tests;
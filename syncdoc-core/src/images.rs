@@ -0,0 +1,138 @@
+//! Local images in doc files, embedded as `data:` URIs so they show up in rustdoc and on docs.rs
+//!
+//! `![diagram](img/arch.png)` in a doc file works on GitHub, but the image isn't shipped with the
+//! rendered docs. With `embed-images = true` each image a doc file references by a relative path
+//! is read and put inline, as `![diagram](data:image/png;base64,...)`. Paths are relative to the
+//! doc file, as on GitHub, and an image over `max-image-size` bytes is an error.
+
+/// Whether the markdown references any local images
+pub fn has_local_images(markdown: &str) -> bool {
+    !local_images(markdown).is_empty()
+}
+
+/// Each local image path in the markdown, as written
+pub fn local_images(markdown: &str) -> Vec<&str> {
+    images(markdown).into_iter().map(|(_, path)| path).collect()
+}
+
+/// Replaces each local image path with a `data:` URI of the image
+///
+/// `read` loads an image by its path relative to the doc file, or gives `None` if it doesn't
+/// exist. A missing image, one larger than `max_size` bytes, or one of an unknown type is an error.
+pub fn embed_images(
+    markdown: &str,
+    max_size: usize,
+    read: &mut impl FnMut(&str) -> Option<Vec<u8>>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for (range, path) in images(markdown) {
+        let mime = mime_type(path).ok_or_else(|| {
+            format!(
+                "unknown image type for `{}`, expected png, jpg, gif, svg, webp or avif",
+                path
+            )
+        })?;
+        let bytes = read(path).ok_or_else(|| format!("missing image `{}`", path))?;
+        if bytes.len() > max_size {
+            return Err(format!(
+                "image `{}` is {} bytes, over the max-image-size of {}",
+                path,
+                bytes.len(),
+                max_size
+            ));
+        }
+        output.push_str(&markdown[rest..range.start]);
+        output.push_str(&format!("data:{};base64,{}", mime, base64(&bytes)));
+        rest = range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    Ok(output)
+}
+
+/// The media type for an image path's extension
+fn mime_type(path: &str) -> Option<&'static str> {
+    let (_, extension) = path.rsplit_once('.')?;
+    match extension.to_ascii_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "svg" => Some("image/svg+xml"),
+        "webp" => Some("image/webp"),
+        "avif" => Some("image/avif"),
+        _ => None,
+    }
+}
+
+/// Standard base64, with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | ((byte as u32) << (16 - 8 * i))
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Each local image path in the markdown, with where it is
+///
+/// Covers inline images (`![alt](path)`) outside of code blocks. Paths with a scheme, that start
+/// at `/`, or that are already `data:` URIs, aren't local.
+fn images(markdown: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let mut search = 0;
+        while let Some(open) = line[search..].find("![") {
+            let alt_start = search + open + 2;
+            let Some(close) = line[alt_start..].find("](") else {
+                break;
+            };
+            let path_start = alt_start + close + 2;
+            let Some(len) = line[path_start..].find(')') else {
+                break;
+            };
+            let target = &line[path_start..path_start + len];
+            let path = target.split_whitespace().next().unwrap_or_default();
+            if is_local(path) {
+                let at = start + path_start + target.find(path).unwrap_or(0);
+                found.push((at..at + path.len(), path));
+            }
+            search = path_start + len;
+        }
+    }
+    found
+}
+
+/// Whether an image path points to a file next to the docs
+fn is_local(path: &str) -> bool {
+    !path.is_empty() && !path.contains(':') && !path.starts_with('/') && !path.starts_with('#')
+}
+
+#[cfg(test)]
+#[path = "tests/images.rs"]
+mod images_tests;
@@ -0,0 +1,115 @@
+//! Include directives, for doc fragments shared between items
+//!
+//! A `<!-- syncdoc:include shared/safety.md -->` comment in a doc file is replaced by the
+//! fragment it names, so repeated "# Safety" or "# Errors" paragraphs live in one place.
+//! Paths are relative to the docs root (`docs-path`), and fragments can include others.
+
+/// Where each `<!-- name target -->` directive in the markdown is, and the target it names
+///
+/// Directives inside fenced code blocks are examples of the syntax, so they're left alone.
+pub(crate) fn directives<'a>(
+    markdown: &'a str,
+    name: &'static str,
+) -> impl Iterator<Item = (std::ops::Range<usize>, &'a str)> + 'a {
+    let fenced = fenced_blocks(markdown);
+    let mut offset = 0;
+    std::iter::from_fn(move || loop {
+        let start = offset + markdown[offset..].find("<!--")?;
+        if let Some(block) = fenced.iter().find(|block| block.contains(&start)) {
+            offset = block.end;
+            continue;
+        }
+        let end = start + 4 + markdown[start + 4..].find("-->")? + 3;
+        offset = end;
+        let path = markdown[start + 4..end - 3]
+            .trim()
+            .strip_prefix(name)
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .map(str::trim);
+        if let Some(path) = path.filter(|path| !path.is_empty()) {
+            return Some((start..end, path));
+        }
+    })
+}
+
+/// The byte ranges of the markdown's fenced code blocks, fences included
+fn fenced_blocks(markdown: &str) -> Vec<std::ops::Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<(&str, usize)> = None;
+    for line in markdown.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+        if let Some((marker, opened)) = fence {
+            if trimmed.starts_with(marker) {
+                blocks.push(opened..offset);
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some((&trimmed[..3], start));
+        }
+    }
+    // An unclosed fence runs to the end of the markdown
+    if let Some((_, opened)) = fence {
+        blocks.push(opened..offset);
+    }
+    blocks
+}
+
+/// The fragment paths the markdown's include directives name, in order
+pub fn includes(markdown: &str) -> Vec<&str> {
+    directives(markdown, "syncdoc:include")
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Whether the markdown has any include directives
+pub fn has_includes(markdown: &str) -> bool {
+    directives(markdown, "syncdoc:include").next().is_some()
+}
+
+/// Replaces each include directive with the fragment it names, and theirs in turn
+///
+/// `read` loads a fragment by its path relative to the docs root, or gives `None` if it doesn't
+/// exist. A fragment that (eventually) includes itself is an error naming the cycle.
+pub fn resolve_includes(
+    markdown: &str,
+    read: &mut impl FnMut(&str) -> Option<String>,
+) -> Result<String, String> {
+    resolve(markdown, &mut Vec::new(), read)
+}
+
+fn resolve(
+    markdown: &str,
+    stack: &mut Vec<String>,
+    read: &mut impl FnMut(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for (range, path) in directives(markdown, "syncdoc:include") {
+        if stack.iter().any(|including| including == path) {
+            let cycle: Vec<&str> = stack.iter().map(String::as_str).chain([path]).collect();
+            return Err(format!("include cycle {}", cycle.join(" -> ")));
+        }
+        let fragment = read(path).ok_or_else(|| match stack.last() {
+            Some(parent) => format!("missing include `{}` (from `{}`)", path, parent),
+            None => format!("missing include `{}`", path),
+        })?;
+        stack.push(path.to_string());
+        let fragment = resolve(&fragment, stack, read)?;
+        stack.pop();
+
+        output.push_str(&markdown[rest..range.start]);
+        output.push_str(fragment.trim_end_matches('\n'));
+        rest = range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    Ok(output)
+}
+
+#[cfg(test)]
+#[path = "tests/include.rs"]
+mod include_tests;
@@ -1,10 +1,23 @@
 /// syncdoc-core: documentation injection helper macros
+pub mod admonition;
+pub mod build;
+pub mod cfg;
 pub mod config;
 pub mod debug;
 mod doc_injector;
+pub mod features;
+pub mod front_matter;
+pub mod helper_attr;
+pub mod images;
+pub mod include;
+pub mod links;
 mod omnibus;
 pub mod parse;
 pub mod path_utils;
+pub mod sections;
+pub mod snippet;
+pub mod template;
+pub mod title;
 pub mod token_processors;
 
 pub use doc_injector::{module_doc_impl, omnidoc_impl};
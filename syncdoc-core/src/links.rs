@@ -0,0 +1,308 @@
+//! Links between doc files, rewritten into intra-doc links to the items they document
+//!
+//! In `docs/lib/api/run.md`, a link like `[see](../types/Config.md)` works on GitHub but not once
+//! rustdoc renders the text on the item, so with `rewrite-links` on it becomes
+//! `[see](crate::types::Config)`. The item for a doc file is found by walking the crate's source
+//! the way the macros build doc paths, so only files that really are an item's are rewritten.
+
+use crate::parse::{EnumVariantData, ModuleContent, ModuleItem, StructBody, StructField};
+use crate::path_utils::{find_manifest_dir, impl_context, impl_rust_path};
+use proc_macro2::TokenStream;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use unsynn::{CommaDelimitedVec, IParse, ToTokens};
+
+/// Whether the markdown links to any other doc file
+pub fn has_doc_links(markdown: &str) -> bool {
+    links(markdown).next().is_some()
+}
+
+/// Points each link to another doc file at the item it documents instead
+///
+/// `doc_file` is the path of the markdown being rewritten, relative to the docs root, and link
+/// targets are resolved relative to it. Links that leave the docs root, or lead to a file that
+/// isn't an item's (like a trait impl's method, a fragment or a guide), are left as they are.
+/// A `#heading` on a link is dropped.
+pub fn rewrite_links(markdown: &str, doc_file: &str, items: &mut DocItems) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for (range, target) in links(markdown) {
+        let path = target.split('#').next().unwrap_or_default();
+        let Some(item) = resolve(doc_file, path).and_then(|path| items.item(&path)) else {
+            continue;
+        };
+        output.push_str(&markdown[rest..range.start]);
+        output.push_str(&item);
+        rest = range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    output
+}
+
+/// The items a crate's doc files document, by their path relative to the docs root
+///
+/// A doc path starts with the module of the source file its item is in (`lib` or `main` for the
+/// crate root), and the rest is built from the items nested in that file as the macros build it.
+/// Each source file is parsed once, when a link first leads into its docs.
+pub struct DocItems {
+    src_dir: PathBuf,
+    /// Each source file's doc paths (below its module's, without `.md`) and their items' paths
+    files: HashMap<String, Option<HashMap<String, String>>>,
+}
+
+impl DocItems {
+    /// The items of the crate whose sources are in `src_dir`
+    pub fn new(src_dir: PathBuf) -> Self {
+        DocItems {
+            src_dir,
+            files: HashMap::new(),
+        }
+    }
+
+    /// The items of the crate a source file is in
+    pub fn of_crate(source_file: &Path) -> Option<Self> {
+        Some(Self::new(find_manifest_dir(source_file)?.join("src")))
+    }
+
+    /// The item a doc file documents, like `crate::types::Config` for `lib/types/Config.md`
+    ///
+    /// `None` for a file no item's doc path leads to.
+    pub fn item(&mut self, doc_path: &str) -> Option<String> {
+        let segments: Vec<&str> = doc_path.strip_suffix(".md")?.split('/').collect();
+        // The longest leading run of segments that names a source file is its module
+        let (module, source) = (1..=segments.len()).rev().find_map(|len| {
+            let module = segments[..len].join("/");
+            let source = self.source_file(&module)?;
+            Some((len, source))
+        })?;
+        let mut path = match segments[0] {
+            "lib" | "main" if module == 1 => vec!["crate".to_string()],
+            _ => std::iter::once("crate")
+                .chain(segments[..module].iter().copied())
+                .map(str::to_string)
+                .collect(),
+        };
+        if module < segments.len() {
+            let key = segments[module..].join("/");
+            let items = self
+                .files
+                .entry(segments[..module].join("/"))
+                .or_insert_with(|| file_items(&source))
+                .as_ref()?;
+            path.push(items.get(&key)?.clone());
+        }
+        Some(path.join("::"))
+    }
+
+    /// The source file of the module at `module_path`, as in `api/types`, if there is one
+    fn source_file(&self, module_path: &str) -> Option<PathBuf> {
+        let candidates = match module_path {
+            "lib" | "main" => vec![format!("{}.rs", module_path)],
+            _ => vec![
+                format!("{}.rs", module_path),
+                format!("{}/mod.rs", module_path),
+            ],
+        };
+        candidates
+            .into_iter()
+            .map(|candidate| self.src_dir.join(candidate))
+            .find(|path| path.is_file())
+    }
+}
+
+/// The doc paths of the items in a source file, each with the item's path below the file's module
+fn file_items(source: &Path) -> Option<HashMap<String, String>> {
+    let tokens = TokenStream::from_str(&std::fs::read_to_string(source).ok()?).ok()?;
+    let content = tokens.into_token_iter().parse::<ModuleContent>().ok()?;
+    let mut items = HashMap::new();
+    collect_items(&content, &[], &[], &mut items);
+    Some(items)
+}
+
+/// Adds each item in `content` under the doc and Rust paths of what it's nested in
+///
+/// Members of trait impls have no path of their own to link to, and neither do tuple fields or
+/// `macro_rules!` macros, so they're left out.
+fn collect_items(
+    content: &ModuleContent,
+    doc_prefix: &[String],
+    rust_prefix: &[String],
+    items: &mut HashMap<String, String>,
+) {
+    let below = |prefix: &[String], name: String| -> Vec<String> {
+        prefix.iter().cloned().chain([name]).collect()
+    };
+    for item in &content.items.0 {
+        // The item's own path and those of its fields or variants, the same in the docs and Rust
+        let mut paths: Vec<Vec<String>> = Vec::new();
+        match &item.value {
+            ModuleItem::Function(f) => paths.push(vec![f.name.to_string()]),
+            ModuleItem::TraitMethod(f) => paths.push(vec![f.name.to_string()]),
+            ModuleItem::TypeAlias(t) => paths.push(vec![t.name.to_string()]),
+            ModuleItem::Const(c) => paths.push(vec![c.name.to_string()]),
+            ModuleItem::Static(s) => paths.push(vec![s.name.to_string()]),
+            ModuleItem::Struct(s) => {
+                let name = s.name.to_string();
+                if let StructBody::Named(fields) = &s.body {
+                    for field in field_names(&fields.content) {
+                        paths.push(vec![name.clone(), field]);
+                    }
+                }
+                paths.push(vec![name]);
+            }
+            ModuleItem::Union(u) => {
+                let name = u.name.to_string();
+                for field in field_names(&u.fields.content) {
+                    paths.push(vec![name.clone(), field]);
+                }
+                paths.push(vec![name]);
+            }
+            ModuleItem::Enum(e) => {
+                let name = e.name.to_string();
+                for variant in e.variants.content.iter().flat_map(|variants| &variants.0) {
+                    let variant = &variant.value;
+                    let variant_name = variant.name.to_string();
+                    if let Some(EnumVariantData::Struct(fields)) = &variant.data {
+                        for field in field_names(&fields.content) {
+                            paths.push(vec![name.clone(), variant_name.clone(), field]);
+                        }
+                    }
+                    paths.push(vec![name.clone(), variant_name]);
+                }
+                paths.push(vec![name]);
+            }
+            ModuleItem::Module(m) => {
+                let name = m.name.to_string();
+                let (doc, rust) = (
+                    below(doc_prefix, name.clone()),
+                    below(rust_prefix, name.clone()),
+                );
+                collect_items(&m.items.content, &doc, &rust, items);
+                paths.push(vec![name]);
+            }
+            ModuleItem::Trait(t) => {
+                let name = t.name.to_string();
+                let (doc, rust) = (
+                    below(doc_prefix, name.clone()),
+                    below(rust_prefix, name.clone()),
+                );
+                collect_items(&t.items.content, &doc, &rust, items);
+                paths.push(vec![name]);
+            }
+            ModuleItem::ImplBlock(impl_block) if impl_block.for_trait.is_none() => {
+                let mut doc = doc_prefix.to_vec();
+                doc.extend(impl_context(impl_block));
+                let rust = below(rust_prefix, impl_rust_path(impl_block, ""));
+                collect_items(&impl_block.items.content, &doc, &rust, items);
+            }
+            ModuleItem::ExternBlock(extern_block) => {
+                collect_items(&extern_block.items.content, doc_prefix, rust_prefix, items);
+            }
+            ModuleItem::ImplBlock(_) | ModuleItem::MacroRules(_) | ModuleItem::Other(_) => {}
+        }
+        for path in paths {
+            let doc: Vec<&String> = doc_prefix.iter().chain(&path).collect();
+            let rust: Vec<&String> = rust_prefix.iter().chain(&path).collect();
+            let join = |segments: Vec<&String>, sep: &str| {
+                segments
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(sep)
+            };
+            items.insert(join(doc, "/"), join(rust, "::"));
+        }
+    }
+}
+
+fn field_names(fields: &Option<CommaDelimitedVec<StructField>>) -> Vec<String> {
+    fields
+        .iter()
+        .flat_map(|fields| &fields.0)
+        .map(|field| field.value.name.to_string())
+        .collect()
+}
+
+/// Resolves a link target against the doc file it is in, to a path relative to the docs root
+///
+/// `None` for a target that leaves the docs root.
+pub fn resolve(doc_file: &str, target: &str) -> Option<String> {
+    let mut segments: Vec<&str> = doc_file.split('/').collect();
+    segments.pop();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            part => segments.push(part),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// Each link target in the markdown that points to another doc file, with where it is
+///
+/// Covers inline links (`[text](target)`) and reference definitions (`[label]: target`), outside
+/// of code blocks. Targets with a scheme, or that start at `/`, aren't doc files.
+fn links(markdown: &str) -> impl Iterator<Item = (std::ops::Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        // A reference definition is a whole line
+        if let Some(definition) = trimmed.strip_prefix('[') {
+            if let Some((_, target)) = definition.split_once("]:") {
+                let target = target.split_whitespace().next().unwrap_or_default();
+                if is_doc_link(target) {
+                    let at = start + line.find(target).unwrap_or_default();
+                    found.push((at..at + target.len(), target));
+                    continue;
+                }
+            }
+        }
+
+        let mut search = 0;
+        while let Some(open) = line[search..].find("](") {
+            let target_start = search + open + 2;
+            let Some(len) = line[target_start..].find(')') else {
+                break;
+            };
+            let target = line[target_start..target_start + len]
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            if is_doc_link(target) {
+                let at = start + target_start + line[target_start..].find(target).unwrap_or(0);
+                found.push((at..at + target.len(), target));
+            }
+            search = target_start + len;
+        }
+    }
+    found.into_iter()
+}
+
+/// Whether a link target is a relative path to a markdown file
+fn is_doc_link(target: &str) -> bool {
+    let path = target.split('#').next().unwrap_or_default();
+    path.ends_with(".md") && !path.contains("://") && !path.starts_with('/')
+}
+
+#[cfg(test)]
+#[path = "tests/links.rs"]
+mod links_tests;
@@ -1,7 +1,9 @@
-use proc_macro2::TokenStream;
-use quote::quote;
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
 use unsynn::*;
 
+use crate::cfg::CfgPredicate;
+use crate::config::{Admonitions, DocLayout, MergeDocs, MissingDocs, SyncdocConfig};
 use crate::parse::{SyncDocArg, SyncDocInner};
 use crate::path_utils::apply_module_path;
 use crate::token_processors::TokenProcessor;
@@ -10,86 +12,188 @@ pub fn inject_all_docs_impl(
     args: TokenStream,
     input: TokenStream,
 ) -> core::result::Result<TokenStream, TokenStream> {
-    let (base_path, cfg_attr) = match parse_path_from_args(args) {
+    let OmnidocArgs {
+        base_path,
+        docs_root,
+        locale_cfg,
+        translations,
+        name,
+        cfg_attr,
+        missing,
+        strict,
+        merge,
+        layout,
+        strip_title,
+        admonitions,
+        embed_images,
+        rewrite_links,
+    } = match parse_path_from_args(args) {
         Ok(result) => result,
-        Err(e) => {
-            let error_msg = e.to_string();
-            return Ok(quote! {
-                compile_error!(#error_msg);
+        Err(ArgsError { span, message }) => {
+            return Ok(quote_spanned! {span=>
+                compile_error!(#message);
                 #input
             });
         }
     };
 
-    Ok(TokenProcessor::new(input, base_path, cfg_attr).process())
+    Ok(TokenProcessor::new(input, base_path, cfg_attr)
+        .with_name(name)
+        .with_docs_root(docs_root)
+        .with_locales(locale_cfg, translations)
+        .with_missing(missing)
+        .with_strict(strict)
+        .with_merge(merge)
+        .with_layout(layout)
+        .with_strip_title(strip_title)
+        .with_admonitions(admonitions)
+        .with_embed_images(embed_images)
+        .with_rewrite_links(rewrite_links)
+        .process())
 }
 
-fn parse_path_from_args(
-    args: TokenStream,
-) -> core::result::Result<(String, Option<String>), String> {
-    // If no args provided, try to get from config
-    if args.is_empty() {
-        let call_site = proc_macro2::Span::call_site();
-        if let Some(source_path) = call_site.local_file() {
-            let source_file = source_path.to_string_lossy().to_string();
-            let base_path = crate::config::get_docs_path(&source_file)
-                .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
-            let cfg_attr = crate::config::get_cfg_attr(&source_file).ok().flatten();
-
-            let path = apply_module_path(base_path);
+/// A problem with the macro's arguments or config, reported at the argument it's about if any
+struct ArgsError {
+    span: Span,
+    message: String,
+}
 
-            return Ok((path, cfg_attr));
-        } else {
-            return Err("omnidoc requires a path argument".to_string());
+impl From<String> for ArgsError {
+    fn from(message: String) -> Self {
+        ArgsError {
+            span: Span::call_site(),
+            message,
         }
     }
+}
+
+/// Arguments resolved from `#[omnidoc(...)]`, falling back to the Cargo.toml config
+struct OmnidocArgs {
+    base_path: String,
+    /// The docs directory before the module path is applied
+    docs_root: String,
+    /// What the docs directory's locale is picked by, when the docs are translated
+    locale_cfg: Option<CfgPredicate>,
+    /// Each translation's cfg and docs directory
+    translations: Vec<(CfgPredicate, String)>,
+    /// Replaces the annotated item's own name when building its doc path
+    name: Option<String>,
+    /// What docs are gated behind, as in `#[cfg_attr(doc, doc = ...)]`
+    cfg_attr: Option<CfgPredicate>,
+    /// What to do about items whose doc file does not exist
+    missing: MissingDocs,
+    /// Whether items the parser can't handle are errors rather than left undocumented
+    strict: bool,
+    /// What to do about items with inline docs as well as a doc file
+    merge: MergeDocs,
+    /// Whether docs come from a file per item or a section per item
+    layout: DocLayout,
+    /// Whether a doc file's leading title naming its item is left out of the docs
+    strip_title: bool,
+    /// How GitHub alerts in doc files are shown
+    admonitions: Admonitions,
+    /// The size ceiling for images embedded as `data:` URIs, if they are
+    embed_images: Option<usize>,
+    /// Whether links between doc files become intra-doc links to their items
+    rewrite_links: bool,
+}
 
-    let mut args_iter = args.into_token_iter();
-    match args_iter.parse::<SyncDocInner>() {
-        Ok(parsed) => {
-            let mut path = None;
-            let mut cfg_attr = None;
+fn parse_path_from_args(args: TokenStream) -> core::result::Result<OmnidocArgs, ArgsError> {
+    // Outside of a compiler-driven expansion there is no crate to read the config of
+    let source_file = proc_macro2::Span::call_site()
+        .local_file()
+        .map(|source_path| source_path.to_string_lossy().to_string());
+    let config = match &source_file {
+        Some(source_file) => SyncdocConfig::load(source_file)
+            .map_err(|e| format!("Failed to read syncdoc config: {}", e))?,
+        None if args.is_empty() => {
+            return Err("omnidoc requires a path argument".to_string().into());
+        }
+        None => SyncdocConfig::default(),
+    };
 
-            if let Some(arg_list) = parsed.args {
-                for arg in arg_list.0 {
-                    match arg.value {
-                        SyncDocArg::Path(path_arg) => {
-                            path = Some(path_arg.value.as_str().to_string());
-                        }
-                        SyncDocArg::CfgAttr(cfg_arg) => {
-                            cfg_attr = Some(cfg_arg.value.as_str().to_string());
-                        }
-                        _ => {}
-                    }
-                }
-            }
+    let mut path = None;
+    let mut name = None;
+    let mut cfg_attr = config.cfg_attr.clone();
+    let mut missing = config.missing;
+    // The call site can turn strict on, but not off
+    let mut strict = config.strict;
+    let mut merge = config.merge;
 
-            let path = if let Some(p) = path {
-                apply_module_path(p)
-            } else {
-                // Try config
-                let call_site = proc_macro2::Span::call_site();
-                if let Some(source_path) = call_site.local_file() {
-                    let source_file = source_path.to_string_lossy().to_string();
-                    let base_path = crate::config::get_docs_path(&source_file)
-                        .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
-                    apply_module_path(base_path)
-                } else {
-                    return Err("path argument not found".to_string());
+    if !args.is_empty() {
+        let mut args_iter = args.into_token_iter();
+        let parsed = args_iter
+            .parse::<SyncDocInner>()
+            .map_err(|_| "Failed to parse arguments".to_string())?;
+        for arg in parsed.args.into_iter().flat_map(|arg_list| arg_list.0) {
+            match arg.value {
+                SyncDocArg::Path(path_arg) => {
+                    path = Some(path_arg.value.as_str().to_string());
                 }
-            };
-
-            // If cfg_attr still None, try config
-            if cfg_attr.is_none() {
-                let call_site = proc_macro2::Span::call_site();
-                if let Some(source_path) = call_site.local_file() {
-                    let source_file = source_path.to_string_lossy().to_string();
-                    cfg_attr = crate::config::get_cfg_attr(&source_file).ok().flatten();
+                SyncDocArg::Name(name_arg) => {
+                    name = Some(name_arg.value.as_str().to_string());
+                }
+                SyncDocArg::CfgAttr(cfg_arg) => {
+                    let predicate = cfg_arg.value.as_str().parse().map_err(|e| ArgsError {
+                        span: cfg_arg.value.clone().into_inner().span(),
+                        message: format!("Invalid cfg_attr: {}", e),
+                    })?;
+                    cfg_attr = Some(predicate);
+                }
+                SyncDocArg::Optional(_) => {
+                    missing = MissingDocs::Ignore;
+                }
+                SyncDocArg::Strict(_) => {
+                    strict = true;
+                }
+                SyncDocArg::Merge(merge_arg) => {
+                    merge = merge_arg.value.as_str().parse::<MergeDocs>()?;
                 }
             }
-
-            Ok((path, cfg_attr))
         }
-        Err(_e) => Err("Failed to parse arguments".to_string()),
     }
+
+    // Only the configured docs-path is translated
+    let (docs_root, translations) = match (path, &source_file) {
+        (Some(path), _) => (path, Vec::new()),
+        (None, Some(source_file)) => {
+            let docs_root = config
+                .docs_path_from(source_file, None)
+                .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
+            let translations = config
+                .translated_docs_paths_from(source_file)
+                .map_err(|e| format!("Failed to get docs path from config: {}", e))?;
+            (docs_root, translations)
+        }
+        (None, None) => return Err("path argument not found".to_string().into()),
+    };
+    let (locale_cfg, translations) = match &config.locales {
+        Some(locales) if !translations.is_empty() => (
+            Some(locales.cfg(&locales.default)),
+            translations
+                .into_iter()
+                .map(|(locale, docs_root)| (locales.cfg(&locale), docs_root))
+                .collect(),
+        ),
+        _ => (None, Vec::new()),
+    };
+
+    // The layout, titles, alerts, images and links are conventions of the whole docs tree, so they
+    // are only set in config
+    Ok(OmnidocArgs {
+        base_path: apply_module_path(docs_root.clone()),
+        docs_root,
+        locale_cfg,
+        translations,
+        name,
+        cfg_attr,
+        missing,
+        strict,
+        merge,
+        layout: config.layout,
+        strip_title: config.strip_title,
+        admonitions: config.admonitions,
+        embed_images: config.embed_images,
+        rewrite_links: config.rewrite_links,
+    })
 }
@@ -12,6 +12,12 @@ keyword! {
     pub KName = "name";
     /// The "cfg_attr" keyword
     pub KCfgAttr = "cfg_attr";
+    /// The "optional" keyword
+    pub KOptional = "optional";
+    /// The "strict" keyword
+    pub KStrict = "strict";
+    /// The "merge" keyword
+    pub KMerge = "merge";
     /// The "fn" keyword
     pub KFn = "fn";
     /// The "pub" keyword
@@ -50,6 +56,12 @@ keyword! {
     pub KType = "type";
     /// The "static" keyword
     pub KStatic = "static";
+    /// The "union" keyword
+    pub KUnion = "union";
+    /// The "macro_rules" keyword
+    pub KMacroRules = "macro_rules";
+    /// The "skip" keyword
+    pub KSkip = "skip";
 }
 
 operator! {
@@ -80,6 +92,12 @@ unsynn! {
         Name(NameArg),
         /// cfg_attr = "doc"
         CfgAttr(CfgAttrArg),
+        /// optional
+        Optional(KOptional),
+        /// strict
+        Strict(KStrict),
+        /// merge = "prefer-file"
+        Merge(MergeArg),
     }
 
     /// Path argument: path = "docs"
@@ -103,6 +121,27 @@ unsynn! {
         pub value: LiteralString,
     }
 
+    /// Merge argument: merge = "prefer-file"
+    pub struct MergeArg {
+        pub _merge: KMerge,
+        pub _eq: Eq,
+        pub value: LiteralString,
+    }
+
+    /// Arguments of the per-item `#[syncdoc(...)]` helper attribute
+    pub struct SyncDocHelperInner {
+        /// Comma-delimited list of arguments
+        pub args: CommaDelimitedVec<SyncDocHelperArg>,
+    }
+
+    /// Single helper attribute argument
+    pub enum SyncDocHelperArg {
+        /// skip
+        Skip(KSkip),
+        /// path = "shared/ctor.md"
+        Path(PathArg),
+    }
+
     /// Complete function signature
     #[derive(Clone)]
     pub struct FnSig {
@@ -272,12 +311,18 @@ unsynn! {
         Enum(EnumSig),
         /// A struct definition
         Struct(StructSig),
+        /// A union definition
+        Union(UnionSig),
         /// A type alias
         TypeAlias(TypeAliasSig),
         /// A constant
         Const(ConstSig),
         /// A static
         Static(StaticSig),
+        /// A declarative macro definition
+        MacroRules(MacroRulesSig),
+        /// An extern block of foreign items
+        ExternBlock(ExternBlockSig),
         /// Any other item (use, extern crate, etc.)
         Other(TokenTree),
     }
@@ -339,11 +384,13 @@ unsynn! {
         pub items: BraceGroupContaining<ModuleContent>,
     }
 
-    /// Trait method signature (no body)
+    /// Trait method or foreign function signature (no body)
     #[derive(Clone)]
     pub struct TraitMethodSig {
         /// Optional attributes
         pub attributes: Option<Many<Attribute>>,
+        /// Optional visibility (only foreign functions have one)
+        pub visibility: Option<Visibility>,
         /// Optional const modifier
         pub const_kw: Option<KConst>,
         /// Optional async modifier
@@ -411,12 +458,31 @@ unsynn! {
     pub enum StructBody {
         /// Named fields with parsed field list
         Named(BraceGroupContaining<Option<CommaDelimitedVec<StructField>>>),
-        /// Tuple fields: (Type, Type)
-        Tuple(Cons<ParenthesisGroup, Semicolon>),
+        /// Tuple fields with parsed field list: (Type, Type);
+        Tuple(Cons<ParenthesisGroupContaining<Option<CommaDelimitedVec<TupleField>>>, Semicolon>),
         /// Unit struct: ;
         Unit(Semicolon),
     }
 
+    /// union Name { ... }
+    #[derive(Clone)]
+    pub struct UnionSig {
+        /// Optional attributes
+        pub attributes: Option<Many<Attribute>>,
+        /// Optional visibility
+        pub visibility: Option<Visibility>,
+        /// "union" keyword
+        pub _union: KUnion,
+        /// Union name
+        pub name: Ident,
+        /// Optional generic parameters
+        pub generics: Option<Generics>,
+        /// Optional where clause
+        pub where_clause: Option<WhereClauses>,
+        /// Named fields, which unions always have
+        pub fields: BraceGroupContaining<Option<CommaDelimitedVec<StructField>>>,
+    }
+
     /// Named struct field: pub name: Type
     #[derive(Clone)]
     pub struct StructField {
@@ -432,7 +498,18 @@ unsynn! {
         pub field_type: VerbatimUntil<Either<Comma, BraceGroup>>,
     }
 
-    /// type Alias = Type;
+    /// Positional field of a tuple struct or variant: pub Type
+    #[derive(Clone)]
+    pub struct TupleField {
+        /// Optional attributes
+        pub attributes: Option<Many<Attribute>>,
+        /// Optional visibility
+        pub visibility: Option<Visibility>,
+        /// Field type (everything until comma or parenthesis closing)
+        pub field_type: VerbatimUntil<Comma>,
+    }
+
+    /// type Alias = Type; or an associated type: type Output: Bound;
     #[derive(Clone)]
     pub struct TypeAliasSig {
         /// Optional attributes
@@ -445,15 +522,17 @@ unsynn! {
         pub name: Ident,
         /// Optional generic parameters
         pub generics: Option<Generics>,
-        /// Equals sign
-        pub _eq: Eq,
-        /// Target type (everything until semicolon)
-        pub target: VerbatimUntil<Semicolon>,
+        /// Optional bounds, only found on associated types
+        pub bounds: Option<Cons<Colon, VerbatimUntil<Either<KWhere, Eq, Semicolon>>>>,
+        /// Optional where clause (e.g. `where Self: 'a` on a generic associated type)
+        pub where_clause: Option<Cons<KWhere, VerbatimUntil<Either<Eq, Semicolon>>>>,
+        /// Equals sign and target type, absent on associated type declarations
+        pub target: Option<Cons<Eq, VerbatimUntil<Semicolon>>>,
         /// Semicolon
         pub _semi: Semicolon,
     }
 
-    /// const NAME: Type = value;
+    /// const NAME: Type = value; or an associated const without a value
     #[derive(Clone)]
     pub struct ConstSig {
         /// Optional attributes
@@ -466,12 +545,10 @@ unsynn! {
         pub name: Ident,
         /// Colon
         pub _colon: Colon,
-        /// Type (everything until equals)
-        pub const_type: VerbatimUntil<Eq>,
-        /// Equals sign
-        pub _eq: Eq,
-        /// Value (everything until semicolon)
-        pub value: VerbatimUntil<Semicolon>,
+        /// Type (everything until equals, or semicolon for an associated const)
+        pub const_type: VerbatimUntil<Either<Eq, Semicolon>>,
+        /// Equals sign and value, absent on associated const declarations
+        pub value: Option<Cons<Eq, VerbatimUntil<Semicolon>>>,
         /// Semicolon
         pub _semi: Semicolon,
     }
@@ -491,16 +568,55 @@ unsynn! {
         pub name: Ident,
         /// Colon
         pub _colon: Colon,
-        /// Type (everything until equals)
-        pub static_type: VerbatimUntil<Eq>,
-        /// Equals sign
-        pub _eq: Eq,
-        /// Value (everything until semicolon)
-        pub value: VerbatimUntil<Semicolon>,
+        /// Type (everything until equals, or semicolon for a foreign static)
+        pub static_type: VerbatimUntil<Either<Eq, Semicolon>>,
+        /// Equals sign and value, absent on foreign statics
+        pub value: Option<Cons<Eq, VerbatimUntil<Semicolon>>>,
         /// Semicolon
         pub _semi: Semicolon,
     }
 
+    /// macro_rules! name { ... }
+    #[derive(Clone)]
+    pub struct MacroRulesSig {
+        /// Optional attributes
+        pub attributes: Option<Many<Attribute>>,
+        /// "macro_rules" keyword
+        pub _macro_rules: KMacroRules,
+        /// Bang
+        pub _bang: Bang,
+        /// Macro name
+        pub name: Ident,
+        /// Macro rules (opaque)
+        pub body: MacroRulesBody,
+    }
+
+    /// extern "C" { ... } block of foreign items
+    #[derive(Clone)]
+    pub struct ExternBlockSig {
+        /// Optional attributes
+        pub attributes: Option<Many<Attribute>>,
+        /// Optional unsafe
+        pub unsafe_kw: Option<KUnsafe>,
+        /// "extern" keyword
+        pub _extern: KExtern,
+        /// Optional ABI string
+        pub abi: Option<LiteralString>,
+        /// Parsed foreign items
+        pub items: BraceGroupContaining<ModuleContent>,
+    }
+
+    /// Macro rules in any of the three delimiters
+    #[derive(Clone)]
+    pub enum MacroRulesBody {
+        /// macro_rules! name { ... }
+        Brace(BraceGroup),
+        /// macro_rules! name ( ... );
+        Parenthesis(Cons<ParenthesisGroup, Semicolon>),
+        /// macro_rules! name [ ... ];
+        Bracket(Cons<BracketGroup, Semicolon>),
+    }
+
     /// Single enum variant
     #[derive(Clone)]
     pub struct EnumVariant {
@@ -516,7 +632,7 @@ unsynn! {
     #[derive(Clone)]
     pub enum EnumVariantData {
         /// Tuple variant: (Type, Type)
-        Tuple(ParenthesisGroup),
+        Tuple(ParenthesisGroupContaining<Option<CommaDelimitedVec<TupleField>>>),
         /// Struct variant: { field: Type }
         Struct(BraceGroupContaining<Option<CommaDelimitedVec<StructField>>>),
         /// Discriminant: = value
@@ -528,8 +644,8 @@ unsynn! {
     pub struct ModuleContent {
         /// Inner attributes at the top of the module (#![...])
         pub inner_attrs: Option<Many<InnerAttribute>>,
-        /// All items in the module
-        pub items: Many<ModuleItem>,
+        /// All items in the module, if any (`impl Marker for T {}` has none)
+        pub items: Any<ModuleItem>,
     }
 
     /// Function parameter: name: Type or self variants
@@ -541,6 +657,8 @@ unsynn! {
         Named(NamedParam),
         /// Pattern parameter: (a, b): (i32, i32)
         Pattern(PatternParam),
+        /// C variadic parameter of a foreign function: ...
+        Variadic(Ellipsis),
     }
 
     /// self, &self, &mut self, mut self
@@ -676,6 +794,11 @@ impl quote::ToTokens for TraitMethodSig {
             }
         }
 
+        // Add visibility
+        if let Some(vis) = &self.visibility {
+            quote::ToTokens::to_tokens(vis, tokens);
+        }
+
         // Add const keyword
         if let Some(const_kw) = &self.const_kw {
             unsynn::ToTokens::to_tokens(const_kw, tokens);
@@ -724,6 +847,7 @@ impl quote::ToTokens for FnParam {
             FnParam::SelfParam(self_param) => quote::ToTokens::to_tokens(self_param, tokens),
             FnParam::Named(named) => quote::ToTokens::to_tokens(named, tokens),
             FnParam::Pattern(pattern) => quote::ToTokens::to_tokens(pattern, tokens),
+            FnParam::Variadic(ellipsis) => unsynn::ToTokens::to_tokens(ellipsis, tokens),
         }
     }
 }
@@ -889,9 +1013,14 @@ impl quote::ToTokens for ModuleItem {
             ModuleItem::Trait(trait_def) => quote::ToTokens::to_tokens(trait_def, tokens),
             ModuleItem::Enum(enum_sig) => quote::ToTokens::to_tokens(enum_sig, tokens),
             ModuleItem::Struct(struct_sig) => quote::ToTokens::to_tokens(struct_sig, tokens),
+            ModuleItem::Union(union_sig) => quote::ToTokens::to_tokens(union_sig, tokens),
             ModuleItem::TypeAlias(type_alias) => quote::ToTokens::to_tokens(type_alias, tokens),
             ModuleItem::Const(const_sig) => quote::ToTokens::to_tokens(const_sig, tokens),
             ModuleItem::Static(static_sig) => quote::ToTokens::to_tokens(static_sig, tokens),
+            ModuleItem::MacroRules(macro_sig) => quote::ToTokens::to_tokens(macro_sig, tokens),
+            ModuleItem::ExternBlock(extern_block) => {
+                quote::ToTokens::to_tokens(extern_block, tokens)
+            }
             ModuleItem::Other(token_tree) => unsynn::ToTokens::to_tokens(token_tree, tokens),
         }
     }
@@ -1019,6 +1148,28 @@ impl quote::ToTokens for StructSig {
     }
 }
 
+impl quote::ToTokens for UnionSig {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        if let Some(attrs) = &self.attributes {
+            for attr in &attrs.0 {
+                unsynn::ToTokens::to_tokens(attr, tokens);
+            }
+        }
+        if let Some(vis) = &self.visibility {
+            quote::ToTokens::to_tokens(vis, tokens);
+        }
+        unsynn::ToTokens::to_tokens(&self._union, tokens);
+        quote::ToTokens::to_tokens(&self.name, tokens);
+        if let Some(generics) = &self.generics {
+            unsynn::ToTokens::to_tokens(generics, tokens);
+        }
+        if let Some(where_clause) = &self.where_clause {
+            unsynn::ToTokens::to_tokens(where_clause, tokens);
+        }
+        unsynn::ToTokens::to_tokens(&self.fields, tokens);
+    }
+}
+
 impl quote::ToTokens for StructBody {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         match self {
@@ -1045,6 +1196,20 @@ impl quote::ToTokens for StructField {
     }
 }
 
+impl quote::ToTokens for TupleField {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        if let Some(attrs) = &self.attributes {
+            for attr in &attrs.0 {
+                unsynn::ToTokens::to_tokens(attr, tokens);
+            }
+        }
+        if let Some(vis) = &self.visibility {
+            quote::ToTokens::to_tokens(vis, tokens);
+        }
+        unsynn::ToTokens::to_tokens(&self.field_type, tokens);
+    }
+}
+
 impl quote::ToTokens for TypeAliasSig {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         if let Some(attrs) = &self.attributes {
@@ -1060,8 +1225,15 @@ impl quote::ToTokens for TypeAliasSig {
         if let Some(generics) = &self.generics {
             unsynn::ToTokens::to_tokens(generics, tokens);
         }
-        unsynn::ToTokens::to_tokens(&self._eq, tokens);
-        unsynn::ToTokens::to_tokens(&self.target, tokens);
+        if let Some(bounds) = &self.bounds {
+            unsynn::ToTokens::to_tokens(bounds, tokens);
+        }
+        if let Some(where_clause) = &self.where_clause {
+            unsynn::ToTokens::to_tokens(where_clause, tokens);
+        }
+        if let Some(target) = &self.target {
+            unsynn::ToTokens::to_tokens(target, tokens);
+        }
         unsynn::ToTokens::to_tokens(&self._semi, tokens);
     }
 }
@@ -1080,8 +1252,9 @@ impl quote::ToTokens for ConstSig {
         quote::ToTokens::to_tokens(&self.name, tokens);
         unsynn::ToTokens::to_tokens(&self._colon, tokens);
         unsynn::ToTokens::to_tokens(&self.const_type, tokens);
-        unsynn::ToTokens::to_tokens(&self._eq, tokens);
-        unsynn::ToTokens::to_tokens(&self.value, tokens);
+        if let Some(value) = &self.value {
+            unsynn::ToTokens::to_tokens(value, tokens);
+        }
         unsynn::ToTokens::to_tokens(&self._semi, tokens);
     }
 }
@@ -1096,19 +1269,52 @@ impl quote::ToTokens for StaticSig {
         if let Some(vis) = &self.visibility {
             quote::ToTokens::to_tokens(vis, tokens);
         }
+        unsynn::ToTokens::to_tokens(&self._static, tokens);
         if let Some(mut_kw) = &self.mut_kw {
             unsynn::ToTokens::to_tokens(mut_kw, tokens);
         }
-        unsynn::ToTokens::to_tokens(&self._static, tokens);
         quote::ToTokens::to_tokens(&self.name, tokens);
         unsynn::ToTokens::to_tokens(&self._colon, tokens);
         unsynn::ToTokens::to_tokens(&self.static_type, tokens);
-        unsynn::ToTokens::to_tokens(&self._eq, tokens);
-        unsynn::ToTokens::to_tokens(&self.value, tokens);
+        if let Some(value) = &self.value {
+            unsynn::ToTokens::to_tokens(value, tokens);
+        }
         unsynn::ToTokens::to_tokens(&self._semi, tokens);
     }
 }
 
+impl quote::ToTokens for MacroRulesSig {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        if let Some(attrs) = &self.attributes {
+            for attr in &attrs.0 {
+                unsynn::ToTokens::to_tokens(attr, tokens);
+            }
+        }
+        unsynn::ToTokens::to_tokens(&self._macro_rules, tokens);
+        unsynn::ToTokens::to_tokens(&self._bang, tokens);
+        quote::ToTokens::to_tokens(&self.name, tokens);
+        unsynn::ToTokens::to_tokens(&self.body, tokens);
+    }
+}
+
+impl quote::ToTokens for ExternBlockSig {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        if let Some(attrs) = &self.attributes {
+            for attr in &attrs.0 {
+                unsynn::ToTokens::to_tokens(attr, tokens);
+            }
+        }
+        if let Some(unsafe_kw) = &self.unsafe_kw {
+            unsynn::ToTokens::to_tokens(unsafe_kw, tokens);
+        }
+        unsynn::ToTokens::to_tokens(&self._extern, tokens);
+        if let Some(abi) = &self.abi {
+            unsynn::ToTokens::to_tokens(abi, tokens);
+        }
+        unsynn::ToTokens::to_tokens(&self.items, tokens);
+    }
+}
+
 impl quote::ToTokens for EnumVariant {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         if let Some(attrs) = &self.attributes {
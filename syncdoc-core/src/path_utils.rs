@@ -1,4 +1,6 @@
+use crate::parse::ImplBlockSig;
 use crate::syncdoc_debug;
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
 use std::path::{Path, PathBuf};
 
 /// Find the Cargo manifest directory by walking up from a given path
@@ -14,6 +16,26 @@ pub fn find_manifest_dir(start_path: &Path) -> Option<PathBuf> {
     }
 }
 
+/// The paths of all the files under a docs directory, relative to it with `/` separators, sorted
+pub fn doc_files(docs_root: &Path) -> std::io::Result<Vec<String>> {
+    fn collect(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect(root, &path, files)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect(docs_root, docs_root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
 /// Convert a doc path to be relative to the Cargo manifest directory
 /// from the perspective of the call site file
 pub fn make_manifest_relative_path(doc_path: &str, call_site_file: &Path) -> String {
@@ -136,6 +158,14 @@ pub fn extract_module_path(source_file: &str) -> String {
     String::new()
 }
 
+/// The module's path within its crate, like `types::shapes`, or empty for the crate root
+pub fn rust_module_path(source_file: &str) -> String {
+    match extract_module_path(source_file).as_str() {
+        "lib" | "main" => String::new(),
+        module_path => module_path.replace('/', "::"),
+    }
+}
+
 pub fn apply_module_path(base_path: String) -> String {
     syncdoc_debug!("apply_module_path called:");
     syncdoc_debug!("  base_path: {}", base_path);
@@ -161,3 +191,284 @@ pub fn apply_module_path(base_path: String) -> String {
         base_path
     }
 }
+
+/// Doc path segments for the items of an impl block
+///
+/// `impl Type` gives `[Type]` and `impl Trait for Type` gives `[Type, Trait]`, with each
+/// segment named by [`type_path_segment`].
+pub fn impl_context(impl_block: &ImplBlockSig) -> Vec<String> {
+    let params = impl_block
+        .generics
+        .as_ref()
+        .map(|generics| generic_param_names(unsynn::ToTokens::to_token_stream(&generics.content)))
+        .unwrap_or_default();
+    let target = type_path_segment(
+        unsynn::ToTokens::to_token_stream(&impl_block.target_type),
+        &params,
+    );
+    match &impl_block.for_trait {
+        // In `impl Trait for Type` the tokens before `for` are the trait
+        Some(for_trait) => vec![
+            type_path_segment(
+                unsynn::ToTokens::to_token_stream(&for_trait.second),
+                &params,
+            ),
+            target,
+        ],
+        None => vec![target],
+    }
+}
+
+/// Doc path segments for the items of an impl block, given its `name = "..."` override if any
+///
+/// The override stands in for the whole [`impl_context`], so `impl Trait for Type` named
+/// `Parser` gives `[Parser]` rather than `[Type, Trait]`.
+pub fn named_impl_context(impl_block: &ImplBlockSig, name: Option<String>) -> Vec<String> {
+    match name {
+        Some(name) => vec![name],
+        None => impl_context(impl_block),
+    }
+}
+
+/// The Rust path of the items of an impl block, for `{{item.path}}`
+///
+/// `impl Type` gives the type without its generic arguments, as in `Foo::new`, and
+/// `impl Trait for Type` gives `<Type as Trait>`, as in `<Foo as From<A>>::from`, with the
+/// path of the module the impl is in (`module`) put before a type named from there.
+pub fn impl_rust_path(impl_block: &ImplBlockSig, module: &str) -> String {
+    let target = rust_text(unsynn::ToTokens::to_token_stream(&impl_block.target_type));
+    let Some(for_trait) = &impl_block.for_trait else {
+        return without_generics(&target);
+    };
+    // In `impl Trait for Type` the tokens before `for` are the trait
+    let ty = rust_text(unsynn::ToTokens::to_token_stream(&for_trait.second));
+    let is_local = ty.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && !["crate::", "self::", "super::", "dyn ", "impl ", "fn("]
+            .iter()
+            .any(|prefix| ty.starts_with(prefix))
+        && !PRIMITIVES.contains(&without_generics(&ty).as_str());
+    match module {
+        module if is_local && !module.is_empty() => format!("<{}::{} as {}>", module, ty, target),
+        _ => format!("<{} as {}>", ty, target),
+    }
+}
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+    "i128", "isize", "f32", "f64",
+];
+
+/// Writes type tokens the way they're usually written in Rust, as in `From<Vec<u8>>`
+///
+/// Words are spaced apart, commas are followed by a space, and `->`, `+` and `=` are spaced
+/// around. A `where` clause ends the type.
+fn rust_text(tokens: TokenStream) -> String {
+    let mut out = String::new();
+    push_rust_text(&tokens.into_iter().collect::<Vec<_>>(), &mut out);
+    out
+}
+
+fn push_rust_text(tokens: &[TokenTree], out: &mut String) {
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            TokenTree::Ident(ident) if ident == "where" => break,
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if out.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    out.push(' ');
+                }
+                out.push_str(&tokens[i].to_string());
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '-' if punct.spacing() == Spacing::Joint => {
+                    out.push_str(" -> ");
+                    i += 1;
+                }
+                ',' => out.push_str(", "),
+                c @ ('+' | '=') => {
+                    out.push(' ');
+                    out.push(c);
+                    out.push(' ');
+                }
+                c => out.push(c),
+            },
+            TokenTree::Group(group) => {
+                let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                push_rust_text(&inner, out);
+                out.push_str(close);
+            }
+        }
+        i += 1;
+    }
+}
+
+/// A type's name without its generic arguments, as in `Foo` for `Foo<T>`
+fn without_generics(ty: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    for c in ty.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' if depth > 0 => depth -= 1,
+            c if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Names a type or trait from an impl header as a single, filesystem-safe path segment
+///
+/// Plain names are kept as they are, so `Foo` stays `Foo`. Otherwise:
+/// - path qualifiers and lifetimes are dropped: `std::fmt::Display` -> `Display`
+/// - generic arguments go in square brackets: `From<Vec<u8>>` -> `From[Vec[u8]]`
+/// - arguments that are the impl's own parameters (`params`) become `_`, and brackets
+///   holding nothing else are dropped: `impl<T> Foo<T, u8>` -> `Foo[_,u8]`, `impl<T> Foo<T>` -> `Foo`
+/// - adjacent words are joined with `-`: `&mut T` -> `&mut-T`, `dyn Error` -> `dyn-Error`
+/// - raw pointers are spelled out: `*const T` -> `ptr-const-T`
+///
+/// Tuples, slices and arrays keep their own brackets, and a `where` clause ends the name.
+pub fn type_path_segment(tokens: TokenStream, params: &[String]) -> String {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut segment = String::new();
+    push_type_tokens(&tokens, params, &mut segment);
+    if segment.is_empty() {
+        "Unknown".to_string()
+    } else {
+        segment
+    }
+}
+
+fn push_type_tokens(tokens: &[TokenTree], params: &[String], out: &mut String) {
+    // Where each open generic argument list starts in `out`
+    let mut generic_starts = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            TokenTree::Ident(ident) if ident == "where" => break,
+            TokenTree::Ident(ident) => {
+                // Only the last segment of a path is kept
+                if !is_path_sep(&tokens[i + 1..]) {
+                    let name = ident.to_string();
+                    if out.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                        out.push('-');
+                    }
+                    if !generic_starts.is_empty() && params.contains(&name) {
+                        out.push('_');
+                    } else {
+                        out.push_str(&name);
+                    }
+                }
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                // Lifetimes carry nothing that tells two impls apart
+                '\'' => {
+                    i += 1;
+                    if matches!(tokens.get(i + 1), Some(TokenTree::Punct(p)) if p.as_char() == ',')
+                    {
+                        i += 1;
+                    }
+                }
+                ':' => {}
+                '<' => {
+                    generic_starts.push(out.len());
+                    out.push('[');
+                }
+                '>' => {
+                    trim_dangling(out);
+                    let start = generic_starts.pop().unwrap_or(out.len());
+                    // `Foo<'a>` and `impl<T> Foo<T>` have nothing left to tell them apart
+                    if out[start..].chars().all(|c| matches!(c, '[' | '_' | ',')) {
+                        out.truncate(start);
+                    } else {
+                        out.push(']');
+                    }
+                }
+                '-' if punct.spacing() == Spacing::Joint => {
+                    // The `->` of a function pointer's return type
+                    out.push_str("-to-");
+                    i += 1;
+                }
+                '*' => out.push_str("ptr-"),
+                c @ ('&' | '!' | '=' | '+' | ',' | ';') => out.push(c),
+                _ => out.push('_'),
+            },
+            TokenTree::Group(group) => {
+                let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ('(', ')'),
+                    Delimiter::Bracket => ('[', ']'),
+                    Delimiter::Brace => ('{', '}'),
+                    Delimiter::None => {
+                        push_type_tokens(&inner, params, out);
+                        i += 1;
+                        continue;
+                    }
+                };
+                out.push(open);
+                push_type_tokens(&inner, params, out);
+                trim_dangling(out);
+                out.push(close);
+            }
+            TokenTree::Literal(literal) => out.push_str(&literal.to_string()),
+        }
+        i += 1;
+    }
+    trim_dangling(out);
+}
+
+/// Names of the type and const parameters declared between an impl's `<` and `>`
+fn generic_param_names(content: TokenStream) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    let mut at_param_start = true;
+    let mut tokens = content.into_iter();
+    while let Some(token) = tokens.next() {
+        match &token {
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    at_param_start = true;
+                    continue;
+                }
+                // A lifetime parameter
+                '\'' if at_param_start => {
+                    tokens.next();
+                }
+                _ => {}
+            },
+            TokenTree::Ident(ident) if at_param_start && ident == "const" => continue,
+            TokenTree::Ident(ident) if at_param_start => names.push(ident.to_string()),
+            _ => {}
+        }
+        at_param_start = false;
+    }
+    names
+}
+
+fn is_path_sep(tokens: &[TokenTree]) -> bool {
+    matches!(
+        tokens,
+        [TokenTree::Punct(first), TokenTree::Punct(second), ..]
+            if first.as_char() == ':' && first.spacing() == Spacing::Joint && second.as_char() == ':'
+    )
+}
+
+/// Drops separators left hanging by a dropped lifetime, as in `Foo<T, 'a>` or `dyn Trait + 'a`
+fn trim_dangling(out: &mut String) {
+    while out.ends_with([',', '+']) {
+        out.pop();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/path_utils.rs"]
+mod tests;
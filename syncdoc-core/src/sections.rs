@@ -0,0 +1,136 @@
+//! The sections layout, where a module's docs are one markdown file with a heading per item
+//!
+//! In `docs/types.md`, a heading made of a single code span, like ``## `Config` `` or
+//! ``### `Config::port` ``, starts the docs for that item, which run until the next such
+//! heading. Anything before the first is the module's own documentation. Lines inside code
+//! fences are never headings, so `# ` lines hidden from doctests are left alone.
+
+/// A sections file split into the module's own docs and its items' docs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sections {
+    /// The module's own documentation, before the first item heading
+    pub preamble: String,
+    /// Each item's key (such as `Config::port`) and docs, in file order
+    pub items: Vec<(String, String)>,
+}
+
+impl Sections {
+    pub fn parse(markdown: &str) -> Self {
+        let mut sections = Sections::default();
+        let mut current: Vec<&str> = Vec::new();
+        let mut key: Option<String> = None;
+        let mut fence: Option<&str> = None;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim_start();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+            } else if let Some(next_key) = item_heading(line) {
+                sections.push(key.take(), &current);
+                current.clear();
+                key = Some(next_key.to_string());
+                continue;
+            }
+            current.push(line);
+        }
+        sections.push(key, &current);
+        sections
+    }
+
+    fn push(&mut self, key: Option<String>, lines: &[&str]) {
+        let content = trim_blank_lines(lines);
+        match key {
+            Some(key) => self.items.push((key, content)),
+            None => self.preamble = content,
+        }
+    }
+
+    /// The docs under an item's heading, if it has one
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|(item_key, _)| item_key == key)
+            .map(|(_, content)| content.as_str())
+    }
+
+    /// Replaces an item's docs, or adds a heading for it at the end
+    pub fn set(&mut self, key: &str, content: &str) {
+        let content = trim_blank_lines(&content.lines().collect::<Vec<_>>());
+        match self.items.iter_mut().find(|(item_key, _)| item_key == key) {
+            Some((_, existing)) => *existing = content,
+            None => self.items.push((key.to_string(), content)),
+        }
+    }
+
+    /// Writes the file back out, with each heading's level following its key's depth
+    pub fn render(&self) -> String {
+        let mut blocks = Vec::new();
+        if !self.preamble.is_empty() {
+            blocks.push(self.preamble.clone());
+        }
+        for (key, content) in &self.items {
+            let heading = format!("{} `{}`", "#".repeat(heading_level(key)), key);
+            if content.is_empty() {
+                blocks.push(heading);
+            } else {
+                blocks.push(format!("{}\n\n{}", heading, content));
+            }
+        }
+        let mut output = blocks.join("\n\n");
+        output.push('\n');
+        output
+    }
+}
+
+/// The sections file and key for an item's doc path, if it is under the module's `base` path
+///
+/// `docs/types/Config/port.md` under `docs/types` is `Config::port` in `docs/types.md`.
+pub fn section_of(doc_path: &str, base: &str) -> Option<(String, String)> {
+    let relative = doc_path
+        .strip_prefix(base)?
+        .strip_prefix('/')?
+        .strip_suffix(".md")?;
+    Some((format!("{}.md", base), relative.replace('/', "::")))
+}
+
+/// The doc path an item's section is kept at in the per-item layout, the inverse of [`section_of`]
+pub fn item_path(base: &str, key: &str) -> String {
+    format!("{}/{}.md", base, key.replace("::", "/"))
+}
+
+/// `##` for an item in the module, one more `#` for each level of nesting below it
+fn heading_level(key: &str) -> usize {
+    (key.matches("::").count() + 2).min(6)
+}
+
+/// The key of an item heading, which is only a code span after the `#`s
+fn item_heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    if text.len() == line.len() || line.len() - text.len() > 6 {
+        return None;
+    }
+    let key = text
+        .strip_prefix(' ')?
+        .trim()
+        .strip_prefix('`')?
+        .strip_suffix('`')?;
+    (!key.is_empty() && !key.contains('`')).then_some(key)
+}
+
+/// Joins lines, dropping blank lines from the start and end
+fn trim_blank_lines(lines: &[&str]) -> String {
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/sections.rs"]
+mod sections_tests;
@@ -0,0 +1,92 @@
+//! Snippet directives, for showing real example code in docs
+//!
+//! A `<!-- syncdoc:snippet examples/client.rs#connect -->` comment in a doc file is replaced by a
+//! fenced Rust block of the lines between `// ANCHOR: connect` and `// ANCHOR_END: connect` in
+//! that file, as mdBook's anchors work. Without an `#anchor` the whole file is shown. Paths are
+//! relative to the crate's manifest directory, so examples and tests can be quoted directly.
+
+use crate::include::directives;
+
+/// Whether the markdown has any snippet directives
+pub fn has_snippets(markdown: &str) -> bool {
+    directives(markdown, "syncdoc:snippet").next().is_some()
+}
+
+/// The files the markdown's snippet directives quote, in order, without their anchors
+pub fn snippet_files(markdown: &str) -> Vec<&str> {
+    directives(markdown, "syncdoc:snippet")
+        .map(|(_, target)| target.split_once('#').map_or(target, |(path, _)| path))
+        .collect()
+}
+
+/// Replaces each snippet directive with the code it names, as a fenced Rust block
+///
+/// `read` loads a file by its path relative to the manifest directory, or gives `None` if it
+/// doesn't exist. A missing file or anchor is an error.
+pub fn resolve_snippets(
+    markdown: &str,
+    read: &mut impl FnMut(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for (range, target) in directives(markdown, "syncdoc:snippet") {
+        let (path, anchor) = match target.split_once('#') {
+            Some((path, anchor)) => (path, Some(anchor)),
+            None => (target, None),
+        };
+        let source = read(path).ok_or_else(|| format!("missing snippet file `{}`", path))?;
+        let code = match anchor {
+            Some(anchor) => anchor_region(&source, anchor)
+                .ok_or_else(|| format!("no `// ANCHOR: {}` in `{}`", anchor, path))?,
+            None => without_anchors(source.lines()),
+        };
+
+        output.push_str(&markdown[rest..range.start]);
+        output.push_str("```rust\n");
+        output.push_str(&code);
+        output.push_str("\n```");
+        rest = range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    Ok(output)
+}
+
+/// The lines between an anchor's start and end comments, dedented
+///
+/// An anchor without an end runs to the end of the file. Other anchors' comments inside it are
+/// left out.
+fn anchor_region(source: &str, anchor: &str) -> Option<String> {
+    let mut lines = source.lines();
+    lines.find(|line| anchor_comment(line) == Some(("ANCHOR", anchor)))?;
+    let region = lines.take_while(|line| anchor_comment(line) != Some(("ANCHOR_END", anchor)));
+    Some(without_anchors(region))
+}
+
+/// Joins lines, dropping anchor comments and the indentation they all share
+fn without_anchors<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let lines: Vec<&str> = lines
+        .filter(|line| anchor_comment(line).is_none())
+        .collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect();
+    dedented.join("\n").trim_matches('\n').to_string()
+}
+
+/// The kind (`ANCHOR` or `ANCHOR_END`) and name of an anchor comment line
+fn anchor_comment(line: &str) -> Option<(&str, &str)> {
+    let comment = line.trim().strip_prefix("//")?.trim();
+    let (kind, name) = comment.split_once(':')?;
+    matches!(kind, "ANCHOR" | "ANCHOR_END").then(|| (kind, name.trim()))
+}
+
+#[cfg(test)]
+#[path = "tests/snippet.rs"]
+mod snippet_tests;
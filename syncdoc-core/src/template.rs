@@ -0,0 +1,98 @@
+//! Placeholders in doc files, filled in when the docs are injected
+//!
+//! `{{crate.name}}`, `{{crate.version}}`, `{{item.name}}`, `{{item.path}}` and `{{env.NAME}}`
+//! are replaced by their values, so install snippets and version pins don't go stale. Double
+//! braces around anything else, like the `{{}}` of a `format!` in an example, are left alone.
+
+/// The values placeholders are replaced with, apart from environment variables
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplateVars {
+    /// The `name` from the crate's manifest
+    pub crate_name: String,
+    /// The `version` from the crate's manifest
+    pub crate_version: String,
+    /// The item's own name, such as `port`
+    pub item_name: String,
+    /// The item's path within the crate, such as `types::Config::port`
+    pub item_path: String,
+}
+
+/// Whether the markdown has any placeholders to fill in
+pub fn has_placeholders(markdown: &str) -> bool {
+    placeholders(markdown).next().is_some()
+}
+
+/// The environment variables the markdown's placeholders read
+pub fn env_vars(markdown: &str) -> Vec<String> {
+    placeholders(markdown)
+        .filter_map(|(_, (namespace, key))| (namespace == "env").then(|| key.to_string()))
+        .collect()
+}
+
+/// Replaces the markdown's placeholders with their values
+///
+/// A placeholder for an unknown key, or an environment variable that isn't set, is an error.
+pub fn expand(markdown: &str, vars: &TemplateVars) -> Result<String, String> {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = 0;
+    for (range, (namespace, key)) in placeholders(markdown) {
+        let value = match (namespace, key) {
+            ("crate", "name") => vars.crate_name.clone(),
+            ("crate", "version") => vars.crate_version.clone(),
+            ("item", "name") => vars.item_name.clone(),
+            ("item", "path") => vars.item_path.clone(),
+            ("env", name) => std::env::var(name).map_err(|_| {
+                format!(
+                    "environment variable `{}` is not set, for `{}`",
+                    name, &markdown[range.clone()]
+                )
+            })?,
+            _ => {
+                return Err(format!(
+                    "unknown placeholder `{}`, expected crate.name, crate.version, item.name, item.path or env.NAME",
+                    &markdown[range]
+                ))
+            }
+        };
+        output.push_str(&markdown[rest..range.start]);
+        output.push_str(&value);
+        rest = range.end;
+    }
+    output.push_str(&markdown[rest..]);
+    Ok(output)
+}
+
+/// Each `{{namespace.key}}` in the markdown, with where it is
+///
+/// Only the `crate`, `item` and `env` namespaces are placeholders, and spaces inside the braces
+/// are allowed.
+fn placeholders(
+    markdown: &str,
+) -> impl Iterator<Item = (std::ops::Range<usize>, (&str, &str))> + '_ {
+    let mut offset = 0;
+    std::iter::from_fn(move || loop {
+        let start = offset + markdown[offset..].find("{{")?;
+        let end = match markdown[start + 2..].find("}}") {
+            Some(len) => start + 2 + len + 2,
+            None => return None,
+        };
+        let inner = markdown[start + 2..end - 2].trim();
+        let parsed = inner.split_once('.').filter(|(namespace, key)| {
+            matches!(*namespace, "crate" | "item" | "env")
+                && !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        match parsed {
+            Some(parsed) => {
+                offset = end;
+                return Some((start..end, parsed));
+            }
+            // Not a placeholder, so look again from just after its opening brace
+            None => offset = start + 1,
+        }
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/template.rs"]
+mod template_tests;
@@ -0,0 +1,36 @@
+use super::*;
+
+const ALERTS: &str = "Frees the buffer.\n\n> [!WARNING]\n> The pointer must not be used after.\n>\n> Nor freed twice.\n\n> [!note]\n> Any length works.\n";
+
+#[test]
+fn test_alerts_are_found_outside_code_blocks() {
+    assert!(has_alerts(ALERTS));
+    assert!(!has_alerts("> A plain quote\n> [!WARNING]\n"));
+    assert!(!has_alerts(
+        "```markdown\n> [!WARNING]\n> Shown as is.\n```\n"
+    ));
+    assert!(!has_alerts("> [!DANGER]\n> Not a kind GitHub has.\n"));
+}
+
+#[test]
+fn test_rustdoc_style_uses_the_warning_block() {
+    assert_eq!(
+        render_alerts(ALERTS, &Admonitions::Rustdoc),
+        "Frees the buffer.\n\n<div class=\"warning\">\n\n**Warning**\n\n\
+        The pointer must not be used after.\n\nNor freed twice.\n\n</div>\n\n\
+        > **Note**\n>\n> Any length works.\n"
+    );
+}
+
+#[test]
+fn test_template_style_fills_in_each_alert() {
+    let template = Admonitions::Template(
+        "<div class=\"alert {kind}\" title=\"{title}\">{body}</div>".to_string(),
+    );
+
+    assert_eq!(
+        render_alerts("> [!TIP]\n> Reuse the buffer.\nAfter.\n", &template),
+        "<div class=\"alert tip\" title=\"Tip\">\n\nReuse the buffer.\n\n</div>\nAfter.\n"
+    );
+    assert_eq!(render_alerts(ALERTS, &Admonitions::Keep), ALERTS);
+}
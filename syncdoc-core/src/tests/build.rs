@@ -0,0 +1,103 @@
+use super::*;
+use tempfile::TempDir;
+
+/// A crate whose manifest has `syncdoc` as its syncdoc config
+fn manifest_dir(syncdoc: &str) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    std::fs::write(
+        temp.path().join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[package.metadata.syncdoc]\n{}",
+            syncdoc
+        ),
+    )
+    .unwrap();
+    temp
+}
+
+#[test]
+fn test_docs_path_is_tracked() {
+    let temp = manifest_dir("docs-path = \"docs\"\n");
+
+    assert_eq!(
+        directives(temp.path(), None).unwrap(),
+        [
+            "cargo:rerun-if-changed=Cargo.toml",
+            "cargo:rerun-if-changed=docs",
+        ]
+    );
+}
+
+#[test]
+fn test_every_locale_is_tracked_and_declared() {
+    let temp = manifest_dir("docs-path = \"docs/{locale}\"\nlocales = [\"en\", \"ja\"]\n");
+
+    assert_eq!(
+        directives(temp.path(), None).unwrap(),
+        [
+            "cargo:rerun-if-changed=Cargo.toml",
+            "cargo:rustc-check-cfg=cfg(syncdoc_locale, values(\"en\", \"ja\"))",
+            "cargo:rerun-if-env-changed=SYNCDOC_LOCALE",
+            "cargo:rerun-if-changed=docs/en",
+            "cargo:rerun-if-changed=docs/ja",
+        ]
+    );
+}
+
+#[test]
+fn test_the_picked_locale_is_passed_to_the_compiler() {
+    let temp = manifest_dir("docs-path = \"docs/{locale}\"\nlocales = [\"en\", \"ja\"]\n");
+
+    let ja = directives(temp.path(), Some("ja")).unwrap();
+    assert!(ja.contains(&"cargo:rustc-cfg=syncdoc_locale=\"ja\"".to_string()));
+    assert!(ja.contains(&"cargo:rustc-env=SYNCDOC_ACTIVE_LOCALE=ja".to_string()));
+
+    // The default locale's docs need no cfg
+    let en = directives(temp.path(), Some("en")).unwrap();
+    assert!(!en.iter().any(|line| line.starts_with("cargo:rustc-cfg=")));
+    assert!(en.contains(&"cargo:rustc-env=SYNCDOC_ACTIVE_LOCALE=en".to_string()));
+
+    assert_eq!(
+        directives(temp.path(), Some("")).unwrap(),
+        directives(temp.path(), None).unwrap()
+    );
+
+    let error = directives(temp.path(), Some("fr")).unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "unknown locale `fr` in SYNCDOC_LOCALE, expected one of en, ja"
+    );
+}
+
+#[test]
+fn test_snippets_images_outside_the_docs_and_variables_are_tracked() {
+    let temp = manifest_dir("docs-path = \"docs\"\nembed-images = true\n");
+    let api = temp.path().join("docs/lib/api");
+    std::fs::create_dir_all(&api).unwrap();
+    std::fs::write(
+        api.join("run.md"),
+        "<!-- syncdoc:snippet examples/client.rs#connect -->\n\n\
+        ![inside](img/run.png) ![outside](../../../assets/logo.png)\n\n\
+        Built for {{env.TARGET_BOARD}}.\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        directives(temp.path(), None).unwrap(),
+        [
+            "cargo:rerun-if-changed=Cargo.toml",
+            "cargo:rerun-if-changed=docs",
+            "cargo:rerun-if-changed=assets/logo.png",
+            "cargo:rerun-if-changed=examples/client.rs",
+            "cargo:rerun-if-env-changed=TARGET_BOARD",
+        ]
+    );
+}
+
+#[test]
+fn test_no_docs_path_is_an_error() {
+    let temp = manifest_dir("missing = \"warn\"\n");
+
+    let error = directives(temp.path(), None).unwrap_err().to_string();
+    assert!(error.contains("has no docs-path"), "{}", error);
+}
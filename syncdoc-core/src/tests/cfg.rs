@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn test_parse_nested_predicates() {
+    let predicate: CfgPredicate = "all(doc, not(test), any(docsrs, feature = \"serde\"))"
+        .parse()
+        .unwrap();
+
+    assert_eq!(
+        predicate,
+        CfgPredicate::All(vec![
+            CfgPredicate::Option("doc".to_string()),
+            CfgPredicate::Not(Box::new(CfgPredicate::Option("test".to_string()))),
+            CfgPredicate::Any(vec![
+                CfgPredicate::Option("docsrs".to_string()),
+                CfgPredicate::KeyValue("feature".to_string(), "serde".to_string()),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn test_display_and_tokens_match_what_was_written() {
+    for written in [
+        "doc",
+        "all(doc, not(test))",
+        "any(docsrs, feature = \"serde\")",
+    ] {
+        let predicate: CfgPredicate = written.parse().unwrap();
+
+        assert_eq!(predicate.to_string(), written);
+        let reparsed = CfgPredicate::parse(predicate.to_token_stream()).unwrap();
+        assert_eq!(reparsed, predicate);
+    }
+}
+
+#[test]
+fn test_malformed_predicates_say_what_is_wrong() {
+    let error = |s: &str| s.parse::<CfgPredicate>().unwrap_err();
+
+    assert_eq!(
+        error("all(doc"),
+        "`all(doc` isn't a cfg predicate, its brackets or quotes don't match"
+    );
+    assert_eq!(
+        error("all(doc,, test)"),
+        "`all(doc,, test)` isn't a cfg predicate: expected a cfg predicate between commas"
+    );
+    assert_eq!(
+        error("not(doc, test)"),
+        "`not(doc, test)` isn't a cfg predicate: `not(...)` takes exactly one predicate"
+    );
+    assert_eq!(
+        error("both(doc, test)"),
+        "`both(doc, test)` isn't a cfg predicate: expected `all`, `any` or `not`, found `both(...)`"
+    );
+    assert_eq!(
+        error("feature = serde"),
+        "`feature = serde` isn't a cfg predicate: expected a string after `feature =`, found `serde`"
+    );
+    assert_eq!(
+        error("doc test"),
+        "`doc test` isn't a cfg predicate: expected a cfg option, like `doc` or \
+        `feature = \"serde\"`, found `test`"
+    );
+    assert_eq!(
+        error("doc, test"),
+        "`doc, test` isn't a cfg predicate: expected one cfg predicate, combine them with \
+        `all(...)` or `any(...)`"
+    );
+    assert_eq!(
+        error(""),
+        "`` isn't a cfg predicate: expected a cfg predicate, like `doc` or `all(doc, not(test))`"
+    );
+}
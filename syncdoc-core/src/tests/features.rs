@@ -0,0 +1,51 @@
+use super::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_feature_doc_path_goes_beside_the_doc_file() {
+    assert_eq!(
+        feature_doc_path("docs/lib/Config.md", "serde"),
+        "docs/lib/Config.feature-serde.md"
+    );
+}
+
+#[test]
+fn test_feature_of_companions_only() {
+    assert_eq!(
+        feature_of(Path::new("docs/lib/Config.feature-serde.md")).as_deref(),
+        Some("serde")
+    );
+    assert_eq!(feature_of(Path::new("docs/lib/Config.md")), None);
+    assert_eq!(feature_of(Path::new("docs/lib/Config.feature-.md")), None);
+}
+
+#[test]
+fn test_find_feature_docs_lists_companions_by_feature() {
+    let temp = TempDir::new().unwrap();
+    for file in [
+        "Config.md",
+        "Config.feature-tokio.md",
+        "Config.feature-serde.md",
+        "ConfigBuilder.feature-serde.md",
+        "Config.feature-serde.png",
+    ] {
+        fs::write(temp.path().join(file), "").unwrap();
+    }
+
+    let companions = find_feature_docs(&temp.path().join("Config.md"));
+
+    assert_eq!(
+        companions,
+        [
+            (
+                "serde".to_string(),
+                temp.path().join("Config.feature-serde.md")
+            ),
+            (
+                "tokio".to_string(),
+                temp.path().join("Config.feature-tokio.md")
+            ),
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+use super::*;
+
+#[test]
+fn test_yaml_front_matter_is_split_off() {
+    let markdown = "---\ndeprecated: \"use bar\"\nsince: 1.4\naliases: [fetch, \"get\"]\nhidden: true\n---\n\nFetches it.\n";
+    let (front_matter, body) = FrontMatter::parse(markdown).unwrap();
+
+    assert_eq!(
+        front_matter,
+        FrontMatter {
+            deprecated: true,
+            note: Some("use bar".to_string()),
+            since: Some("1.4".to_string()),
+            aliases: vec!["fetch".to_string(), "get".to_string()],
+            hidden: true,
+        }
+    );
+    assert_eq!(body, "Fetches it.\n");
+}
+
+#[test]
+fn test_toml_front_matter_and_block_lists() {
+    let (toml, _) =
+        FrontMatter::parse("+++\ndeprecated = true\naliases = [\"fetch\"]\n+++\nDocs\n").unwrap();
+    assert!(toml.deprecated);
+    assert_eq!(toml.note, None);
+    assert_eq!(toml.aliases, ["fetch"]);
+
+    let (yaml, _) = FrontMatter::parse("---\naliases:\n  - fetch\n  - get\n---\nDocs\n").unwrap();
+    assert_eq!(yaml.aliases, ["fetch", "get"]);
+}
+
+#[test]
+fn test_markdown_without_front_matter_is_unchanged() {
+    for markdown in ["Docs\n\n---\n\nMore\n", "---\nnot closed\n", ""] {
+        let (front_matter, body) = FrontMatter::parse(markdown).unwrap();
+        assert!(front_matter.is_empty());
+        assert_eq!(body, markdown);
+    }
+}
+
+#[test]
+fn test_invalid_front_matter_is_an_error() {
+    let err = FrontMatter::parse("---\nalias: fetch\n---\n").unwrap_err();
+    assert!(err.contains("unknown front matter key `alias`"), "{}", err);
+
+    let err = FrontMatter::parse("---\nsince: 1.4\n---\n").unwrap_err();
+    assert!(err.contains("needs `deprecated`"), "{}", err);
+}
+
+#[test]
+fn test_attributes_and_render_round_trip() {
+    let front_matter = FrontMatter {
+        deprecated: true,
+        note: Some("use \"bar\"".to_string()),
+        since: Some("1.4".to_string()),
+        aliases: vec!["fetch".to_string()],
+        hidden: false,
+    };
+
+    assert_eq!(
+        front_matter.attributes(false).to_string(),
+        "# [deprecated (since = \"1.4\" , note = \"use \\\"bar\\\"\")] # [doc (alias = \"fetch\")]"
+    );
+    assert_eq!(
+        front_matter.attributes(true).to_string(),
+        "# [doc (alias = \"fetch\")]"
+    );
+
+    let rendered = front_matter.render();
+    assert_eq!(
+        rendered,
+        "---\ndeprecated: \"use \\\"bar\\\"\"\nsince: \"1.4\"\naliases: [\"fetch\"]\n---\n"
+    );
+    assert_eq!(FrontMatter::parse(&rendered).unwrap().0, front_matter);
+}
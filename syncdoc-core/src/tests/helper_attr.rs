@@ -0,0 +1,73 @@
+use super::*;
+use quote::quote;
+
+fn parse_attrs(tokens: TokenStream) -> Option<Many<Attribute>> {
+    tokens.into_token_iter().parse::<Many<Attribute>>().ok()
+}
+
+#[test]
+fn test_take_skip_directive_strips_attr() {
+    let mut attrs = parse_attrs(quote! {
+        #[cfg(test)]
+        #[syncdoc(skip)]
+    });
+
+    let directive = take_item_directive(&mut attrs).unwrap();
+    assert_eq!(directive, Some(ItemDirective::Skip));
+
+    let mut remaining = TokenStream::new();
+    unsynn::ToTokens::to_tokens(&attrs, &mut remaining);
+    assert_eq!(remaining.to_string(), "# [cfg (test)]");
+}
+
+#[test]
+fn test_take_path_directive() {
+    let mut attrs = parse_attrs(quote! {
+        #[syncdoc(path = "shared/ctor.md")]
+    });
+
+    let directive = take_item_directive(&mut attrs).unwrap();
+    assert_eq!(
+        directive,
+        Some(ItemDirective::Path("shared/ctor.md".to_string()))
+    );
+    assert!(attrs.is_none());
+}
+
+#[test]
+fn test_omnidoc_attr_is_not_a_helper() {
+    let mut attrs = parse_attrs(quote! {
+        #[syncdoc::omnidoc(path = "docs")]
+    });
+
+    assert_eq!(take_item_directive(&mut attrs).unwrap(), None);
+    assert!(attrs.is_some());
+}
+
+#[test]
+fn test_malformed_helper_is_an_error() {
+    let mut attrs = parse_attrs(quote! {
+        #[syncdoc(skp)]
+    });
+    let error = take_item_directive(&mut attrs).unwrap_err();
+    assert!(error.message.contains("expected `#[syncdoc(skip)]`"));
+
+    let mut attrs = parse_attrs(quote! {
+        #[syncdoc(skip, path = "a.md")]
+    });
+    let error = take_item_directive(&mut attrs).unwrap_err();
+    assert!(error.message.contains("cannot be combined"));
+}
+
+#[test]
+fn test_find_item_directive_leaves_attrs() {
+    let attrs = parse_attrs(quote! {
+        #[syncdoc(path = "shared/ctor.md")]
+    });
+
+    assert_eq!(
+        find_item_directive(&attrs),
+        Some(ItemDirective::Path("shared/ctor.md".to_string()))
+    );
+    assert!(attrs.is_some());
+}
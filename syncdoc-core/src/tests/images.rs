@@ -0,0 +1,64 @@
+use super::*;
+
+fn read(path: &str) -> Option<Vec<u8>> {
+    match path {
+        "img/dot.png" => Some(b"PNG".to_vec()),
+        "../shared/logo.svg" => Some(b"<svg/>".to_vec()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_local_images_are_found_outside_code_blocks() {
+    let markdown = "![diagram](img/dot.png \"The design\") and ![logo](../shared/logo.svg)\n\n\
+        ![remote](https://example.com/a.png) ![inline](data:image/png;base64,AA==)\n\
+        ```markdown\n![example](img/example.png)\n```\n";
+
+    assert!(has_local_images(markdown));
+    assert_eq!(
+        local_images(markdown),
+        ["img/dot.png", "../shared/logo.svg"]
+    );
+    assert!(!has_local_images("[a link](img/dot.png)"));
+}
+
+#[test]
+fn test_images_become_data_uris() {
+    assert_eq!(
+        embed_images(
+            "See ![diagram](img/dot.png \"The design\").\n",
+            100,
+            &mut read
+        )
+        .unwrap(),
+        "See ![diagram](data:image/png;base64,UE5H \"The design\").\n"
+    );
+    assert_eq!(
+        embed_images("![logo](../shared/logo.svg)", 100, &mut read).unwrap(),
+        "![logo](data:image/svg+xml;base64,PHN2Zy8+)"
+    );
+}
+
+#[test]
+fn test_missing_large_or_unknown_images_are_errors() {
+    assert_eq!(
+        embed_images("![gone](img/gone.png)", 100, &mut read).unwrap_err(),
+        "missing image `img/gone.png`"
+    );
+    assert_eq!(
+        embed_images("![diagram](img/dot.png)", 2, &mut read).unwrap_err(),
+        "image `img/dot.png` is 3 bytes, over the max-image-size of 2"
+    );
+    assert_eq!(
+        embed_images("![doc](img/spec.pdf)", 100, &mut read).unwrap_err(),
+        "unknown image type for `img/spec.pdf`, expected png, jpg, gif, svg, webp or avif"
+    );
+}
+
+#[test]
+fn test_base64_pads_the_last_group() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+}
@@ -0,0 +1,84 @@
+use super::*;
+use std::collections::HashMap;
+
+fn fragments(files: &[(&str, &str)]) -> impl FnMut(&str) -> Option<String> {
+    let files: HashMap<String, String> = files
+        .iter()
+        .map(|(path, content)| (path.to_string(), content.to_string()))
+        .collect();
+    move |path| files.get(path).cloned()
+}
+
+#[test]
+fn test_directives_are_found_with_their_paths() {
+    let markdown = "Frees it.\n\n<!-- syncdoc:include shared/safety.md -->\n\n\
+        <!-- a plain comment -->\n<!--syncdoc:include  shared/errors.md-->\n";
+
+    assert!(has_includes(markdown));
+    assert_eq!(includes(markdown), ["shared/safety.md", "shared/errors.md"]);
+    assert!(!has_includes("<!-- syncdoc:includes nothing -->"));
+}
+
+#[test]
+fn test_directives_in_fenced_code_are_skipped() {
+    let markdown = "Shares a fragment:\n\n```markdown\n<!-- syncdoc:include shared/safety.md -->\n```\n\n\
+        ~~~\n<!-- syncdoc:include shared/tilde.md -->\n~~~\n<!-- syncdoc:include shared/errors.md -->\n";
+
+    assert_eq!(includes(markdown), ["shared/errors.md"]);
+    assert_eq!(
+        resolve_includes(
+            markdown,
+            &mut fragments(&[("shared/errors.md", "# Errors\n")])
+        )
+        .unwrap(),
+        "Shares a fragment:\n\n```markdown\n<!-- syncdoc:include shared/safety.md -->\n```\n\n\
+        ~~~\n<!-- syncdoc:include shared/tilde.md -->\n~~~\n# Errors\n"
+    );
+    assert!(!has_includes(
+        "```\n<!-- syncdoc:include shared/safety.md -->\n"
+    ));
+}
+
+#[test]
+fn test_resolve_replaces_directives_recursively() {
+    let mut read = fragments(&[
+        (
+            "shared/safety.md",
+            "# Safety\n\nThe pointer must be valid.\n<!-- syncdoc:include shared/aligned.md -->\n",
+        ),
+        ("shared/aligned.md", "It must also be aligned.\n"),
+    ]);
+    let markdown = "Reads the value.\n\n<!-- syncdoc:include shared/safety.md -->\n";
+
+    assert_eq!(
+        resolve_includes(markdown, &mut read).unwrap(),
+        "Reads the value.\n\n# Safety\n\nThe pointer must be valid.\nIt must also be aligned.\n"
+    );
+}
+
+#[test]
+fn test_missing_include_names_the_fragment() {
+    let mut read = fragments(&[("shared/a.md", "<!-- syncdoc:include shared/b.md -->")]);
+
+    assert_eq!(
+        resolve_includes("<!-- syncdoc:include shared/c.md -->", &mut read).unwrap_err(),
+        "missing include `shared/c.md`"
+    );
+    assert_eq!(
+        resolve_includes("<!-- syncdoc:include shared/a.md -->", &mut read).unwrap_err(),
+        "missing include `shared/b.md` (from `shared/a.md`)"
+    );
+}
+
+#[test]
+fn test_cycles_are_an_error() {
+    let mut read = fragments(&[
+        ("shared/a.md", "<!-- syncdoc:include shared/b.md -->"),
+        ("shared/b.md", "<!-- syncdoc:include shared/a.md -->"),
+    ]);
+
+    assert_eq!(
+        resolve_includes("<!-- syncdoc:include shared/a.md -->", &mut read).unwrap_err(),
+        "include cycle shared/a.md -> shared/b.md -> shared/a.md"
+    );
+}
@@ -0,0 +1,88 @@
+use super::*;
+use tempfile::TempDir;
+
+/// A crate with a root module, a module file and a `main.rs`, and its items
+fn crate_items() -> (TempDir, DocItems) {
+    let temp = TempDir::new().unwrap();
+    let src = temp.path().join("src");
+    std::fs::create_dir_all(src.join("net")).unwrap();
+    std::fs::write(
+        src.join("lib.rs"),
+        "pub mod api {\n    pub fn run() {}\n    pub fn stop() {}\n}\n\n\
+        pub mod types {\n    pub struct Config {\n        pub port: u16,\n    }\n\n    \
+        pub struct Point(pub u8);\n\n    \
+        impl Config {\n        pub fn new() -> Self {\n            todo!()\n        }\n    }\n\n    \
+        impl std::fmt::Display for Config {\n        \
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n            \
+        todo!()\n        }\n    }\n\n    \
+        pub enum Mode {\n        Fast { level: u8 },\n        Slow,\n    }\n}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("net/mod.rs"),
+        "pub trait Transport {\n    fn send();\n}\n",
+    )
+    .unwrap();
+    std::fs::write(src.join("main.rs"), "fn main() {}\n").unwrap();
+    let items = DocItems::new(src);
+    (temp, items)
+}
+
+#[test]
+fn test_links_between_doc_files_become_item_paths() {
+    let (_temp, mut items) = crate_items();
+    let markdown = "Runs it with a [config](../types/Config.md) on its \
+        [port](../types/Config/port.md#range \"The port\").\n\n\
+        See [the crate](../../lib.md) and [stop][stop].\n\n[stop]: stop.md\n";
+
+    assert!(has_doc_links(markdown));
+    assert_eq!(
+        rewrite_links(markdown, "lib/api/run.md", &mut items),
+        "Runs it with a [config](crate::types::Config) on its \
+        [port](crate::types::Config::port \"The port\").\n\n\
+        See [the crate](crate) and [stop][stop].\n\n[stop]: crate::api::stop\n"
+    );
+}
+
+#[test]
+fn test_other_links_are_left_alone() {
+    let (_temp, mut items) = crate_items();
+    let markdown = "[Docs](https://docs.rs/x/README.md), [abs](/docs/x.md), [up](../../../x.md), \
+        [impl](../types/Config/Display/fmt.md), [guide](../../guide.md), \
+        [fragment](../../shared/safety.md), [serde](../types/Config.feature-serde.md), \
+        [tuple](../types/Point/0.md), [text](notes.txt)\n\n```\n[code](../types.md)\n```\n";
+
+    assert_eq!(
+        rewrite_links(markdown, "lib/api/run.md", &mut items),
+        markdown
+    );
+    assert!(!has_doc_links(
+        "[text](notes.txt)\n```\n[code](types.md)\n```\n"
+    ));
+}
+
+#[test]
+fn test_doc_items_follow_the_source() {
+    let (_temp, mut items) = crate_items();
+
+    assert_eq!(items.item("lib.md").as_deref(), Some("crate"));
+    assert_eq!(items.item("main.md").as_deref(), Some("crate"));
+    assert_eq!(items.item("lib/types.md").as_deref(), Some("crate::types"));
+    assert_eq!(
+        items.item("lib/types/Config/new.md").as_deref(),
+        Some("crate::types::Config::new")
+    );
+    assert_eq!(
+        items.item("lib/types/Mode/Fast/level.md").as_deref(),
+        Some("crate::types::Mode::Fast::level")
+    );
+    assert_eq!(items.item("net.md").as_deref(), Some("crate::net"));
+    assert_eq!(
+        items.item("net/Transport/send.md").as_deref(),
+        Some("crate::net::Transport::send")
+    );
+    // Not an item in the source, even if it looks like one
+    assert_eq!(items.item("lib/types/Settings.md"), None);
+    assert_eq!(items.item("lib/api/run/extra.md"), None);
+    assert_eq!(items.item("net/Transport/recv.md"), None);
+}
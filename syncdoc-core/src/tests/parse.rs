@@ -86,8 +86,11 @@ fn test_syncdoc_inner_parsing() {
                         assert_eq!(name_arg.value.as_str(), "custom");
                         found_name = true;
                     }
-                    SyncDocArg::CfgAttr(_) => {
-                        // Not testing cfg-attr in this test
+                    SyncDocArg::CfgAttr(_)
+                    | SyncDocArg::Optional(_)
+                    | SyncDocArg::Strict(_)
+                    | SyncDocArg::Merge(_) => {
+                        // Not testing cfg-attr or optional in this test
                     }
                 }
             }
@@ -181,3 +184,186 @@ fn test_parse_static_mut() {
     assert_eq!(static_sig.name.to_string(), "COUNTER");
     assert!(static_sig.mut_kw.is_some());
 }
+
+#[test]
+fn test_parse_union_as_module_item() {
+    let code = "#[repr(C)] pub union IntOrFloat<T: Copy> { pub i: u32, f: T }";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let result = tokens.into_token_iter().parse::<ModuleItem>();
+
+    let Ok(ModuleItem::Union(union_sig)) = result else {
+        panic!("Expected a union item");
+    };
+    assert_eq!(union_sig.name.to_string(), "IntOrFloat");
+    assert_eq!(union_sig.fields.content.unwrap().0.len(), 2);
+}
+
+#[test]
+fn test_union_ident_is_not_a_union_item() {
+    let code = "fn union() {}";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let result = tokens.into_token_iter().parse::<ModuleItem>();
+
+    assert!(matches!(result, Ok(ModuleItem::Function(_))));
+}
+
+#[test]
+fn test_parse_macro_rules_as_module_item() {
+    let code = "#[macro_export] macro_rules! square { ($x:expr) => { $x * $x }; }";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let result = tokens.into_token_iter().parse::<ModuleItem>();
+
+    let Ok(ModuleItem::MacroRules(macro_sig)) = result else {
+        panic!("Expected a macro_rules item");
+    };
+    assert_eq!(macro_sig.name.to_string(), "square");
+}
+
+#[test]
+fn test_parse_parenthesized_macro_rules() {
+    let code = "macro_rules! square ( ($x:expr) => { $x * $x } ); fn after() {}";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let content = tokens.into_token_iter().parse::<ModuleContent>().unwrap();
+
+    assert!(matches!(
+        content.items.0[0].value,
+        ModuleItem::MacroRules(_)
+    ));
+    assert!(matches!(content.items.0[1].value, ModuleItem::Function(_)));
+}
+
+#[test]
+fn test_parse_extern_block_foreign_items() {
+    let code = r#"unsafe extern "C" { pub fn printf(fmt: *const c_char, ...) -> c_int; static mut errno: c_int; }"#;
+    let tokens = TokenStream::from_str(code).unwrap();
+    let result = tokens.into_token_iter().parse::<ModuleItem>();
+
+    let Ok(ModuleItem::ExternBlock(extern_block)) = result else {
+        panic!("Expected an extern block");
+    };
+    let items = &extern_block.items.content.items.0;
+    assert_eq!(items.len(), 2);
+
+    let ModuleItem::TraitMethod(foreign_fn) = &items[0].value else {
+        panic!("Expected a foreign function");
+    };
+    assert_eq!(foreign_fn.name.to_string(), "printf");
+    assert!(foreign_fn.visibility.is_some());
+
+    let ModuleItem::Static(foreign_static) = &items[1].value else {
+        panic!("Expected a foreign static");
+    };
+    assert_eq!(foreign_static.name.to_string(), "errno");
+    assert!(foreign_static.value.is_none());
+}
+
+#[test]
+fn test_static_mut_round_trips() {
+    let code = "static mut COUNTER: i32 = 0;";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let static_sig = tokens.into_token_iter().parse::<StaticSig>().unwrap();
+
+    let mut output = TokenStream::new();
+    quote::ToTokens::to_tokens(&static_sig, &mut output);
+    assert_eq!(output.to_string(), "static mut COUNTER : i32 = 0 ;");
+}
+
+#[test]
+fn test_parse_associated_items_in_trait() {
+    let code = "type Output: Clone where Self: Sized; type Item<'a> where Self: 'a; const MAX: usize; const MIN: usize = 0;";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let content = tokens.into_token_iter().parse::<ModuleContent>().unwrap();
+    let items = &content.items.0;
+    assert_eq!(items.len(), 4);
+
+    let ModuleItem::TypeAlias(output) = &items[0].value else {
+        panic!("Expected an associated type");
+    };
+    assert_eq!(output.name.to_string(), "Output");
+    assert!(output.bounds.is_some());
+    assert!(output.where_clause.is_some());
+    assert!(output.target.is_none());
+
+    let ModuleItem::TypeAlias(item) = &items[1].value else {
+        panic!("Expected a generic associated type");
+    };
+    assert!(item.bounds.is_none());
+    assert!(item.where_clause.is_some());
+
+    let ModuleItem::Const(max) = &items[2].value else {
+        panic!("Expected an associated const");
+    };
+    assert_eq!(max.name.to_string(), "MAX");
+    assert!(max.value.is_none());
+
+    let ModuleItem::Const(min) = &items[3].value else {
+        panic!("Expected an associated const with a default");
+    };
+    assert!(min.value.is_some());
+}
+
+#[test]
+fn test_associated_items_round_trip() {
+    let code = "type Output: Clone = String; const MAX: usize;";
+    let tokens = TokenStream::from_str(code).unwrap();
+    let content = tokens.into_token_iter().parse::<ModuleContent>().unwrap();
+
+    let mut output = TokenStream::new();
+    for item in content.items.0 {
+        unsynn::ToTokens::to_tokens(&item.value, &mut output);
+    }
+    assert_eq!(
+        output.to_string(),
+        "type Output : Clone = String ; const MAX : usize ;"
+    );
+}
+
+#[test]
+fn test_optional_arg_parsing() {
+    let input = quote!(path = "docs", optional);
+    let parsed = input.into_token_iter().parse::<SyncDocInner>().unwrap();
+    let args = parsed.args.unwrap();
+
+    assert_eq!(args.0.len(), 2);
+    assert!(matches!(args.0[1].value, SyncDocArg::Optional(_)));
+}
+
+#[test]
+fn test_strict_arg_parsing() {
+    let input = quote!(strict, path = "docs");
+    let parsed = input.into_token_iter().parse::<SyncDocInner>().unwrap();
+    let args = parsed.args.unwrap();
+
+    assert_eq!(args.0.len(), 2);
+    assert!(matches!(args.0[0].value, SyncDocArg::Strict(_)));
+}
+
+#[test]
+fn test_empty_blocks_parse() {
+    let input = quote! {
+        impl Marker for Foo {}
+        trait Marker {}
+        mod empty {}
+    };
+    let content = input.into_token_iter().parse::<ModuleContent>().unwrap();
+    let items = &content.items.0;
+
+    assert_eq!(items.len(), 3);
+    assert!(matches!(items[0].value, ModuleItem::ImplBlock(_)));
+    assert!(matches!(items[1].value, ModuleItem::Trait(_)));
+    assert!(matches!(items[2].value, ModuleItem::Module(_)));
+}
+
+#[test]
+fn test_merge_arg_parsing() {
+    let input = quote!(path = "docs", merge = "prefer-inline");
+    let parsed = input.into_token_iter().parse::<SyncDocInner>().unwrap();
+    let args = parsed.args.unwrap();
+
+    assert_eq!(args.0.len(), 2);
+    if let SyncDocArg::Merge(merge_arg) = &args.0[1].value {
+        assert_eq!(merge_arg.value.as_str(), "prefer-inline");
+    } else {
+        panic!("Expected merge argument");
+    }
+}
@@ -0,0 +1,183 @@
+use super::*;
+use quote::quote;
+use unsynn::{IParse, ToTokens};
+
+fn parse_impl(tokens: TokenStream) -> ImplBlockSig {
+    tokens.into_token_iter().parse::<ImplBlockSig>().unwrap()
+}
+
+#[test]
+fn test_plain_type_names_are_unchanged() {
+    assert_eq!(type_path_segment(quote! { Foo }, &[]), "Foo");
+    assert_eq!(type_path_segment(quote! { my_type }, &[]), "my_type");
+}
+
+#[test]
+fn test_generic_arguments_in_brackets() {
+    assert_eq!(type_path_segment(quote! { From<A> }, &[]), "From[A]");
+    assert_eq!(type_path_segment(quote! { Foo<u8> }, &[]), "Foo[u8]");
+    assert_eq!(
+        type_path_segment(quote! { HashMap<String, Vec<u8>> }, &[]),
+        "HashMap[String,Vec[u8]]"
+    );
+    assert_eq!(
+        type_path_segment(quote! { Iterator<Item = u8> }, &[]),
+        "Iterator[Item=u8]"
+    );
+}
+
+#[test]
+fn test_path_qualifiers_and_lifetimes_dropped() {
+    assert_eq!(
+        type_path_segment(quote! { std::fmt::Display }, &[]),
+        "Display"
+    );
+    assert_eq!(
+        type_path_segment(quote! { ::core::ops::Add<i32> }, &[]),
+        "Add[i32]"
+    );
+    assert_eq!(type_path_segment(quote! { Cow<'a, str> }, &[]), "Cow[str]");
+    assert_eq!(type_path_segment(quote! { Parser<'a> }, &[]), "Parser");
+    assert_eq!(type_path_segment(quote! { &'a T }, &[]), "&T");
+}
+
+#[test]
+fn test_references_pointers_and_compound_types() {
+    assert_eq!(type_path_segment(quote! { &T }, &[]), "&T");
+    assert_eq!(type_path_segment(quote! { &mut T }, &[]), "&mut-T");
+    assert_eq!(type_path_segment(quote! { *const T }, &[]), "ptr-const-T");
+    assert_eq!(type_path_segment(quote! { [T] }, &[]), "[T]");
+    assert_eq!(type_path_segment(quote! { [u8; 4] }, &[]), "[u8;4]");
+    assert_eq!(type_path_segment(quote! { (A, B) }, &[]), "(A,B)");
+    assert_eq!(
+        type_path_segment(quote! { Box<dyn Error + Send + 'static> }, &[]),
+        "Box[dyn-Error+Send]"
+    );
+    assert_eq!(
+        type_path_segment(quote! { fn(u8) -> u8 }, &[]),
+        "fn(u8)-to-u8"
+    );
+}
+
+#[test]
+fn test_where_clause_ends_the_name() {
+    assert_eq!(
+        type_path_segment(quote! { Wrapper<T> where T: Clone }, &[]),
+        "Wrapper[T]"
+    );
+}
+
+#[test]
+fn test_impl_context_for_inherent_and_trait_impls() {
+    let inherent = parse_impl(quote! { impl Foo<u8> { fn f() {} } });
+    assert_eq!(impl_context(&inherent), vec!["Foo[u8]"]);
+
+    let trait_impl =
+        parse_impl(quote! { impl From<A> for Foo { fn from(a: A) -> Self { todo!() } } });
+    assert_eq!(impl_context(&trait_impl), vec!["Foo", "From[A]"]);
+
+    let blanket = parse_impl(quote! { impl<T: Debug> Trait for &T { fn f() {} } });
+    assert_eq!(impl_context(&blanket), vec!["&T", "Trait"]);
+}
+
+#[test]
+fn test_name_override_replaces_the_impl_context() {
+    let trait_impl =
+        parse_impl(quote! { impl From<A> for Foo { fn from(a: A) -> Self { todo!() } } });
+    assert_eq!(
+        named_impl_context(&trait_impl, Some("FooFromA".to_string())),
+        vec!["FooFromA"]
+    );
+    assert_eq!(
+        named_impl_context(&trait_impl, None),
+        vec!["Foo", "From[A]"]
+    );
+}
+
+#[test]
+fn test_impl_contexts_do_not_collide() {
+    let a = parse_impl(quote! { impl From<A> for Foo { fn from(a: A) -> Self { todo!() } } });
+    let b = parse_impl(quote! { impl From<B> for Foo { fn from(b: B) -> Self { todo!() } } });
+    assert_ne!(impl_context(&a), impl_context(&b));
+
+    let u8_impl = parse_impl(quote! { impl Foo<u8> { fn f() {} } });
+    let u16_impl = parse_impl(quote! { impl Foo<u16> { fn f() {} } });
+    assert_ne!(impl_context(&u8_impl), impl_context(&u16_impl));
+}
+
+#[test]
+fn test_impl_context_round_trips_header_tokens() {
+    let impl_block = parse_impl(quote! { impl<T> Trait for Vec<T> where T: Clone { fn f() {} } });
+    assert_eq!(impl_context(&impl_block), vec!["Vec", "Trait"]);
+    assert!(impl_block.to_token_stream().to_string().contains("where"));
+}
+
+#[test]
+fn test_impl_params_become_placeholders() {
+    let params = vec!["T".to_string()];
+    assert_eq!(type_path_segment(quote! { Foo<T> }, &params), "Foo");
+    assert_eq!(
+        type_path_segment(quote! { Foo<T, u8> }, &params),
+        "Foo[_,u8]"
+    );
+    assert_eq!(
+        type_path_segment(quote! { From<Vec<T>> }, &params),
+        "From[Vec]"
+    );
+    assert_eq!(type_path_segment(quote! { &T }, &params), "&T");
+}
+
+#[test]
+fn test_impl_context_ignores_impl_params() {
+    let generic = parse_impl(quote! {
+        impl<'a, T: Clone, const N: usize> Buffer<'a, T, N> where T: Clone { fn f() {} }
+    });
+    assert_eq!(impl_context(&generic), vec!["Buffer"]);
+
+    let concrete = parse_impl(quote! { impl Buffer<'static, u8, 4> { fn f() {} } });
+    assert_eq!(impl_context(&concrete), vec!["Buffer[u8,4]"]);
+
+    let blanket =
+        parse_impl(quote! { impl<T> From<T> for Wrapper<T> { fn from(t: T) -> Self { todo!() } } });
+    assert_eq!(impl_context(&blanket), vec!["Wrapper", "From"]);
+}
+
+#[test]
+fn test_impl_rust_paths() {
+    let rust_path = |tokens, module| impl_rust_path(&parse_impl(tokens), module);
+
+    assert_eq!(rust_path(quote! { impl<T> Foo<T> {} }, "api"), "Foo");
+    assert_eq!(
+        rust_path(quote! { impl From<Vec<u8>> for Foo {} }, "api"),
+        "<api::Foo as From<Vec<u8>>>"
+    );
+    assert_eq!(
+        rust_path(
+            quote! { impl<'a> Iterator for Iter<'a> where Self: Sized {} },
+            ""
+        ),
+        "<Iter<'a> as Iterator>"
+    );
+    assert_eq!(
+        rust_path(quote! { impl Describe for &mut dyn Fn(u8) -> u8 {} }, "api"),
+        "<&mut dyn Fn(u8) -> u8 as Describe>"
+    );
+    assert_eq!(
+        rust_path(quote! { impl Add<Output = u8> for u8 {} }, "api"),
+        "<u8 as Add<Output = u8>>"
+    );
+}
+
+#[test]
+fn test_doc_files_are_relative_and_sorted() {
+    let temp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(temp.path().join("shared")).unwrap();
+    for file in ["lib.md", "shared/safety.md", "diagram.png"] {
+        std::fs::write(temp.path().join(file), "").unwrap();
+    }
+
+    assert_eq!(
+        doc_files(temp.path()).unwrap(),
+        ["diagram.png", "lib.md", "shared/safety.md"]
+    );
+}
@@ -0,0 +1,76 @@
+use super::*;
+
+const TYPES_MD: &str = "\
+Types shared across the crate.
+
+## `Config`
+
+Settings for a run.
+
+# Examples
+
+```
+# use demo::Config;
+let config = Config::default();
+```
+
+### `Config::port`
+
+The port to listen on.
+
+## `Status`
+";
+
+#[test]
+fn test_parse_splits_preamble_and_items() {
+    let sections = Sections::parse(TYPES_MD);
+
+    assert_eq!(sections.preamble, "Types shared across the crate.");
+    let keys: Vec<&str> = sections.items.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["Config", "Config::port", "Status"]);
+    assert_eq!(sections.get("Config::port"), Some("The port to listen on."));
+    assert_eq!(sections.get("Status"), Some(""));
+    assert_eq!(sections.get("Config::host"), None);
+}
+
+#[test]
+fn test_doc_headings_and_code_fences_stay_in_the_item() {
+    let sections = Sections::parse(TYPES_MD);
+
+    assert_eq!(
+        sections.get("Config"),
+        Some(
+            "Settings for a run.\n\n# Examples\n\n```\n# use demo::Config;\nlet config = Config::default();\n```"
+        )
+    );
+}
+
+#[test]
+fn test_render_round_trips() {
+    let sections = Sections::parse(TYPES_MD);
+
+    assert_eq!(sections.render(), TYPES_MD);
+}
+
+#[test]
+fn test_set_replaces_or_appends() {
+    let mut sections = Sections::parse(TYPES_MD);
+    sections.set("Status", "\nWhere a run is up to.\n");
+    sections.set("Status::Done", "");
+
+    assert_eq!(sections.get("Status"), Some("Where a run is up to."));
+    assert!(sections
+        .render()
+        .ends_with("## `Status`\n\nWhere a run is up to.\n\n### `Status::Done`\n"));
+}
+
+#[test]
+fn test_section_of_and_item_path_are_inverses() {
+    let (file, key) = section_of("docs/types/Config/port.md", "docs/types").unwrap();
+    assert_eq!(file, "docs/types.md");
+    assert_eq!(key, "Config::port");
+    assert_eq!(item_path("docs/types", &key), "docs/types/Config/port.md");
+
+    assert_eq!(section_of("docs/shared/ctor.md", "docs/types"), None);
+    assert_eq!(section_of("docs/typesetting/Font.md", "docs/types"), None);
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+const CLIENT_RS: &str = "\
+use demo::Client;
+
+fn main() {
+    // ANCHOR: connect
+    let client = Client::connect(\"localhost\");
+    // ANCHOR: send
+    client.send(b\"hi\");
+    // ANCHOR_END: send
+    // ANCHOR_END: connect
+}
+";
+
+fn read(path: &str) -> Option<String> {
+    (path == "examples/client.rs").then(|| CLIENT_RS.to_string())
+}
+
+#[test]
+fn test_anchor_region_becomes_a_rust_block() {
+    let markdown = "Connect first:\n\n<!-- syncdoc:snippet examples/client.rs#connect -->\n";
+
+    assert!(has_snippets(markdown));
+    assert_eq!(
+        resolve_snippets(markdown, &mut read).unwrap(),
+        "Connect first:\n\n```rust\nlet client = Client::connect(\"localhost\");\nclient.send(b\"hi\");\n```\n"
+    );
+}
+
+#[test]
+fn test_snippet_files_are_listed_without_anchors() {
+    let markdown = "<!-- syncdoc:snippet examples/client.rs#connect -->\n\n\
+        <!-- syncdoc:snippet tests/smoke.rs -->\n";
+
+    assert_eq!(
+        snippet_files(markdown),
+        ["examples/client.rs", "tests/smoke.rs"]
+    );
+}
+
+#[test]
+fn test_whole_file_without_an_anchor() {
+    let resolved = resolve_snippets("<!-- syncdoc:snippet examples/client.rs -->", &mut read);
+
+    assert_eq!(
+        resolved.unwrap(),
+        "```rust\nuse demo::Client;\n\nfn main() {\n    let client = Client::connect(\"localhost\");\n    client.send(b\"hi\");\n}\n```"
+    );
+}
+
+#[test]
+fn test_missing_file_or_anchor_is_an_error() {
+    assert_eq!(
+        resolve_snippets(
+            "<!-- syncdoc:snippet examples/server.rs#bind -->",
+            &mut read
+        )
+        .unwrap_err(),
+        "missing snippet file `examples/server.rs`"
+    );
+    assert_eq!(
+        resolve_snippets(
+            "<!-- syncdoc:snippet examples/client.rs#bind -->",
+            &mut read
+        )
+        .unwrap_err(),
+        "no `// ANCHOR: bind` in `examples/client.rs`"
+    );
+}
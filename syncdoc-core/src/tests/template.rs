@@ -0,0 +1,51 @@
+use super::*;
+
+fn vars() -> TemplateVars {
+    TemplateVars {
+        crate_name: "demo".to_string(),
+        crate_version: "1.4.0".to_string(),
+        item_name: "port".to_string(),
+        item_path: "types::Config::port".to_string(),
+    }
+}
+
+#[test]
+fn test_expand_fills_in_each_placeholder() {
+    let markdown = "```toml\n{{crate.name}} = \"{{ crate.version }}\"\n```\n\n`{{item.name}}` is `{{item.path}}`\n";
+
+    assert!(has_placeholders(markdown));
+    assert_eq!(
+        expand(markdown, &vars()).unwrap(),
+        "```toml\ndemo = \"1.4.0\"\n```\n\n`port` is `types::Config::port`\n"
+    );
+}
+
+#[test]
+fn test_other_double_braces_are_left_alone() {
+    let markdown = "println!(\"{{}} {{{}}}\", x, y);\n{{title}} and {{ page.url }}\n";
+
+    assert!(!has_placeholders(markdown));
+    assert_eq!(expand(markdown, &vars()).unwrap(), markdown);
+}
+
+#[test]
+fn test_env_placeholders() {
+    // Cargo sets this for the test binary as well as for rustc
+    let markdown = "Built by {{env.CARGO_PKG_NAME}}";
+
+    assert_eq!(env_vars(markdown), ["CARGO_PKG_NAME"]);
+    assert_eq!(expand(markdown, &vars()).unwrap(), "Built by syncdoc-core");
+
+    assert_eq!(
+        expand("{{env.SYNCDOC_SURELY_UNSET}}", &vars()).unwrap_err(),
+        "environment variable `SYNCDOC_SURELY_UNSET` is not set, for `{{env.SYNCDOC_SURELY_UNSET}}`"
+    );
+}
+
+#[test]
+fn test_unknown_key_is_an_error() {
+    assert_eq!(
+        expand("{{crate.authors}}", &vars()).unwrap_err(),
+        "unknown placeholder `{{crate.authors}}`, expected crate.name, crate.version, item.name, item.path or env.NAME"
+    );
+}
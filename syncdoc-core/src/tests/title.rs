@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_title_naming_the_item_is_stripped() {
+    assert_eq!(
+        strip_title("# Config\n\nSettings for a run.\n", "Config").unwrap(),
+        "Settings for a run.\n"
+    );
+    assert_eq!(
+        strip_title("# `Config` #\nSettings for a run.\n", "Config").unwrap(),
+        "Settings for a run.\n"
+    );
+    assert_eq!(
+        strip_title(
+            "---\nhidden: true\n---\n\n# Config\n\nSettings.\n",
+            "Config"
+        )
+        .unwrap(),
+        "---\nhidden: true\n---\n\nSettings.\n"
+    );
+}
+
+#[test]
+fn test_other_headings_are_kept() {
+    assert_eq!(strip_title("# Overview\n\nSettings.\n", "Config"), None);
+    assert_eq!(strip_title("## Config\n\nSettings.\n", "Config"), None);
+    assert_eq!(strip_title("Settings.\n\n# Config\n", "Config"), None);
+    assert_eq!(strip_title("# C#\n", "C"), None);
+}
+
+#[test]
+fn test_add_title_goes_after_front_matter() {
+    assert_eq!(
+        add_title("Settings for a run.\n", "Config"),
+        "# Config\n\nSettings for a run.\n"
+    );
+    assert_eq!(add_title("", "Config"), "# Config\n");
+    assert_eq!(
+        add_title("---\nhidden: true\n---\nSettings.\n", "Config"),
+        "---\nhidden: true\n---\n# Config\n\nSettings.\n"
+    );
+    assert_eq!(
+        add_title("# Config\n\nSettings.\n", "Config"),
+        "# Config\n\nSettings.\n"
+    );
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::path_utils::impl_context;
 use braces::{brace_paths, BraceConfig};
 use insta::assert_snapshot;
 use quote::quote;
@@ -57,6 +58,18 @@ impl PathCollector {
             ModuleItem::Struct(struct_sig) => {
                 self.collect_from_struct(struct_sig);
             }
+            ModuleItem::Union(union_sig) => {
+                let union_name = union_sig.name.to_string();
+                self.add_path(&union_name);
+                if let Some(fields_cdv) = union_sig.fields.content.as_ref() {
+                    for field_delimited in &fields_cdv.0 {
+                        self.add_path_with_context(
+                            &union_name,
+                            &field_delimited.value.name.to_string(),
+                        );
+                    }
+                }
+            }
             ModuleItem::TypeAlias(type_alias) => {
                 self.add_path(&type_alias.name.to_string());
             }
@@ -66,21 +79,20 @@ impl PathCollector {
             ModuleItem::Static(static_sig) => {
                 self.add_path(&static_sig.name.to_string());
             }
+            ModuleItem::MacroRules(macro_sig) => {
+                self.add_path(&macro_sig.name.to_string());
+            }
+            ModuleItem::ExternBlock(extern_block) => {
+                for item_delimited in &extern_block.items.content.items.0 {
+                    self.collect_from_item(item_delimited.value.clone());
+                }
+            }
             ModuleItem::Other(_) => {}
         }
     }
 
     fn collect_from_impl_block(&mut self, impl_block: ImplBlockSig) {
-        let context_path = if let Some(for_trait) = &impl_block.for_trait {
-            let trait_name = extract_type_name(&impl_block.target_type);
-            let type_name = extract_first_ident_from_tokens(&for_trait.second);
-            vec![type_name, trait_name]
-        } else {
-            let type_name = extract_type_name(&impl_block.target_type);
-            vec![type_name]
-        };
-
-        self.context.extend(context_path);
+        self.context.extend(impl_context(&impl_block));
 
         let module_content = &impl_block.items.content;
         for item_delimited in &module_content.items.0 {
@@ -344,3 +356,449 @@ fn test_trait_impl_methods_doc_paths() {
 
     assert_snapshot!(to_braces(&path_refs), @"docs/TreeNode/Display/fmt.md");
 }
+
+#[test]
+fn test_name_override_replaces_item_stem() {
+    let input = quote! {
+        #[cfg(feature = "v2")]
+        pub fn read() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None)
+        .with_name(Some("read_v2".to_string()))
+        .process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/read_v2.md")] # [cfg (feature = "v2")] pub fn read () { }"#);
+}
+
+#[test]
+fn test_name_override_applies_to_nested_paths() {
+    let input = quote! {
+        pub struct Config {
+            pub port: u16,
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None)
+        .with_name(Some("ServerConfig".to_string()))
+        .process()
+        .to_string();
+
+    assert!(output.contains(r#""docs/ServerConfig.md""#));
+    assert!(output.contains(r#""docs/ServerConfig/port.md""#));
+}
+
+#[test]
+fn test_name_override_not_inherited_by_members() {
+    let input = quote! {
+        impl Reader {
+            fn read(&self) {}
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None)
+        .with_name(Some("LegacyReader".to_string()))
+        .process();
+
+    assert_snapshot!(output.to_string(), @r#"impl Reader { # [doc = include_str ! ("docs/LegacyReader/read.md")] fn read (& self) { } }"#);
+}
+
+#[test]
+fn test_helper_skip_leaves_item_undocumented() {
+    let input = quote! {
+        impl Reader {
+            #[syncdoc(skip)]
+            fn helper(&self) {}
+            fn read(&self) {}
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"impl Reader { fn helper (& self) { } # [doc = include_str ! ("docs/Reader/read.md")] fn read (& self) { } }"#);
+}
+
+#[test]
+fn test_helper_path_redirects_relative_to_docs_root() {
+    let input = quote! {
+        impl Reader {
+            #[syncdoc(path = "shared/ctor.md")]
+            fn new() -> Self { Reader }
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs/io".to_string(), None)
+        .with_docs_root("docs".to_string())
+        .process();
+
+    assert_snapshot!(output.to_string(), @r#"impl Reader { # [doc = include_str ! ("docs/shared/ctor.md")] fn new () -> Self { Reader } }"#);
+}
+
+#[test]
+fn test_helper_on_fields_and_variants() {
+    let input = quote! {
+        pub enum Mode {
+            #[syncdoc(skip)]
+            Fast,
+            Slow {
+                #[syncdoc(path = "shared/delay.md")]
+                delay: u32,
+            },
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/Mode.md")] pub enum Mode { Fast , # [doc = include_str ! ("docs/Mode/Slow.md")] Slow { # [doc = include_str ! ("docs/shared/delay.md")] delay : u32 } }"#);
+}
+
+#[test]
+fn test_helper_skip_strips_nested_helpers() {
+    let input = quote! {
+        #[syncdoc(skip)]
+        pub struct Config {
+            #[syncdoc(skip)]
+            pub port: u16,
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @"pub struct Config { pub port : u16 , }");
+}
+
+#[test]
+fn test_helper_malformed_reports_compile_error() {
+    let input = quote! {
+        #[syncdoc(sikp)]
+        fn read() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/read.md")] fn read () { } compile_error ! ("expected `#[syncdoc(skip)]` or `#[syncdoc(path = \"...\")]`") ;"#);
+}
+
+#[test]
+fn test_tuple_struct_fields_documented_by_index() {
+    let input = quote! {
+        pub struct Meters(pub f64, HashMap<String, u8>);
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/Meters.md")] pub struct Meters (# [doc = include_str ! ("docs/Meters/0.md")] pub f64 , # [doc = include_str ! ("docs/Meters/1.md")] HashMap < String , u8 >) ;"#);
+}
+
+#[test]
+fn test_tuple_variant_fields_documented_by_index() {
+    let input = quote! {
+        pub enum Shape {
+            Circle(f64),
+            Rect(#[syncdoc(skip)] f64, f64),
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/Shape.md")] pub enum Shape { # [doc = include_str ! ("docs/Shape/Circle.md")] Circle (# [doc = include_str ! ("docs/Shape/Circle/0.md")] f64) , # [doc = include_str ! ("docs/Shape/Rect.md")] Rect (f64 , # [doc = include_str ! ("docs/Shape/Rect/1.md")] f64) }"#);
+}
+
+#[test]
+fn test_union_fields_doc_paths() {
+    let input = quote! {
+        #[repr(C)]
+        pub union IntOrFloat {
+            pub i: u32,
+            pub f: f32,
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/IntOrFloat.md")] # [repr (C)] pub union IntOrFloat { # [doc = include_str ! ("docs/IntOrFloat/i.md")] pub i : u32 , # [doc = include_str ! ("docs/IntOrFloat/f.md")] pub f : f32 }"#);
+}
+
+#[test]
+fn test_macro_rules_doc_path() {
+    let input = quote! {
+        mod helpers {
+            #[macro_export]
+            macro_rules! square {
+                ($x:expr) => { $x * $x };
+            }
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"mod helpers { # [doc = include_str ! ("docs/helpers/square.md")] # [macro_export] macro_rules ! square { ($ x : expr) => { $ x * $ x } ; } }"#);
+}
+
+#[test]
+fn test_extern_block_items_documented_in_enclosing_module() {
+    let input = quote! {
+        mod ffi {
+            extern "C" {
+                pub fn abs(x: i32) -> i32;
+                pub static errno: i32;
+            }
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"mod ffi { extern "C" { # [doc = include_str ! ("docs/ffi/abs.md")] pub fn abs (x : i32) -> i32 ; # [doc = include_str ! ("docs/ffi/errno.md")] pub static errno : i32 ; } }"#);
+}
+
+#[test]
+fn test_extern_block_redirect_is_an_error() {
+    let input = quote! {
+        #[syncdoc(path = "ffi/libc.md")]
+        extern "C" {
+            fn abs(x: i32) -> i32;
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r##"extern "C" { # [doc = include_str ! ("docs/abs.md")] fn abs (x : i32) -> i32 ; } compile_error ! ("#[syncdoc(path = \"...\")] cannot be used on impl blocks, modules or extern blocks; put it on the items inside") ;"##);
+}
+
+#[test]
+fn test_associated_items_doc_paths() {
+    let input = quote! {
+        pub trait Parser {
+            type Output: Clone;
+            const MAX_DEPTH: usize;
+            fn parse(&self) -> Self::Output;
+        }
+
+        impl Parser for Json {
+            type Output = Value;
+            const MAX_DEPTH: usize = 64;
+            fn parse(&self) -> Value { Value }
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/Parser.md")] pub trait Parser { # [doc = include_str ! ("docs/Parser/Output.md")] type Output : Clone ; # [doc = include_str ! ("docs/Parser/MAX_DEPTH.md")] const MAX_DEPTH : usize ; # [doc = include_str ! ("docs/Parser/parse.md")] fn parse (& self) -> Self :: Output ; } impl Parser for Json { # [doc = include_str ! ("docs/Json/Parser/Output.md")] type Output = Value ; # [doc = include_str ! ("docs/Json/Parser/MAX_DEPTH.md")] const MAX_DEPTH : usize = 64 ; # [doc = include_str ! ("docs/Json/Parser/parse.md")] fn parse (& self) -> Value { Value } }"#);
+}
+
+#[test]
+fn test_generic_impls_get_distinct_doc_paths() {
+    let input = quote! {
+        impl From<A> for Foo {
+            fn from(a: A) -> Self { Foo }
+        }
+
+        impl From<B> for Foo {
+            fn from(b: B) -> Self { Foo }
+        }
+
+        impl<T: Debug> Describe for &T {
+            fn describe(&self) -> String { String::new() }
+        }
+
+        impl Foo<u8> {
+            fn new() -> Self { Foo }
+        }
+    };
+
+    let collector = PathCollector::new("docs".to_string());
+    let paths = collector.collect_paths(input);
+    let path_refs: Vec<&str> = paths.iter().map(|s| s.as_str()).collect();
+
+    assert_snapshot!(path_refs.join("\n"), @r"
+    docs/&T/Describe/describe.md
+    docs/Foo/From[A]/from.md
+    docs/Foo/From[B]/from.md
+    docs/Foo[u8]/new.md
+    ");
+}
+
+#[test]
+fn test_duplicate_doc_path_reports_compile_error() {
+    let input = quote! {
+        impl Parser {
+            fn parse() {}
+        }
+
+        impl Parser {
+            fn parse() {}
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"impl Parser { # [doc = include_str ! ("docs/Parser/parse.md")] fn parse () { } } impl Parser { # [doc = include_str ! ("docs/Parser/parse.md")] fn parse () { } } compile_error ! ("another item already takes its docs from `docs/Parser/parse.md`; give this one its own file with #[syncdoc(path = \"...\")]") ;"#);
+}
+
+#[test]
+fn test_cfg_alternates_share_a_doc_path() {
+    let input = quote! {
+        #[cfg(unix)]
+        fn open() {}
+
+        #[cfg(windows)]
+        fn open() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert!(!output.to_string().contains("compile_error"));
+}
+
+#[test]
+fn test_items_of_cfg_alternate_containers_share_a_doc_path() {
+    let input = quote! {
+        pub struct T;
+
+        #[cfg(unix)]
+        impl T {
+            pub fn open() {}
+        }
+
+        #[cfg(windows)]
+        impl T {
+            pub fn open() {}
+        }
+
+        #[cfg(unix)]
+        mod sys {
+            pub fn page_size() {}
+        }
+
+        #[cfg(windows)]
+        mod sys {
+            pub fn page_size() {}
+        }
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert!(!output.to_string().contains("compile_error"), "{}", output);
+}
+
+#[test]
+fn test_unparsed_item_passes_through_verbatim() {
+    let input = quote! {
+        fn before() {}
+
+        pub fn nested_bounds<T: Into<Vec<u8>>>() -> impl Iterator<Item = u8> {}
+
+        fn after() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/before.md")] fn before () { } pub fn nested_bounds < T : Into < Vec < u8 >> > () -> impl Iterator < Item = u8 > { } # [doc = include_str ! ("docs/after.md")] fn after () { }"#);
+}
+
+#[test]
+fn test_unparsed_item_fragments_are_not_documented() {
+    let input = quote! {
+        pub const fn make<T: Into<Vec<u8>>>() -> u8 { 0 }
+        fn after() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None).process();
+
+    assert_snapshot!(output.to_string(), @r#"pub const fn make < T : Into < Vec < u8 >> > () -> u8 { 0 } # [doc = include_str ! ("docs/after.md")] fn after () { }"#);
+}
+
+#[test]
+fn test_empty_blocks_are_documented() {
+    let input = quote! {
+        trait Marker {}
+        impl Marker for Foo {}
+        mod declared_elsewhere;
+        extern crate alloc;
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None)
+        .with_strict(true)
+        .process();
+
+    assert_snapshot!(output.to_string(), @r#"# [doc = include_str ! ("docs/Marker.md")] trait Marker { } impl Marker for Foo { } mod declared_elsewhere ; extern crate alloc ;"#);
+}
+
+#[test]
+fn test_strict_reports_unparsed_item() {
+    let input = quote! {
+        fn nested_bounds<T: Into<Vec<u8>>>() {}
+    };
+
+    let output = TokenProcessor::new(input, "docs".to_string(), None)
+        .with_strict(true)
+        .process();
+
+    assert_snapshot!(output.to_string(), @r#"fn nested_bounds < T : Into < Vec < u8 >> > () { } compile_error ! ("syncdoc could not parse this `fn` item, so it would be left undocumented") ;"#);
+}
+
+fn process_with_merge(merge: MergeDocs) -> String {
+    let input = quote! {
+        /// Inline docs
+        #[inline]
+        fn documented() {}
+
+        fn undocumented() {}
+    };
+
+    TokenProcessor::new(input, "docs".to_string(), None)
+        .with_merge(merge)
+        .process()
+        .to_string()
+}
+
+#[test]
+fn test_merge_concat_keeps_both() {
+    assert_snapshot!(process_with_merge(MergeDocs::Concat), @r#"# [doc = include_str ! ("docs/documented.md")] # [doc = r" Inline docs"] # [inline] fn documented () { } # [doc = include_str ! ("docs/undocumented.md")] fn undocumented () { }"#);
+}
+
+#[test]
+fn test_merge_prefer_file_drops_inline_docs() {
+    assert_snapshot!(process_with_merge(MergeDocs::PreferFile), @r#"# [doc = include_str ! ("docs/documented.md")] # [inline] fn documented () { } # [doc = include_str ! ("docs/undocumented.md")] fn undocumented () { }"#);
+}
+
+#[test]
+fn test_merge_prefer_inline_skips_file() {
+    assert_snapshot!(process_with_merge(MergeDocs::PreferInline), @r#"# [doc = r" Inline docs"] # [inline] fn documented () { } # [doc = include_str ! ("docs/undocumented.md")] fn undocumented () { }"#);
+}
+
+#[test]
+fn test_merge_error_reports_inline_docs() {
+    assert_snapshot!(process_with_merge(MergeDocs::Error), @r#"# [doc = include_str ! ("docs/documented.md")] # [doc = r" Inline docs"] # [inline] fn documented () { } # [doc = include_str ! ("docs/undocumented.md")] fn undocumented () { } compile_error ! ("this item has inline docs as well as the doc file `docs/documented.md`; move them into the file or remove one") ;"#);
+}
+
+#[test]
+fn test_items_of_their_own_only_go_after_items_outside_impls_and_traits() {
+    assert!(takes_sibling_items(
+        &quote! { #[derive(Debug)] pub struct S; }
+    ));
+    assert!(takes_sibling_items(&quote! { impl S { fn f() {} } }));
+    assert!(takes_sibling_items(&quote! { pub static COUNT: u8 = 0; }));
+    // Any of these could be in an impl or trait
+    assert!(!takes_sibling_items(
+        &quote! { #[inline] pub(crate) const unsafe fn f() { g() } }
+    ));
+    assert!(!takes_sibling_items(&quote! { fn required(&self); }));
+    assert!(!takes_sibling_items(&quote! { const LIMIT: u8; }));
+    assert!(!takes_sibling_items(&quote! { type Output = u8; }));
+}
+
+#[test]
+fn test_front_matter_attributes_are_gated_by_locale() {
+    let attributes = quote! { #[deprecated(since = "1.2")] #[doc(alias = "run")] };
+    let cfg: CfgPredicate = "syncdoc_locale = \"ja\"".parse().unwrap();
+
+    assert_snapshot!(
+        gated(attributes.clone(), Some(&cfg)).to_string(),
+        @r#"# [cfg_attr (syncdoc_locale = "ja" , deprecated (since = "1.2"))] # [cfg_attr (syncdoc_locale = "ja" , doc (alias = "run"))]"#
+    );
+    assert_eq!(
+        gated(attributes.clone(), None).to_string(),
+        attributes.to_string()
+    );
+}
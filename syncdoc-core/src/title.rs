@@ -0,0 +1,57 @@
+//! Leading `# Name` titles, so doc files read well when browsing the docs directory
+//!
+//! With `strip-title = true`, a doc file can open (after any front matter) with an H1 naming its
+//! item, like `# Config` or ``# `Config` ``. The title is left out when the docs are injected, as
+//! rustdoc already shows the item's name and the heading would otherwise be its summary line.
+
+use crate::front_matter::FrontMatter;
+
+/// The markdown without its title, if it opens with one naming `name`
+///
+/// Front matter before the title is kept, and the blank lines after it are dropped with it.
+pub fn strip_title(markdown: &str, name: &str) -> Option<String> {
+    let (front_matter, body) = split_front_matter(markdown);
+    let (line, rest) = body.split_once('\n').unwrap_or((body, ""));
+    (title_text(line)? == name)
+        .then(|| format!("{}{}", front_matter, rest.trim_start_matches('\n')))
+}
+
+/// The markdown with a title naming `name` after any front matter, unless it already has one
+pub fn add_title(markdown: &str, name: &str) -> String {
+    if strip_title(markdown, name).is_some() {
+        return markdown.to_string();
+    }
+    let (front_matter, body) = split_front_matter(markdown);
+    match body.trim() {
+        "" => format!("{}# {}\n", front_matter, name),
+        _ => format!("{}# {}\n\n{}", front_matter, name, body),
+    }
+}
+
+/// Splits off the front matter block, with the blank lines after it
+fn split_front_matter(markdown: &str) -> (&str, &str) {
+    let body = match FrontMatter::parse(markdown) {
+        Ok((_, body)) => body,
+        Err(_) => markdown,
+    };
+    let body = body.trim_start_matches('\n');
+    markdown.split_at(markdown.len() - body.len())
+}
+
+/// The text of an H1 heading line, without a code span around it or closing `#`s
+fn title_text(line: &str) -> Option<&str> {
+    let text = line.strip_prefix("# ")?.trim();
+    let text = match text.trim_end_matches('#').strip_suffix(' ') {
+        Some(text) => text.trim_end(),
+        None => text,
+    };
+    Some(
+        text.strip_prefix('`')
+            .and_then(|text| text.strip_suffix('`'))
+            .unwrap_or(text),
+    )
+}
+
+#[cfg(test)]
+#[path = "tests/title.rs"]
+mod title_tests;
@@ -1,28 +1,271 @@
+use crate::admonition::{has_alerts, render_alerts};
+use crate::cfg::CfgPredicate;
+use crate::config::{Admonitions, DocLayout, MergeDocs, MissingDocs};
+use crate::doc_injector::{
+    beside, feature_doc_attrs, literal_doc_impl, localised, needs_processing, read_doc_bytes,
+    read_doc_file, template_vars, track_binary_asset, track_doc_file, track_env_var,
+    track_text_asset,
+};
+use crate::front_matter::FrontMatter;
+use crate::images::{embed_images, has_local_images};
+use crate::include::resolve_includes;
+use crate::links::{has_doc_links, rewrite_links, DocItems};
 use crate::omnidoc_impl;
+use crate::snippet::resolve_snippets;
 use proc_macro2::TokenStream;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::rc::Rc;
 use unsynn::*;
 
-use crate::parse::{ImplBlockSig, ModuleContent, ModuleItem, ModuleSig, TraitSig};
-
+use crate::helper_attr::{
+    item_attributes_mut, strip_helper_attrs, take_item_directive, ItemDirective,
+};
+use crate::parse::{
+    Attribute, ExternBlockSig, ImplBlockSig, ModuleContent, ModuleItem, ModuleSig, TraitSig,
+};
+use crate::path_utils::{impl_rust_path, named_impl_context};
+use crate::sections::{section_of, Sections};
+use crate::template::{env_vars, expand};
+use crate::title::strip_title;
+
+#[derive(Clone)]
 pub struct TokenProcessor {
     input: TokenStream,
     base_path: String,
-    cfg_attr: Option<String>,
+    cfg_attr: Option<CfgPredicate>,
     context: Vec<String>,
+    /// The Rust path of the module, impl or trait the items are in, below the macro's module
+    rust_context: Vec<String>,
+    /// Doc path stem overriding the name of the item `#[omnidoc]` was applied to
+    name: Option<String>,
+    /// Docs directory that `#[syncdoc(path = "...")]` redirects are relative to
+    docs_root: String,
+    /// The default locale's docs directory, for docs not translated into this one
+    fallback_root: Option<String>,
+    /// What the docs directory's locale is picked by, when the docs are translated
+    locale_cfg: Option<CfgPredicate>,
+    /// Each translation's cfg and docs directory, whose docs go on the items alongside these
+    translations: Vec<(CfgPredicate, String)>,
+    /// Doc path from a `#[syncdoc(path = "...")]` redirect on the item being processed
+    redirect: Option<String>,
+    /// `compile_error!` invocations emitted after the processed items
+    diagnostics: Rc<RefCell<Vec<TokenStream>>>,
+    /// Doc files already claimed by an item's derived path, to catch two items sharing one
+    doc_paths: Rc<RefCell<HashSet<String>>>,
+    /// Whether an enclosing module, impl, trait or extern block is behind a `#[cfg(...)]`
+    under_cfg: bool,
+    /// What to do about items whose doc file does not exist
+    missing: MissingDocs,
+    /// Items left undocumented to warn about, by the span of their name and their doc path
+    missing_warnings: Rc<RefCell<Vec<(proc_macro2::Span, String)>>>,
+    /// Whether an item the grammar couldn't parse is an error rather than left undocumented
+    strict: bool,
+    /// What to do about items with inline docs as well as a doc file
+    merge: MergeDocs,
+    /// Whether docs come from a file per item or a section per item
+    layout: DocLayout,
+    /// Whether a doc file's leading title naming its item is left out of the docs
+    strip_title: bool,
+    /// How GitHub alerts in doc files are shown
+    admonitions: Admonitions,
+    /// The size ceiling for images embedded as `data:` URIs, or `None` to leave them as paths
+    embed_images: Option<usize>,
+    /// The crate's items by doc path, when links between doc files are rewritten to them
+    doc_items: Option<Rc<RefCell<DocItems>>>,
+    /// Sections files read so far, by path, or `None` if one could not be read
+    sections: Rc<RefCell<BTreeMap<String, Option<Sections>>>>,
+    /// Doc files read directly rather than through `include_str!`, to track for rebuilds
+    tracked: Rc<RefCell<BTreeSet<String>>>,
+    /// Environment variables read by doc files' placeholders, to track for rebuilds
+    tracked_env: Rc<RefCell<BTreeSet<String>>>,
+    /// Images embedded in the docs, to track for rebuilds
+    tracked_images: Rc<RefCell<BTreeSet<String>>>,
 }
 
 impl TokenProcessor {
-    pub fn new(input: TokenStream, base_path: String, cfg_attr: Option<String>) -> Self {
+    pub fn new(input: TokenStream, base_path: String, cfg_attr: Option<CfgPredicate>) -> Self {
         Self {
             input,
+            docs_root: base_path.clone(),
             base_path,
+            fallback_root: None,
+            locale_cfg: None,
+            translations: Vec::new(),
             cfg_attr,
             context: Vec::new(),
+            rust_context: Vec::new(),
+            name: None,
+            redirect: None,
+            diagnostics: Rc::new(RefCell::new(Vec::new())),
+            doc_paths: Rc::new(RefCell::new(HashSet::new())),
+            under_cfg: false,
+            missing: MissingDocs::default(),
+            missing_warnings: Rc::new(RefCell::new(Vec::new())),
+            strict: false,
+            merge: MergeDocs::default(),
+            layout: DocLayout::default(),
+            strip_title: false,
+            admonitions: Admonitions::default(),
+            embed_images: None,
+            doc_items: None,
+            sections: Rc::new(RefCell::new(BTreeMap::new())),
+            tracked: Rc::new(RefCell::new(BTreeSet::new())),
+            tracked_env: Rc::new(RefCell::new(BTreeSet::new())),
+            tracked_images: Rc::new(RefCell::new(BTreeSet::new())),
         }
     }
 
+    /// Sets the doc path stem used for the top-level item in place of its own name
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Sets the docs directory (before any module path is applied) for helper redirects
+    pub fn with_docs_root(mut self, docs_root: String) -> Self {
+        self.docs_root = docs_root;
+        self
+    }
+
+    /// Sets the cfg picking the docs directory's locale, and each translation's cfg and directory
+    ///
+    /// The compiler keeps the docs of the locale whose cfg is set, so one build has one language.
+    pub fn with_locales(
+        mut self,
+        locale_cfg: Option<CfgPredicate>,
+        translations: Vec<(CfgPredicate, String)>,
+    ) -> Self {
+        self.locale_cfg = locale_cfg;
+        self.translations = translations;
+        self
+    }
+
+    /// Sets what happens to items whose doc file does not exist
+    pub fn with_missing(mut self, missing: MissingDocs) -> Self {
+        self.missing = missing;
+        self
+    }
+
+    /// Sets whether an item the grammar couldn't parse is reported as an error
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets what happens to items with inline docs as well as a doc file
+    pub fn with_merge(mut self, merge: MergeDocs) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    /// Sets whether docs come from a file per item or a section per item
+    pub fn with_layout(mut self, layout: DocLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets whether a doc file's leading title naming its item is left out of the docs
+    pub fn with_strip_title(mut self, strip_title: bool) -> Self {
+        self.strip_title = strip_title;
+        self
+    }
+
+    /// Sets how GitHub alerts in doc files are shown
+    pub fn with_admonitions(mut self, admonitions: Admonitions) -> Self {
+        self.admonitions = admonitions;
+        self
+    }
+
+    /// Sets the size ceiling for images embedded as `data:` URIs, or `None` to leave them as paths
+    pub fn with_embed_images(mut self, embed_images: Option<usize>) -> Self {
+        self.embed_images = embed_images;
+        self
+    }
+
+    /// Sets whether links between doc files become intra-doc links to the items they document
+    ///
+    /// The items are those of the crate the macro is expanded in, so outside of a compiler-driven
+    /// expansion there are none to link to.
+    pub fn with_rewrite_links(mut self, rewrite_links: bool) -> Self {
+        self.doc_items = proc_macro2::Span::call_site()
+            .local_file()
+            .filter(|_| rewrite_links)
+            .and_then(|source_file| DocItems::of_crate(&source_file))
+            .map(|items| Rc::new(RefCell::new(items)));
+        self
+    }
+
+    /// Creates a processor for the items nested under `context`
+    ///
+    /// The name override only ever applies to the annotated item itself, so it is not inherited.
+    fn nested(&self, context: Vec<String>) -> TokenProcessor {
+        TokenProcessor {
+            input: TokenStream::new(),
+            context,
+            name: None,
+            redirect: None,
+            ..self.clone()
+        }
+    }
+
+    /// Notes whether the container of the nested items is behind a `#[cfg(...)]`
+    ///
+    /// Items of differently-configured containers, like `#[cfg(unix)] impl T` and
+    /// `#[cfg(windows)] impl T`, can share a doc file, as only one of them is compiled.
+    fn within(mut self, attributes: &Option<Many<Attribute>>) -> TokenProcessor {
+        if let Some(attributes) = attributes {
+            let mut tokens = TokenStream::new();
+            attributes.to_tokens(&mut tokens);
+            self.under_cfg |= has_cfg_attr(&tokens);
+        }
+        self
+    }
+
+    /// Reads the docs translated under `docs_root`, falling back to this processor's own
+    ///
+    /// Missing docs are left for this processor to report, so they are only reported once.
+    fn translation(&self, cfg: &CfgPredicate, docs_root: &str) -> TokenProcessor {
+        let module = self
+            .base_path
+            .strip_prefix(&self.docs_root)
+            .unwrap_or_default();
+        TokenProcessor {
+            base_path: format!("{}{}", docs_root, module),
+            docs_root: docs_root.to_string(),
+            fallback_root: Some(self.docs_root.clone()),
+            locale_cfg: Some(cfg.clone()),
+            translations: Vec::new(),
+            missing: MissingDocs::Ignore,
+            ..self.clone()
+        }
+    }
+
+    /// What the docs are gated behind: the `cfg-attr` setting and the locale, where there are any
+    fn doc_cfg(&self) -> Option<CfgPredicate> {
+        match (&self.cfg_attr, &self.locale_cfg) {
+            (Some(cfg), Some(locale)) => Some(CfgPredicate::All(vec![cfg.clone(), locale.clone()])),
+            (cfg, locale) => cfg.clone().or_else(|| locale.clone()),
+        }
+    }
+
+    /// The Rust path of the items' container from the crate root, empty at the root itself
+    fn rust_prefix(&self) -> String {
+        let module = module_path();
+        let mut path: Vec<&str> = self.rust_context.iter().map(String::as_str).collect();
+        if !module.is_empty() {
+            path.insert(0, &module);
+        }
+        path.join("::")
+    }
+
+    /// The name an item's markdown file is stored under
+    fn doc_name(&self, item_name: &Ident) -> String {
+        self.name.clone().unwrap_or_else(|| item_name.to_string())
+    }
+
     pub fn process(self) -> TokenStream {
-        match self
+        let output = match self
             .input
             .clone()
             .into_token_iter()
@@ -31,14 +274,83 @@ impl TokenProcessor {
             Ok(_parsed) => self.process_module_content(),
             Err(_) => {
                 // Fallback: if declarative parsing fails, use original input
-                self.input
+                if self.strict {
+                    let span = name_span(&self.input);
+                    let message =
+                        "syncdoc could not parse this item, so it would be left undocumented";
+                    self.diagnostics
+                        .borrow_mut()
+                        .push(quote::quote_spanned! {span=> compile_error!(#message); });
+                }
+                self.input.clone()
+            }
+        };
+
+        // Helpers on items the grammar passes through as raw tokens are never taken above
+        let output = strip_helper_attrs(output);
+
+        // Items of their own, for binary images and warnings, can't go after one in an impl or trait
+        let own_items = takes_sibling_items(&output);
+
+        // Doc files, placeholders' variables and text images like SVGs are tracked by attributes on
+        // the item itself, and binary images by an item of their own where there can be one (the
+        // build helper tracks them elsewhere)
+        let mut tracked = TokenStream::new();
+        for path in self.tracked.borrow().iter() {
+            tracked.extend(track_doc_file(path));
+        }
+        for name in self.tracked_env.borrow().iter() {
+            tracked.extend(track_env_var(name));
+        }
+        let mut binary_images = TokenStream::new();
+        for path in self.tracked_images.borrow().iter() {
+            match track_text_asset(path) {
+                Some(attribute) => tracked.extend(attribute),
+                None if own_items => binary_images.extend(track_binary_asset(path)),
+                None => {}
             }
         }
+        tracked.extend(output);
+        let mut output = tracked;
+        output.extend(binary_images);
+
+        for (span, doc_path) in self.missing_warnings.take() {
+            let warning = if own_items {
+                missing_doc_warning(span, &doc_path)
+            } else {
+                quote::quote_spanned! {span=> ::syncdoc::__missing_doc!(#doc_path); }
+            };
+            self.diagnostics.borrow_mut().push(warning);
+        }
+        output.extend(self.diagnostics.take());
+        output
     }
 
-    fn process_module_content(&self) -> TokenStream {
-        let mut output = TokenStream::new();
+    /// Reads and strips a `#[syncdoc(...)]` helper attribute, reporting it if malformed
+    fn take_directive(&self, attrs: &mut Option<Many<Attribute>>) -> Option<ItemDirective> {
+        take_item_directive(attrs).unwrap_or_else(|e| {
+            self.diagnostics.borrow_mut().push(e.to_compile_error());
+            None
+        })
+    }
 
+    /// Docs from a `#[syncdoc(path = "...")]` redirect, or the item's usual doc path
+    fn inject_with_directive(
+        &self,
+        directive: Option<ItemDirective>,
+        tokens: TokenStream,
+        inject: impl FnOnce(TokenStream) -> TokenStream,
+    ) -> TokenStream {
+        match directive {
+            Some(ItemDirective::Skip) => strip_helper_attrs(tokens),
+            Some(ItemDirective::Path(path)) => {
+                self.include_doc(format!("{}/{}", self.docs_root, path), tokens)
+            }
+            None => inject(tokens),
+        }
+    }
+
+    fn process_module_content(&self) -> TokenStream {
         let content = match self
             .input
             .clone()
@@ -49,46 +361,126 @@ impl TokenProcessor {
             Err(_) => return self.input.clone(),
         };
 
-        for item in content.items.0 {
-            let processed_item = self.process_module_item(item.value);
-            output.extend(processed_item);
+        self.process_items(&content.items)
+    }
+
+    /// Processes a block's items, passing any the grammar couldn't parse through verbatim
+    ///
+    /// An item that fails to parse falls apart into single tokens, starting with its keyword.
+    /// Everything up to its closing `;` or body is kept as is rather than processed, since a
+    /// fragment of it (say the `fn` of an unparsed `const fn`) could parse as an item of its own.
+    fn process_items(&self, items: &Any<ModuleItem>) -> TokenStream {
+        let mut output = TokenStream::new();
+        let mut unparsed: Option<UnparsedItem> = None;
+
+        for (index, item) in items.0.iter().enumerate() {
+            if let Some(state) = &mut unparsed {
+                let mut tokens = TokenStream::new();
+                unsynn::ToTokens::to_tokens(&item.value, &mut tokens);
+                if state.ends_with(&tokens) {
+                    unparsed = None;
+                }
+                output.extend(tokens);
+                continue;
+            }
+            if let Some(keyword) = unparsed_item_keyword(&items.0[index..]) {
+                if self.strict {
+                    let message = format!(
+                        "syncdoc could not parse this `{}` item, so it would be left undocumented",
+                        keyword
+                    );
+                    let span = keyword.span();
+                    self.diagnostics
+                        .borrow_mut()
+                        .push(quote::quote_spanned! {span=> compile_error!(#message); });
+                }
+                keyword.to_tokens(&mut output);
+                unparsed = Some(UnparsedItem::default());
+                continue;
+            }
+            output.extend(self.process_module_item(item.value.clone()));
         }
 
         output
     }
 
-    fn process_module_item(&self, item: ModuleItem) -> TokenStream {
+    fn process_module_item(&self, mut item: ModuleItem) -> TokenStream {
+        let directive = item_attributes_mut(&mut item).and_then(|attrs| self.take_directive(attrs));
+        match directive {
+            Some(ItemDirective::Skip) => {
+                let mut tokens = TokenStream::new();
+                unsynn::ToTokens::to_tokens(&item, &mut tokens);
+                return strip_helper_attrs(tokens);
+            }
+            Some(ItemDirective::Path(path)) => {
+                if matches!(
+                    item,
+                    ModuleItem::ImplBlock(_) | ModuleItem::Module(_) | ModuleItem::ExternBlock(_)
+                ) {
+                    // Impl blocks, modules and extern blocks have no doc file of their own to redirect
+                    let mut tokens = TokenStream::new();
+                    unsynn::ToTokens::to_tokens(&item, &mut tokens);
+                    let span = tokens
+                        .into_iter()
+                        .next()
+                        .map_or_else(proc_macro2::Span::call_site, |t| t.span());
+                    let message = "#[syncdoc(path = \"...\")] cannot be used on impl blocks, modules or extern blocks; put it on the items inside";
+                    self.diagnostics
+                        .borrow_mut()
+                        .push(quote::quote_spanned! {span=> compile_error!(#message); });
+                    return self.process_item_contents(item);
+                }
+                let redirected = TokenProcessor {
+                    redirect: Some(format!("{}/{}", self.docs_root, path)),
+                    ..self.clone()
+                };
+                return redirected.process_item_contents(item);
+            }
+            None => {}
+        }
+
+        self.process_item_contents(item)
+    }
+
+    fn process_item_contents(&self, item: ModuleItem) -> TokenStream {
         match item {
             ModuleItem::TraitMethod(method_sig) => {
                 let mut method_tokens = TokenStream::new();
                 quote::ToTokens::to_tokens(&method_sig, &mut method_tokens);
-                self.inject_doc_into_simple_item(method_tokens, &method_sig.name.to_string())
+                self.inject_doc_into_simple_item(method_tokens, &self.doc_name(&method_sig.name))
             }
             ModuleItem::Function(func_sig) => {
                 let mut func_tokens = TokenStream::new();
                 quote::ToTokens::to_tokens(&func_sig, &mut func_tokens);
-                self.inject_doc_into_simple_item(func_tokens, &func_sig.name.to_string())
+                self.inject_doc_into_simple_item(func_tokens, &self.doc_name(&func_sig.name))
             }
             ModuleItem::ImplBlock(impl_block) => self.process_impl_block(impl_block),
             ModuleItem::Module(module) => self.process_module_block(module),
             ModuleItem::Trait(trait_def) => self.process_trait_block(trait_def),
             ModuleItem::Enum(enum_sig) => self.process_enum(enum_sig),
             ModuleItem::Struct(struct_sig) => self.process_struct(struct_sig),
+            ModuleItem::Union(union_sig) => self.process_union(union_sig),
             ModuleItem::TypeAlias(type_alias) => {
                 let mut alias_tokens = TokenStream::new();
                 quote::ToTokens::to_tokens(&type_alias, &mut alias_tokens);
-                self.inject_doc_into_simple_item(alias_tokens, &type_alias.name.to_string())
+                self.inject_doc_into_simple_item(alias_tokens, &self.doc_name(&type_alias.name))
             }
             ModuleItem::Const(const_sig) => {
                 let mut const_tokens = TokenStream::new();
                 quote::ToTokens::to_tokens(&const_sig, &mut const_tokens);
-                self.inject_doc_into_simple_item(const_tokens, &const_sig.name.to_string())
+                self.inject_doc_into_simple_item(const_tokens, &self.doc_name(&const_sig.name))
             }
             ModuleItem::Static(static_sig) => {
                 let mut static_tokens = TokenStream::new();
                 quote::ToTokens::to_tokens(&static_sig, &mut static_tokens);
-                self.inject_doc_into_simple_item(static_tokens, &static_sig.name.to_string())
+                self.inject_doc_into_simple_item(static_tokens, &self.doc_name(&static_sig.name))
             }
+            ModuleItem::MacroRules(macro_sig) => {
+                let mut macro_tokens = TokenStream::new();
+                quote::ToTokens::to_tokens(&macro_sig, &mut macro_tokens);
+                self.inject_doc_into_simple_item(macro_tokens, &self.doc_name(&macro_sig.name))
+            }
+            ModuleItem::ExternBlock(extern_block) => self.process_extern_block(extern_block),
             ModuleItem::Other(token) => {
                 let mut tokens = TokenStream::new();
                 token.to_tokens(&mut tokens);
@@ -98,41 +490,32 @@ impl TokenProcessor {
     }
 
     fn process_impl_block(&self, impl_block: ImplBlockSig) -> TokenStream {
-        // Check if this is a trait impl (has "for" clause)
-        let context_path = if let Some(for_trait) = &impl_block.for_trait {
-            // This is "impl Trait for Type"
-            // target_type contains the TRAIT name (before "for")
-            let trait_name = extract_type_name(&impl_block.target_type);
-            // for_trait contains "for Type" - extract Type
-            let type_name = extract_first_ident_from_tokens(&for_trait.second);
-            // Context should be: Type/Trait
-            vec![type_name, trait_name]
-        } else {
-            // This is "impl Type"
-            // target_type is the type being implemented
-            let type_name = extract_type_name(&impl_block.target_type);
-            vec![type_name]
-        };
+        let context_path = named_impl_context(&impl_block, self.name.clone());
 
         // Create new processor with updated context
         let mut new_context = self.context.clone();
         new_context.extend(context_path);
 
+        // A trait impl's items are only named through the qualified `<Type as Trait>`
+        let rust_path = impl_rust_path(&impl_block, &self.rust_prefix());
+        let rust_context = if rust_path.starts_with('<') {
+            vec![rust_path]
+        } else {
+            let mut rust_context = self.rust_context.clone();
+            rust_context.push(rust_path);
+            rust_context
+        };
+
         // Access parsed items directly
         let module_content = &impl_block.items.content;
 
         let new_processor = TokenProcessor {
-            input: TokenStream::new(),
-            base_path: self.base_path.clone(),
-            cfg_attr: self.cfg_attr.clone(),
-            context: new_context,
-        };
-
-        let mut processed_content = TokenStream::new();
-        for item_delimited in &module_content.items.0 {
-            processed_content
-                .extend(new_processor.process_module_item(item_delimited.value.clone()));
+            rust_context,
+            ..self.nested(new_context)
         }
+        .within(&impl_block.attributes);
+
+        let processed_content = new_processor.process_items(&module_content.items);
 
         // Reconstruct impl block
         let mut output = TokenStream::new();
@@ -164,23 +547,20 @@ impl TokenProcessor {
 
     fn process_module_block(&self, module: ModuleSig) -> TokenStream {
         let mut new_context = self.context.clone();
-        new_context.push(module.name.to_string());
+        new_context.push(self.doc_name(&module.name));
+        let mut rust_context = self.rust_context.clone();
+        rust_context.push(module.name.to_string());
 
         // Access parsed items directly
         let module_content = &module.items.content;
 
         let new_processor = TokenProcessor {
-            input: TokenStream::new(),
-            base_path: self.base_path.clone(),
-            cfg_attr: self.cfg_attr.clone(),
-            context: new_context,
-        };
-
-        let mut processed_content = TokenStream::new();
-        for item_delimited in &module_content.items.0 {
-            processed_content
-                .extend(new_processor.process_module_item(item_delimited.value.clone()));
+            rust_context,
+            ..self.nested(new_context)
         }
+        .within(&module.attributes);
+
+        let processed_content = new_processor.process_items(&module_content.items);
 
         // Reconstruct module
         let mut output = TokenStream::new();
@@ -201,25 +581,54 @@ impl TokenProcessor {
         output
     }
 
+    /// Documents foreign items as if they were declared in the enclosing module
+    ///
+    /// The block itself is left alone, as rustdoc doesn't show docs on extern blocks.
+    fn process_extern_block(&self, extern_block: ExternBlockSig) -> TokenStream {
+        let new_processor = self
+            .nested(self.context.clone())
+            .within(&extern_block.attributes);
+
+        let processed_content = new_processor.process_items(&extern_block.items.content.items);
+
+        // Reconstruct extern block
+        let mut output = TokenStream::new();
+        if let Some(attrs) = extern_block.attributes {
+            for attr in attrs.0 {
+                attr.to_tokens(&mut output);
+            }
+        }
+        if let Some(unsafe_kw) = extern_block.unsafe_kw {
+            unsafe_kw.to_tokens(&mut output);
+        }
+        extern_block._extern.to_tokens(&mut output);
+        if let Some(abi) = extern_block.abi {
+            abi.to_tokens(&mut output);
+        }
+
+        let group = proc_macro2::Group::new(proc_macro2::Delimiter::Brace, processed_content);
+        output.extend(std::iter::once(proc_macro2::TokenTree::Group(group)));
+
+        output
+    }
+
     fn process_trait_block(&self, trait_def: TraitSig) -> TokenStream {
+        let trait_name = self.doc_name(&trait_def.name);
         let mut new_context = self.context.clone();
-        new_context.push(trait_def.name.to_string());
+        new_context.push(trait_name.clone());
+        let mut rust_context = self.rust_context.clone();
+        rust_context.push(trait_def.name.to_string());
 
         // Access parsed items directly
         let trait_content = &trait_def.items.content;
 
         let new_processor = TokenProcessor {
-            input: TokenStream::new(),
-            base_path: self.base_path.clone(),
-            cfg_attr: self.cfg_attr.clone(),
-            context: new_context,
-        };
-
-        let mut processed_content = TokenStream::new();
-        for item_delimited in &trait_content.items.0 {
-            processed_content
-                .extend(new_processor.process_module_item(item_delimited.value.clone()));
+            rust_context,
+            ..self.nested(new_context)
         }
+        .within(&trait_def.attributes);
+
+        let processed_content = new_processor.process_items(&trait_content.items);
 
         // Inject doc for trait itself
         let mut output = TokenStream::new();
@@ -246,7 +655,6 @@ impl TokenProcessor {
             where_clause.to_tokens(&mut output);
         }
 
-        let trait_name = trait_def.name.to_string();
         let trait_with_doc = self.inject_doc_into_simple_item(output, &trait_name);
 
         // Combine with processed body
@@ -258,9 +666,9 @@ impl TokenProcessor {
     }
 
     fn process_struct(&self, struct_sig: crate::parse::StructSig) -> TokenStream {
-        let struct_name = struct_sig.name.to_string();
+        let struct_name = self.doc_name(&struct_sig.name);
 
-        // Process struct body for named fields
+        // Process struct body for named and positional fields
         let processed_body = match &struct_sig.body {
             crate::parse::StructBody::Named(fields_containing) => {
                 if let Some(fields_cdv) = fields_containing.content.as_ref() {
@@ -276,6 +684,24 @@ impl TokenProcessor {
                     ts
                 }
             }
+            crate::parse::StructBody::Tuple(tuple) => {
+                let processed_fields = tuple
+                    .first
+                    .content
+                    .as_ref()
+                    .map(|fields_cdv| {
+                        self.process_tuple_fields(fields_cdv, |tokens, index| {
+                            self.inject_doc_for_struct_field(tokens, &struct_name, index)
+                        })
+                    })
+                    .unwrap_or_default();
+                let group =
+                    proc_macro2::Group::new(proc_macro2::Delimiter::Parenthesis, processed_fields);
+                let mut ts = TokenStream::new();
+                ts.extend(std::iter::once(proc_macro2::TokenTree::Group(group)));
+                unsynn::ToTokens::to_tokens(&tuple.second, &mut ts);
+                ts
+            }
             other => {
                 let mut ts = TokenStream::new();
                 quote::ToTokens::to_tokens(other, &mut ts);
@@ -309,6 +735,43 @@ impl TokenProcessor {
         final_output
     }
 
+    fn process_union(&self, union_sig: crate::parse::UnionSig) -> TokenStream {
+        let union_name = self.doc_name(&union_sig.name);
+
+        // Union fields are documented like named struct fields
+        let processed_fields = union_sig
+            .fields
+            .content
+            .as_ref()
+            .map(|fields_cdv| self.process_struct_fields(fields_cdv, &union_name))
+            .unwrap_or_default();
+
+        // Reconstruct union
+        let mut output = TokenStream::new();
+        if let Some(attrs) = union_sig.attributes {
+            for attr in attrs.0 {
+                attr.to_tokens(&mut output);
+            }
+        }
+        if let Some(vis) = union_sig.visibility {
+            vis.to_tokens(&mut output);
+        }
+        union_sig._union.to_tokens(&mut output);
+        union_sig.name.to_tokens(&mut output);
+        if let Some(generics) = union_sig.generics {
+            generics.to_tokens(&mut output);
+        }
+        if let Some(where_clause) = union_sig.where_clause {
+            where_clause.to_tokens(&mut output);
+        }
+
+        let mut final_output = self.inject_doc_into_simple_item(output, &union_name);
+        let group = proc_macro2::Group::new(proc_macro2::Delimiter::Brace, processed_fields);
+        final_output.extend(std::iter::once(proc_macro2::TokenTree::Group(group)));
+
+        final_output
+    }
+
     fn process_struct_fields(
         &self,
         fields_cdv: &CommaDelimitedVec<crate::parse::StructField>,
@@ -317,14 +780,44 @@ impl TokenProcessor {
         let mut output = TokenStream::new();
 
         for (idx, field_delimited) in fields_cdv.0.iter().enumerate() {
-            let field = &field_delimited.value;
+            let mut field = field_delimited.value.clone();
+            let directive = self.take_directive(&mut field.attributes);
             let field_name = field.name.to_string();
 
             let mut field_tokens = TokenStream::new();
-            quote::ToTokens::to_tokens(field, &mut field_tokens);
+            quote::ToTokens::to_tokens(&field, &mut field_tokens);
 
-            let documented =
-                self.inject_doc_for_struct_field(field_tokens, struct_name, &field_name);
+            let documented = self.inject_with_directive(directive, field_tokens, |tokens| {
+                self.inject_doc_for_struct_field(tokens, struct_name, &field_name)
+            });
+            output.extend(documented);
+
+            if idx < fields_cdv.0.len() - 1 {
+                output.extend(quote::quote! { , });
+            }
+        }
+
+        output
+    }
+
+    /// Documents positional fields, which are named by their index
+    fn process_tuple_fields(
+        &self,
+        fields_cdv: &CommaDelimitedVec<crate::parse::TupleField>,
+        inject: impl Fn(TokenStream, &str) -> TokenStream,
+    ) -> TokenStream {
+        let mut output = TokenStream::new();
+
+        for (idx, field_delimited) in fields_cdv.0.iter().enumerate() {
+            let mut field = field_delimited.value.clone();
+            let directive = self.take_directive(&mut field.attributes);
+
+            let mut field_tokens = TokenStream::new();
+            quote::ToTokens::to_tokens(&field, &mut field_tokens);
+
+            let documented = self.inject_with_directive(directive, field_tokens, |tokens| {
+                inject(tokens, &idx.to_string())
+            });
             output.extend(documented);
 
             if idx < fields_cdv.0.len() - 1 {
@@ -346,11 +839,11 @@ impl TokenProcessor {
         path_parts.push(format!("{}/{}.md", struct_name, field_name));
 
         let full_path = path_parts.join("/");
-        omnidoc_impl(full_path, self.cfg_attr.clone(), field_tokens)
+        self.inject_derived_doc(full_path, field_tokens)
     }
 
     fn process_enum(&self, enum_sig: crate::parse::EnumSig) -> TokenStream {
-        let enum_name = enum_sig.name.to_string();
+        let enum_name = self.doc_name(&enum_sig.name);
 
         // Process enum variants
         let processed_variants = if let Some(variants_cdv) = enum_sig.variants.content.as_ref() {
@@ -394,25 +887,64 @@ impl TokenProcessor {
         let mut output = TokenStream::new();
 
         for (idx, variant_delimited) in variants_cdv.0.iter().enumerate() {
-            let variant = &variant_delimited.value;
+            let mut variant = variant_delimited.value.clone();
+            let directive = self.take_directive(&mut variant.attributes);
             let variant_name = variant.name.to_string();
 
-            // Check if this is a struct-valued variant
-            let documented = if let Some(crate::parse::EnumVariantData::Struct(fields_containing)) =
-                &variant.data
-            {
-                // Process struct-valued variant with fields
-                self.process_struct_valued_variant(
-                    variant,
-                    enum_name,
-                    &variant_name,
-                    fields_containing,
-                )
-            } else {
-                // Process simple variant (unit, tuple, or discriminant)
-                let mut variant_tokens = TokenStream::new();
-                quote::ToTokens::to_tokens(variant, &mut variant_tokens);
-                self.inject_doc_for_enum_variant(variant_tokens, enum_name, &variant_name)
+            // Variants with fields document each field below the variant's own doc
+            let documented = match &variant.data {
+                Some(crate::parse::EnumVariantData::Struct(fields_containing))
+                    if directive != Some(ItemDirective::Skip) =>
+                {
+                    let processed_fields = fields_containing
+                        .content
+                        .as_ref()
+                        .map(|fields_cdv| {
+                            self.process_enum_variant_fields(fields_cdv, enum_name, &variant_name)
+                        })
+                        .unwrap_or_default();
+                    self.process_variant_with_fields(
+                        &variant,
+                        directive,
+                        enum_name,
+                        proc_macro2::Group::new(proc_macro2::Delimiter::Brace, processed_fields),
+                    )
+                }
+                Some(crate::parse::EnumVariantData::Tuple(fields_containing))
+                    if directive != Some(ItemDirective::Skip) =>
+                {
+                    let processed_fields = fields_containing
+                        .content
+                        .as_ref()
+                        .map(|fields_cdv| {
+                            self.process_tuple_fields(fields_cdv, |tokens, index| {
+                                self.inject_doc_for_enum_variant_field(
+                                    tokens,
+                                    enum_name,
+                                    &variant_name,
+                                    index,
+                                )
+                            })
+                        })
+                        .unwrap_or_default();
+                    self.process_variant_with_fields(
+                        &variant,
+                        directive,
+                        enum_name,
+                        proc_macro2::Group::new(
+                            proc_macro2::Delimiter::Parenthesis,
+                            processed_fields,
+                        ),
+                    )
+                }
+                _ => {
+                    // Process simple variant (unit or discriminant)
+                    let mut variant_tokens = TokenStream::new();
+                    quote::ToTokens::to_tokens(&variant, &mut variant_tokens);
+                    self.inject_with_directive(directive, variant_tokens, |tokens| {
+                        self.inject_doc_for_enum_variant(tokens, enum_name, &variant_name)
+                    })
+                }
             };
 
             output.extend(documented);
@@ -425,14 +957,12 @@ impl TokenProcessor {
         output
     }
 
-    fn process_struct_valued_variant(
+    fn process_variant_with_fields(
         &self,
         variant: &crate::parse::EnumVariant,
+        directive: Option<ItemDirective>,
         enum_name: &str,
-        variant_name: &str,
-        fields_containing: &BraceGroupContaining<
-            Option<CommaDelimitedVec<crate::parse::StructField>>,
-        >,
+        processed_fields: proc_macro2::Group,
     ) -> TokenStream {
         // First, inject doc for the variant itself
         let mut variant_header = TokenStream::new();
@@ -448,20 +978,15 @@ impl TokenProcessor {
         variant.name.to_tokens(&mut variant_header);
 
         // Inject doc for variant name
-        let variant_with_doc =
-            self.inject_doc_for_enum_variant(variant_header, enum_name, variant_name);
+        let variant_name = variant.name.to_string();
+        let mut output = self.inject_with_directive(directive, variant_header, |tokens| {
+            self.inject_doc_for_enum_variant(tokens, enum_name, &variant_name)
+        });
 
-        // Now process the fields
-        let processed_fields = if let Some(fields_cdv) = fields_containing.content.as_ref() {
-            self.process_enum_variant_fields(fields_cdv, enum_name, variant_name)
-        } else {
-            TokenStream::new()
-        };
-
-        // Combine: variant_name { fields }
-        let mut output = variant_with_doc;
-        let group = proc_macro2::Group::new(proc_macro2::Delimiter::Brace, processed_fields);
-        output.extend(std::iter::once(proc_macro2::TokenTree::Group(group)));
+        // Combine: variant_name { fields } or variant_name(fields)
+        output.extend(std::iter::once(proc_macro2::TokenTree::Group(
+            processed_fields,
+        )));
 
         output
     }
@@ -475,18 +1000,16 @@ impl TokenProcessor {
         let mut output = TokenStream::new();
 
         for (idx, field_delimited) in fields_cdv.0.iter().enumerate() {
-            let field = &field_delimited.value;
+            let mut field = field_delimited.value.clone();
+            let directive = self.take_directive(&mut field.attributes);
             let field_name = field.name.to_string();
 
             let mut field_tokens = TokenStream::new();
-            quote::ToTokens::to_tokens(field, &mut field_tokens);
+            quote::ToTokens::to_tokens(&field, &mut field_tokens);
 
-            let documented = self.inject_doc_for_enum_variant_field(
-                field_tokens,
-                enum_name,
-                variant_name,
-                &field_name,
-            );
+            let documented = self.inject_with_directive(directive, field_tokens, |tokens| {
+                self.inject_doc_for_enum_variant_field(tokens, enum_name, variant_name, &field_name)
+            });
             output.extend(documented);
 
             if idx < fields_cdv.0.len() - 1 {
@@ -510,7 +1033,7 @@ impl TokenProcessor {
         path_parts.push(format!("{}/{}/{}.md", enum_name, variant_name, field_name));
 
         let full_path = path_parts.join("/");
-        omnidoc_impl(full_path, self.cfg_attr.clone(), field_tokens)
+        self.inject_derived_doc(full_path, field_tokens)
     }
 
     fn inject_doc_for_enum_variant(
@@ -524,7 +1047,7 @@ impl TokenProcessor {
         path_parts.push(format!("{}/{}.md", enum_name, variant_name));
 
         let full_path = path_parts.join("/");
-        omnidoc_impl(full_path, self.cfg_attr.clone(), variant_tokens)
+        self.inject_derived_doc(full_path, variant_tokens)
     }
 
     fn inject_doc_into_simple_item(
@@ -532,40 +1055,654 @@ impl TokenProcessor {
         item_tokens: TokenStream,
         item_name: &str,
     ) -> TokenStream {
-        let mut path_parts = vec![self.base_path.clone()];
-        path_parts.extend(self.context.iter().cloned());
-        path_parts.push(format!("{}.md", item_name));
+        match &self.redirect {
+            Some(redirect) => self.include_doc(redirect.clone(), item_tokens),
+            None => {
+                let mut path_parts = vec![self.base_path.clone()];
+                path_parts.extend(self.context.iter().cloned());
+                path_parts.push(format!("{}.md", item_name));
+                self.inject_derived_doc(path_parts.join("/"), item_tokens)
+            }
+        }
+    }
 
-        let full_path = path_parts.join("/");
-        omnidoc_impl(full_path, self.cfg_attr.clone(), item_tokens)
+    /// Injects the doc file at an item's derived path, reporting it if another item already has it
+    ///
+    /// Items under `#[cfg(...)]`, or in a container under one, are left out, since alternates of one
+    /// item share their docs.
+    fn inject_derived_doc(&self, full_path: String, item_tokens: TokenStream) -> TokenStream {
+        if !self.under_cfg
+            && !has_cfg_attr(&item_tokens)
+            && !self.doc_paths.borrow_mut().insert(full_path.clone())
+        {
+            let span = item_tokens
+                .clone()
+                .into_iter()
+                .next()
+                .map_or_else(proc_macro2::Span::call_site, |t| t.span());
+            let message = format!(
+                "another item already takes its docs from `{}`; give this one its own file with #[syncdoc(path = \"...\")]",
+                full_path
+            );
+            self.diagnostics
+                .borrow_mut()
+                .push(quote::quote_spanned! {span=> compile_error!(#message); });
+        }
+        self.include_doc(full_path, item_tokens)
+    }
+
+    /// Injects the doc file at `doc_path`, or reports it as missing on the item's name
+    ///
+    /// A missing file is a spanned error naming the path (rather than the compiler's own
+    /// `include_str!` error), unless the `missing` mode allows it. A skipped item is not rebuilt
+    /// when its file appears later, since there is no `include_str!` for the compiler to track.
+    /// Inline docs already on the item are kept, dropped or reported by the `merge` policy. A file
+    /// that [needs processing](needs_processing) is read and injected as a string literal instead.
+    /// The file's [feature companions](crate::features) follow its docs. When the docs are
+    /// translated, each translation of the file goes on the item too, under its locale's cfg.
+    fn include_doc(&self, doc_path: String, item_tokens: TokenStream) -> TokenStream {
+        let item_tokens = match (self.merge, inline_doc_span(&item_tokens)) {
+            (MergeDocs::Concat, _) | (_, None) => item_tokens,
+            (MergeDocs::PreferFile, Some(_)) => strip_inline_docs(item_tokens),
+            (MergeDocs::PreferInline, Some(_)) => return item_tokens,
+            (MergeDocs::Error, Some(span)) => {
+                let message = format!(
+                    "this item has inline docs as well as the doc file `{}`; move them into the file or remove one",
+                    doc_path
+                );
+                self.diagnostics
+                    .borrow_mut()
+                    .push(quote::quote_spanned! {span=> compile_error!(#message); });
+                item_tokens
+            }
+        };
+        let mut item_tokens = self.include_doc_file(&doc_path, item_tokens);
+        for (cfg, docs_root) in &self.translations {
+            if let Some(rel_path) = doc_path.strip_prefix(&self.docs_root) {
+                let translation = self.translation(cfg, docs_root);
+                let doc_path = format!("{}{}", docs_root, rel_path);
+                item_tokens = translation.include_doc_file(&doc_path, item_tokens);
+            }
+        }
+        item_tokens
+    }
+
+    /// Injects the doc file at `doc_path` in this processor's locale, as [`Self::include_doc`] does
+    fn include_doc_file(&self, doc_path: &str, item_tokens: TokenStream) -> TokenStream {
+        // Feature docs come after the file's, so they go on the item before its doc is added
+        let mut with_features = feature_doc_attrs(doc_path, self.doc_cfg().as_ref());
+        with_features.extend(item_tokens);
+        let item_tokens = with_features;
+        if self.layout == DocLayout::Sections {
+            if let Some((file, key)) = section_of(doc_path, &self.base_path) {
+                let item_path = self.item_path(doc_path, &item_tokens);
+                return self.include_section(file, key, item_path, item_tokens);
+            }
+        }
+        let file = self.localised(doc_path);
+        match read_doc_file(&file) {
+            Some(Ok(markdown)) => {
+                let untitled = self.untitled(&file, &markdown);
+                if untitled.is_none() && !self.restyles(&markdown) && !needs_processing(&markdown) {
+                    return omnidoc_impl(file, self.doc_cfg(), item_tokens);
+                }
+                self.tracked.borrow_mut().insert(file.clone());
+                let item_path = self.item_path(doc_path, &item_tokens);
+                let markdown = untitled.unwrap_or(markdown);
+                return self.inject_doc_text(&file, &markdown, item_path, item_tokens);
+            }
+            None => return omnidoc_impl(file, self.doc_cfg(), item_tokens),
+            Some(Err(_)) => {}
+        }
+        let message = format!(
+            "missing doc file `{}`; run `syncdoc --touch` to create empty files for all missing docs",
+            doc_path
+        );
+        self.report_missing(doc_path, message, item_tokens)
+    }
+
+    /// An item's doc file without its title, when titles are stripped and it has one
+    ///
+    /// The title names the doc file, which is named for its item unless it was redirected.
+    fn untitled(&self, doc_path: &str, markdown: &str) -> Option<String> {
+        let name = std::path::Path::new(doc_path).file_stem()?.to_str()?;
+        self.strip_title
+            .then(|| strip_title(markdown, name))
+            .flatten()
+    }
+
+    /// The doc file at `doc_path`, or the default locale's copy if it isn't translated
+    fn localised(&self, doc_path: &str) -> String {
+        localised(doc_path, &self.docs_root, self.fallback_root.as_deref())
+    }
+
+    /// Whether the configured alert style, image embedding or link rewriting changes the markdown
+    fn restyles(&self, markdown: &str) -> bool {
+        (self.admonitions != Admonitions::Keep && has_alerts(markdown))
+            || (self.embed_images.is_some() && has_local_images(markdown))
+            || (self.doc_items.is_some() && has_doc_links(markdown))
+    }
+
+    /// The markdown with links to other doc files pointed at their items, if `rewrite-links` is on
+    ///
+    /// `doc_file` is the path of the markdown relative to the docs root.
+    fn rewrite_links(&self, markdown: String, doc_file: &str) -> String {
+        match &self.doc_items {
+            Some(items) => rewrite_links(&markdown, doc_file, &mut items.borrow_mut()),
+            None => markdown,
+        }
+    }
+
+    /// The markdown with the local images it references embedded, if `embed-images` is on
+    ///
+    /// Image paths are relative to `file`, and each image is tracked for rebuilds.
+    fn inline_images(&self, file: &str, markdown: &str) -> core::result::Result<String, String> {
+        let Some(max_size) = self.embed_images else {
+            return Ok(markdown.to_string());
+        };
+        embed_images(markdown, max_size, &mut |path| {
+            let image = beside(file, path);
+            let bytes = read_doc_bytes(&image)?.ok()?;
+            self.tracked_images.borrow_mut().insert(image);
+            Some(bytes)
+        })
+    }
+
+    /// Injects an item's section of its module's sections file as a string literal
+    ///
+    /// Each file is read once, and is tracked for rebuilds by [`track_doc_file`] on the output.
+    fn include_section(
+        &self,
+        file: String,
+        key: String,
+        item_path: String,
+        item_tokens: TokenStream,
+    ) -> TokenStream {
+        let file = self.localised(&file);
+        let mut cache = self.sections.borrow_mut();
+        let sections = cache
+            .entry(file.clone())
+            .or_insert_with(|| match read_doc_file(&file) {
+                Some(Ok(markdown)) => {
+                    self.tracked.borrow_mut().insert(file.clone());
+                    Some(Sections::parse(&markdown))
+                }
+                _ => None,
+            });
+        if let Some(doc) = sections.as_ref().and_then(|sections| sections.get(&key)) {
+            let doc = doc.to_string();
+            drop(cache);
+            return self.inject_doc_text(&file, &doc, item_path, item_tokens);
+        }
+        drop(cache);
+        let message = format!(
+            "missing section `{}` in `{}`; run `syncdoc --touch` to add empty sections for all missing docs",
+            key, file
+        );
+        self.report_missing(&file, message, item_tokens)
+    }
+
+    /// Injects docs read from `file`, putting any front matter's attributes on the item
+    ///
+    /// An item that already has `#[deprecated]` keeps its own, rather than getting two.
+    /// With `rewrite-links`, links to other doc files (in the file or its fragments) become links
+    /// to their items, and with `embed-images` the local images they show are embedded.
+    /// Included fragments and snippet files are tracked for rebuilds like the file itself.
+    /// Placeholders (including those from fragments) are filled in for the item at `item_path`,
+    /// relative to this file's module, before snippets go in, so the code is shown as written.
+    fn inject_doc_text(
+        &self,
+        file: &str,
+        markdown: &str,
+        item_path: String,
+        item_tokens: TokenStream,
+    ) -> TokenStream {
+        let docs_root = format!("{}/", self.docs_root);
+        // An untranslated file is in the default locale's tree, but links within it are the same
+        let doc_file = file.strip_prefix(&docs_root).or_else(|| {
+            let fallback_root = format!("{}/", self.fallback_root.as_deref()?);
+            file.strip_prefix(&fallback_root)
+        });
+        let parsed = FrontMatter::parse(markdown).and_then(|(front_matter, body)| {
+            let body = self.inline_images(file, body)?;
+            let body = match doc_file {
+                Some(doc_file) => self.rewrite_links(body, doc_file),
+                None => body,
+            };
+            // A fragment's images are relative to it, and can't fail the include itself
+            let mut image_error = None;
+            let body = resolve_includes(&body, &mut |path| {
+                let fragment = self.localised(&format!("{}{}", docs_root, path));
+                let content = read_doc_file(&fragment)?.ok()?;
+                let content = match self.inline_images(&fragment, &content) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        image_error.get_or_insert(format!("{} (from `{}`)", e, path));
+                        content
+                    }
+                };
+                self.tracked.borrow_mut().insert(fragment);
+                Some(self.rewrite_links(content, path))
+            })?;
+            if let Some(e) = image_error {
+                return Err(e);
+            }
+            self.tracked_env.borrow_mut().extend(env_vars(&body));
+            let item_name = item_path
+                .rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            // A trait impl's items already have the module inside their `<Type as Trait>`
+            let item_path = match module_path() {
+                module if module.is_empty() || item_path.starts_with('<') => item_path,
+                module => format!("{}::{}", module, item_path),
+            };
+            let body = expand(&body, &template_vars(item_name, item_path))?;
+            let body = resolve_snippets(&body, &mut |path| {
+                let content = read_doc_file(path)?.ok()?;
+                self.tracked.borrow_mut().insert(path.to_string());
+                Some(content)
+            })?;
+            Ok((front_matter, render_alerts(&body, &self.admonitions)))
+        });
+        match parsed {
+            Ok((front_matter, body)) => {
+                // Each locale's front matter only goes on the item in that locale
+                let attributes =
+                    front_matter.attributes(has_outer_attr(&item_tokens, "deprecated"));
+                let mut output = gated(attributes, self.locale_cfg.as_ref());
+                output.extend(literal_doc_impl(&body, self.doc_cfg(), item_tokens));
+                output
+            }
+            Err(e) => {
+                let span = name_span(&item_tokens);
+                let message = format!("{} in `{}`", e, file);
+                self.diagnostics
+                    .borrow_mut()
+                    .push(quote::quote_spanned! {span=> compile_error!(#message); });
+                item_tokens
+            }
+        }
+    }
+
+    /// An item's Rust path below the module, as in `Config::port` or `<Foo as From<A>>::from`
+    ///
+    /// The segments of a derived doc path after its container's name the item (a field's are
+    /// its struct's name and its own), and a redirected item is named by its ident instead.
+    fn item_path(&self, doc_path: &str, item_tokens: &TokenStream) -> String {
+        let derived = doc_path
+            .strip_prefix(&format!("{}/", self.base_path))
+            .and_then(|relative| relative.strip_suffix(".md"))
+            .map(|relative| relative.split('/').collect::<Vec<_>>())
+            .filter(|segments| {
+                segments.len() > self.context.len()
+                    && segments[..self.context.len()] == self.context[..]
+            });
+        let mut path = self.rust_context.clone();
+        match derived {
+            Some(segments) => path.extend(
+                segments[self.context.len()..]
+                    .iter()
+                    .map(|segment| segment.to_string()),
+            ),
+            None => path.extend(name_ident(item_tokens).map(|ident| ident.to_string())),
+        }
+        path.join("::")
+    }
+
+    /// Leaves an item with no doc to include undocumented, reporting it as `missing` says
+    fn report_missing(
+        &self,
+        doc_path: &str,
+        message: String,
+        item_tokens: TokenStream,
+    ) -> TokenStream {
+        let span = name_span(&item_tokens);
+        match self.missing {
+            MissingDocs::Error => self
+                .diagnostics
+                .borrow_mut()
+                .push(quote::quote_spanned! {span=> compile_error!(#message); }),
+            MissingDocs::Warn => self
+                .missing_warnings
+                .borrow_mut()
+                .push((span, doc_path.to_string())),
+            MissingDocs::Ignore => {}
+        }
+        item_tokens
+    }
+}
+
+/// Outer attributes, each put behind `cfg` as `#[cfg_attr(cfg, ...)]` if there is one
+fn gated(attributes: TokenStream, cfg: Option<&CfgPredicate>) -> TokenStream {
+    let Some(cfg) = cfg else {
+        return attributes;
+    };
+    attributes
+        .into_iter()
+        .filter_map(|token| match token {
+            proc_macro2::TokenTree::Group(group)
+                if group.delimiter() == proc_macro2::Delimiter::Bracket =>
+            {
+                let attribute = group.stream();
+                Some(quote::quote! { #[cfg_attr(#cfg, #attribute)] })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether an attribute's bracketed contents are `doc = ...`, as a `///` comment becomes
+fn is_inline_doc(attr: &proc_macro2::Group) -> bool {
+    let mut tokens = attr.stream().into_iter();
+    matches!(
+        (tokens.next(), tokens.next()),
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(eq)))
+            if ident == "doc" && eq.as_char() == '='
+    )
+}
+
+/// Whether the item has an outer attribute named `name`, like `#[deprecated(...)]`
+fn has_outer_attr(item_tokens: &TokenStream, name: &str) -> bool {
+    let tokens: Vec<TokenTree> = item_tokens.clone().into_iter().collect();
+    tokens.windows(2).any(|pair| match pair {
+        [TokenTree::Punct(pound), TokenTree::Group(group)]
+            if pound.as_char() == '#' && group.delimiter() == Delimiter::Bracket =>
+        {
+            matches!(group.stream().into_iter().next(), Some(TokenTree::Ident(ident)) if ident == name)
+        }
+        _ => false,
+    })
+}
+
+/// The span of the item's first inline doc attribute, if it has any
+fn inline_doc_span(item_tokens: &TokenStream) -> Option<proc_macro2::Span> {
+    let tokens: Vec<TokenTree> = item_tokens.clone().into_iter().collect();
+    tokens.windows(2).find_map(|pair| match pair {
+        [TokenTree::Punct(pound), TokenTree::Group(group)]
+            if pound.as_char() == '#'
+                && group.delimiter() == Delimiter::Bracket
+                && is_inline_doc(group) =>
+        {
+            Some(pound.span())
+        }
+        _ => None,
+    })
+}
+
+/// Removes the item's inline doc attributes, leaving its other attributes in place
+fn strip_inline_docs(item_tokens: TokenStream) -> TokenStream {
+    let mut output = Vec::new();
+    for token in item_tokens {
+        if let TokenTree::Group(group) = &token {
+            if group.delimiter() == Delimiter::Bracket
+                && is_inline_doc(group)
+                && matches!(output.last(), Some(TokenTree::Punct(p)) if p.as_char() == '#')
+            {
+                output.pop();
+                continue;
+            }
+        }
+        output.push(token);
+    }
+    output.into_iter().collect()
+}
+
+/// Keywords that start an item the grammar should have parsed
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "union",
+    "trait",
+    "impl",
+    "mod",
+    "type",
+    "const",
+    "static",
+    "macro_rules",
+    "extern",
+];
+
+/// The keyword of an item that failed to parse, if `items` starts with one
+///
+/// `extern crate` and `mod name;` are left out, as they have nothing to document here.
+fn unparsed_item_keyword(items: &[Delimited<ModuleItem, Nothing>]) -> Option<&Ident> {
+    let token = |i: usize| match items.get(i).map(|item| &item.value) {
+        Some(ModuleItem::Other(token)) => Some(token),
+        _ => None,
+    };
+    let keyword = match token(0)? {
+        TokenTree::Ident(ident) if ITEM_KEYWORDS.contains(&ident.to_string().as_str()) => ident,
+        _ => return None,
+    };
+    match (keyword.to_string().as_str(), token(1), token(2)) {
+        ("extern", Some(TokenTree::Ident(next)), _) if next == "crate" => None,
+        ("mod", Some(TokenTree::Ident(_)), Some(TokenTree::Punct(p))) if p.as_char() == ';' => None,
+        _ => Some(keyword),
     }
 }
 
-fn extract_type_name(
-    target_type: &unsynn::Many<
-        unsynn::Cons<
-            unsynn::Except<unsynn::Either<crate::parse::KFor, unsynn::BraceGroup>>,
-            proc_macro2::TokenTree,
-        >,
-    >,
-) -> String {
-    if let Some(first) = target_type.0.first() {
-        if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-            return ident.to_string();
+/// Tracks where an item that failed to parse ends
+#[derive(Default)]
+struct UnparsedItem {
+    /// Open `<` brackets, inside which an `=` is an associated type binding
+    angle_depth: usize,
+    /// The last token was the `-` of a `->`
+    after_minus: bool,
+    /// Seen an `=`, after which braces belong to a value (`const X: S = S { .. };`)
+    in_value: bool,
+}
+
+impl UnparsedItem {
+    /// Whether `tokens`, the next part of the item, end it with a `;` or its body
+    fn ends_with(&mut self, tokens: &TokenStream) -> bool {
+        tokens.clone().into_iter().any(|token| {
+            let after_minus = std::mem::take(&mut self.after_minus);
+            match token {
+                TokenTree::Punct(p) => match p.as_char() {
+                    ';' => return true,
+                    '<' => self.angle_depth += 1,
+                    '>' if !after_minus => self.angle_depth = self.angle_depth.saturating_sub(1),
+                    '-' => self.after_minus = p.spacing() == Spacing::Joint,
+                    '=' if self.angle_depth == 0 => self.in_value = true,
+                    _ => {}
+                },
+                TokenTree::Group(g) => {
+                    return !self.in_value && g.delimiter() == Delimiter::Brace;
+                }
+                _ => {}
+            }
+            false
+        })
+    }
+}
+
+/// The span of an item's name, skipping its attributes, visibility and keywords
+///
+/// Falls back to the item's first token for items without a name of their own, such as
+/// tuple fields and extern blocks.
+fn name_span(item_tokens: &TokenStream) -> proc_macro2::Span {
+    if let Some(ident) = name_ident(item_tokens) {
+        return ident.span();
+    }
+    let mut tokens = item_tokens.clone().into_iter();
+    while let Some(token) = tokens.next() {
+        match &token {
+            // Attributes: `#` followed by a bracketed group
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == '#' => {
+                tokens.next();
+            }
+            _ => return token.span(),
         }
     }
-    "Unknown".to_string()
+    proc_macro2::Span::call_site()
+}
+
+/// An item's name, after its attributes, visibility and keywords, if it has one
+fn name_ident(item_tokens: &TokenStream) -> Option<Ident> {
+    const KEYWORDS: &[&str] = &[
+        "pub",
+        "unsafe",
+        "async",
+        "extern",
+        "default",
+        "auto",
+        "fn",
+        "struct",
+        "enum",
+        "union",
+        "trait",
+        "type",
+        "const",
+        "static",
+        "mut",
+        "mod",
+        "macro_rules",
+    ];
+    let mut tokens = item_tokens.clone().into_iter().peekable();
+    let mut after_pub = false;
+    while let Some(token) = tokens.next() {
+        match &token {
+            // Attributes: `#` followed by a bracketed group
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == '#' => {
+                tokens.next();
+                continue;
+            }
+            _ => {}
+        }
+        match &token {
+            proc_macro2::TokenTree::Ident(ident)
+                if KEYWORDS.contains(&ident.to_string().as_str()) =>
+            {
+                after_pub = *ident == "pub";
+                continue;
+            }
+            proc_macro2::TokenTree::Ident(ident) => return Some(ident.clone()),
+            proc_macro2::TokenTree::Group(group)
+                if after_pub && group.delimiter() == proc_macro2::Delimiter::Parenthesis => {}
+            // `macro_rules!` and the ABI string of `extern "C" fn`
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == '!' => {}
+            proc_macro2::TokenTree::Literal(_) => {}
+            _ => break,
+        }
+        after_pub = false;
+    }
+    None
+}
+
+/// The path of the module being expanded within its crate, such as `types::shapes`
+///
+/// Empty for the crate root, or outside of a compiler-driven expansion.
+fn module_path() -> String {
+    proc_macro2::Span::call_site()
+        .local_file()
+        .map(|local_file| crate::path_utils::rust_module_path(&local_file.to_string_lossy()))
+        .unwrap_or_default()
+}
+
+/// Whether items can go after this one, which is so for every kind that can't be in an impl or trait
+///
+/// A function, const or type alias given `#[omnidoc]` on its own may be in one.
+fn takes_sibling_items(item: &TokenStream) -> bool {
+    let tokens: Vec<proc_macro2::TokenTree> = item.clone().into_iter().collect();
+    matches!(
+        item_keyword(&tokens).as_deref(),
+        Some(
+            "struct"
+                | "enum"
+                | "union"
+                | "mod"
+                | "trait"
+                | "impl"
+                | "extern"
+                | "macro_rules"
+                | "static"
+                | "use"
+        )
+    )
+}
+
+/// A warning at `span` that the item there has no doc file at `doc_path`
+///
+/// It is the use of a `#[deprecated]` const of its own, so it needs no crate to be in scope.
+fn missing_doc_warning(span: proc_macro2::Span, doc_path: &str) -> TokenStream {
+    let note = format!(
+        "no markdown file was found for this item at `{}`, so it is left undocumented",
+        doc_path
+    );
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            const MISSING_DOC: () = ();
+            MISSING_DOC
+        };
+    }
 }
 
-fn extract_first_ident_from_tokens(
-    tokens: &unsynn::Many<unsynn::Cons<unsynn::Except<unsynn::BraceGroup>, proc_macro2::TokenTree>>,
-) -> String {
-    if let Some(first) = tokens.0.first() {
-        if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-            return ident.to_string();
+/// The keyword that says what kind of item the tokens are, like `fn` or `struct`
+///
+/// Attributes, visibility and qualifiers, as in `#[inline] pub(crate) const unsafe fn`, are
+/// skipped over.
+fn item_keyword(tokens: &[proc_macro2::TokenTree]) -> Option<String> {
+    use proc_macro2::{Delimiter, TokenTree};
+    let mut i = 0;
+    while let Some(token) = tokens.get(i) {
+        let TokenTree::Ident(ident) = token else {
+            // An outer attribute, `#[...]`
+            match token {
+                TokenTree::Punct(pound) if pound.as_char() == '#' => i += 2,
+                _ => return None,
+            }
+            continue;
+        };
+        let word = ident.to_string();
+        let next = match tokens.get(i + 1) {
+            Some(TokenTree::Ident(next)) => next.to_string(),
+            _ => String::new(),
+        };
+        match word.as_str() {
+            "pub" => {
+                i += 1;
+                if matches!(tokens.get(i), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+                {
+                    i += 1;
+                }
+            }
+            "async" | "unsafe" | "safe" | "default" | "auto" => i += 1,
+            "const" if matches!(next.as_str(), "fn" | "async" | "unsafe" | "extern") => i += 1,
+            // `extern "C" fn` is a function, but `extern "C" { ... }` and `extern crate` aren't
+            "extern" => {
+                let after = match tokens.get(i + 1) {
+                    Some(TokenTree::Literal(_)) => i + 2,
+                    _ => i + 1,
+                };
+                match tokens.get(after) {
+                    Some(TokenTree::Ident(next)) if next == "fn" => i = after,
+                    _ => return Some(word),
+                }
+            }
+            _ => return Some(word),
         }
     }
-    "Unknown".to_string()
+    None
+}
+
+/// Whether the item carries a `#[cfg(...)]` attribute
+fn has_cfg_attr(item_tokens: &TokenStream) -> bool {
+    let tokens: Vec<proc_macro2::TokenTree> = item_tokens.clone().into_iter().collect();
+    tokens.windows(2).any(|pair| match pair {
+        [proc_macro2::TokenTree::Punct(pound), proc_macro2::TokenTree::Group(group)] => {
+            pound.as_char() == '#'
+                && group.delimiter() == proc_macro2::Delimiter::Bracket
+                && matches!(
+                    group.stream().into_iter().next(),
+                    Some(proc_macro2::TokenTree::Ident(ident)) if ident == "cfg"
+                )
+        }
+        _ => false,
+    })
 }
 
 #[cfg(test)]
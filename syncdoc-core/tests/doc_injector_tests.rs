@@ -1,5 +1,6 @@
 // syncdoc-core/tests/doc_injector_tests.rs
 use insta::assert_snapshot;
+use std::fs;
 
 mod helpers;
 use helpers::TestCrate;
@@ -131,3 +132,997 @@ fn attributed_function() {
 
     assert_snapshot!(test_with_code("test_multiple_attributes", code));
 }
+
+/// Writes a lib.rs with one documented function and two undocumented items
+fn write_partial_docs(crate_under_test: &TestCrate, omnidoc_args: &str) {
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        format!(
+            "#![doc = include_str!(\"../docs/lib.md\")]\n\n\
+            #[syncdoc::omnidoc({})]\n\
+            pub mod api {{\n    pub fn documented() {{}}\n    pub fn undocumented() {{}}\n    \
+            pub const UNDOCUMENTED: u8 = 0;\n}}\n",
+            omnidoc_args
+        ),
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Crate docs\n");
+    crate_under_test.write_doc("lib/api/documented.md", "Has docs\n");
+}
+
+/// Appends a line to the test crate's `[package.metadata.syncdoc]` table
+fn add_syncdoc_config(crate_under_test: &TestCrate, line: &str) {
+    let manifest = crate_under_test.root().join("Cargo.toml");
+    let mut content = fs::read_to_string(&manifest).unwrap();
+    content.push_str(line);
+    content.push('\n');
+    fs::write(manifest, content).unwrap();
+}
+
+/// Adds a build script calling `syncdoc_core::build::track_docs` to the test crate
+fn use_build_helper(crate_under_test: &TestCrate) {
+    let manifest = crate_under_test.root().join("Cargo.toml");
+    let content = fs::read_to_string(&manifest).unwrap();
+    // Before the syncdoc table, so lines added to the end still land in it
+    let content = content.replacen(
+        "[package.metadata.syncdoc]",
+        &format!(
+            "[build-dependencies]\nsyncdoc-core = {{ path = \"{}\" }}\n\n\
+            [package.metadata.syncdoc]",
+            env!("CARGO_MANIFEST_DIR")
+        ),
+        1,
+    );
+    fs::write(manifest, content).unwrap();
+    fs::write(
+        crate_under_test.root().join("build.rs"),
+        "fn main() {\n    syncdoc_core::build::track_docs().unwrap();\n}\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_missing_doc_is_an_error_by_default() {
+    let crate_under_test = TestCrate::new("test_missing_doc_default");
+    write_partial_docs(&crate_under_test, "path = \"docs\"");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing doc file to fail the build");
+    // Every missing file is reported, on the item's name, with its manifest-relative path
+    assert!(
+        stderr.contains("missing doc file `docs/lib/api/undocumented.md`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:6:12"), "STDERR:\n{}", stderr);
+    assert!(
+        stderr.contains("missing doc file `docs/lib/api/UNDOCUMENTED.md`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:7:15"), "STDERR:\n{}", stderr);
+    assert!(stderr.contains("syncdoc --touch"), "STDERR:\n{}", stderr);
+    assert!(!stderr.contains("couldn't read"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_optional_skips_missing_doc() {
+    let crate_under_test = TestCrate::new("test_missing_doc_optional");
+    write_partial_docs(&crate_under_test, "path = \"docs\", optional");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(!stderr.contains("warning"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_missing_ignore_from_config() {
+    let crate_under_test = TestCrate::new("test_missing_doc_ignore");
+    add_syncdoc_config(&crate_under_test, "missing = \"ignore\"");
+    write_partial_docs(&crate_under_test, "");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(!stderr.contains("warning"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_missing_warn_from_config() {
+    let crate_under_test = TestCrate::new("test_missing_doc_warn");
+    add_syncdoc_config(&crate_under_test, "missing = \"warn\"");
+    write_partial_docs(&crate_under_test, "");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(
+        stderr.contains("no markdown file was found for this item"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("pub fn undocumented"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(!stderr.contains("pub fn documented"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_missing_warn_under_a_renamed_dependency() {
+    let crate_under_test = TestCrate::new("test_missing_doc_renamed");
+    add_syncdoc_config(&crate_under_test, "missing = \"warn\"");
+    let manifest = crate_under_test.root().join("Cargo.toml");
+    let content = fs::read_to_string(&manifest).unwrap();
+    fs::write(
+        &manifest,
+        content.replacen("syncdoc = {", "docs = { package = \"syncdoc\",", 1),
+    )
+    .unwrap();
+    write_partial_docs(&crate_under_test, "");
+    let lib = crate_under_test.root().join("src/lib.rs");
+    let code = fs::read_to_string(&lib).unwrap();
+    fs::write(&lib, code.replace("syncdoc::omnidoc", "docs::omnidoc")).unwrap();
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(
+        stderr.contains("no markdown file was found for this item"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("pub fn undocumented"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_build_helper_picks_up_docs_added_later() {
+    let crate_under_test = TestCrate::new("test_build_helper");
+    use_build_helper(&crate_under_test);
+    add_syncdoc_config(&crate_under_test, "missing = \"warn\"");
+    write_partial_docs(&crate_under_test, "");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(
+        stderr.contains("pub fn undocumented"),
+        "STDERR:\n{}",
+        stderr
+    );
+
+    // Only the docs change, so without the build script Cargo would replay the warnings
+    crate_under_test.write_doc("lib/api/undocumented.md", "Now has docs\n");
+    crate_under_test.write_doc("lib/api/UNDOCUMENTED.md", "Now has docs\n");
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    assert!(
+        !stderr.contains("no markdown file was found for this item"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_strict_from_config_reports_unparsed_item() {
+    let crate_under_test = TestCrate::new("test_strict_from_config");
+    add_syncdoc_config(&crate_under_test, "strict = true");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = include_str!(\"../docs/lib.md\")]\n\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn bounded<T: Into<Vec<u8>>>(_t: T) {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Crate docs\n");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected strict mode to fail the build");
+    assert!(
+        stderr.contains("syncdoc could not parse this `fn` item"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:5:9"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_sections_layout_reads_each_items_heading() {
+    let crate_under_test = TestCrate::new("test_sections_layout");
+    add_syncdoc_config(&crate_under_test, "layout = \"sections\"");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n#![deny(missing_docs)]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub struct Config {\n        pub port: u16,\n    }\n    \
+        pub fn run() {}\n}\n",
+    )
+    .unwrap();
+    let sections = "Crate docs\n\n### `api::Config`\n\nSettings\n\n\
+        #### `api::Config::port`\n\nThe port\n\n### `api::run`\n\nRuns it\n";
+    crate_under_test.write_doc("lib.md", sections);
+
+    // Every item has docs (from its own section), so `missing_docs` stays quiet
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+
+    // The sections file is tracked, so dropping a section is picked up on the next build
+    crate_under_test.write_doc(
+        "lib.md",
+        sections.trim_end_matches("### `api::run`\n\nRuns it\n"),
+    );
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing section to fail the build");
+    assert!(
+        stderr.contains("missing section `api::run` in `../docs/lib.md`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:10:12"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_front_matter_becomes_item_attributes() {
+    let crate_under_test = TestCrate::new("test_front_matter");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n#![deny(deprecated)]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn old() {}\n    pub fn new() {}\n}\n\n\
+        /// Calls the old API\n\
+        pub fn legacy() {\n    api::old();\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Crate docs\n");
+    crate_under_test.write_doc("lib/api/new.md", "Does it the new way\n");
+    crate_under_test.write_doc(
+        "lib/api/old.md",
+        "---\ndeprecated: \"use `new`\"\nsince: \"1.4\"\naliases: [legacy_old]\n---\n\nDoes it the old way\n",
+    );
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected the deprecated call to fail the build");
+    assert!(
+        stderr.contains("use of deprecated function `api::old`: use `new`"),
+        "STDERR:\n{}",
+        stderr
+    );
+
+    // Keys the macro doesn't know are an error on the item, naming the file
+    crate_under_test.write_doc(
+        "lib/api/old.md",
+        "---\nauthor: me\n---\n\nDoes it the old way\n",
+    );
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected an unknown key to fail the build");
+    assert!(
+        stderr.contains("unknown front matter key `author`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("lib/api/old.md"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_processed_docs_on_methods_are_tracked() {
+    let crate_under_test = TestCrate::new("test_method_tracking");
+    add_syncdoc_config(&crate_under_test, "embed-images = true");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "pub struct Port;\n\nimpl Port {\n    #[syncdoc::omnidoc]\n    pub fn open() {}\n}\n\n\
+        pub trait Connect {\n    #[syncdoc::omnidoc]\n    fn connect(&self);\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc(
+        "lib/open.md",
+        "Opens {{item.name}}.\n\n![A dot](img/dot.svg)\n",
+    );
+    crate_under_test.write_doc(
+        "lib/img/dot.svg",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><circle r=\"1\"/></svg>",
+    );
+    // A binary image can't be tracked on a method without a body, but still builds
+    crate_under_test.write_doc(
+        "lib/connect.md",
+        "Connects to {{crate.name}}.\n\n![A dot](img/dot.png)\n",
+    );
+    fs::write(
+        crate_under_test.root().join("docs/lib/img/dot.png"),
+        [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a],
+    )
+    .unwrap();
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+
+    // The doc file is tracked, so a mistake in it is picked up on the next build
+    crate_under_test.write_doc("lib/connect.md", "Connects to {{crate.authors}}.\n");
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(
+        !success,
+        "Expected an unknown placeholder to fail the build"
+    );
+    assert!(
+        stderr.contains("unknown placeholder `{{crate.authors}}`"),
+        "STDERR:\n{}",
+        stderr
+    );
+
+    // And so is a sections file
+    add_syncdoc_config(&crate_under_test, "layout = \"sections\"");
+    let sections = "Crate docs\n\n### `open`\n\nOpens it.\n\n### `connect`\n\nConnects.\n";
+    crate_under_test.write_doc("lib.md", sections);
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+    crate_under_test.write_doc(
+        "lib.md",
+        sections.trim_end_matches("### `connect`\n\nConnects.\n"),
+    );
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing section to fail the build");
+    assert!(
+        stderr.contains("missing section `connect`"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_processed_module_docs_are_rebuilt_with_the_build_helper() {
+    let crate_under_test = TestCrate::new("test_module_doc_tracking");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "The {{crate.name}} crate\n");
+
+    // The docs are given as text, which builds without the helper but isn't watched
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+
+    use_build_helper(&crate_under_test);
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+
+    // With it, a mistake in them is picked up on the next build
+    crate_under_test.write_doc("lib.md", "The {{crate.authors}} crate\n");
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(
+        !success,
+        "Expected an unknown placeholder to fail the build"
+    );
+    assert!(
+        stderr.contains("unknown placeholder `{{crate.authors}}`"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_placeholders_are_filled_in() {
+    let crate_under_test = TestCrate::new("test_placeholders");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn run() {}\n\n    pub struct Port;\n\n    \
+        impl Port {\n        pub fn open() {}\n    }\n\n    \
+        impl From<u16> for Port {\n        fn from(_: u16) -> Self {\n            Port\n        }\n    }\n}\n",
+    )
+    .unwrap();
+    // The doctests check the values, as rustdoc sees them
+    crate_under_test.write_doc(
+        "lib.md",
+        "```\nassert_eq!(\"{{crate.name}} {{crate.version}}\", \"test_placeholders 0.1.0\");\n\
+        assert_eq!(\"{{item.name}} {{item.path}}\", \"test_placeholders crate\");\n```\n",
+    );
+    crate_under_test.write_doc(
+        "lib/api/run.md",
+        "```\nassert_eq!(\"{{item.name}} {{item.path}}\", \"run api::run\");\n\
+        assert_eq!(\"{{env.SYNCDOC_TEST_CHANNEL}}\", \"beta\");\nprintln!(\"{{}}\");\n```\n",
+    );
+    crate_under_test.write_doc("lib/api/Port.md", "A port.\n");
+    // Impl members are named by their Rust path, not their doc file's
+    crate_under_test.write_doc(
+        "lib/api/Port/open.md",
+        "```\nassert_eq!(\"{{item.path}}\", \"api::Port::open\");\n```\n",
+    );
+    crate_under_test.write_doc(
+        "lib/api/Port/From[u16]/from.md",
+        "```\nassert_eq!(\"{{item.name}} {{item.path}}\", \"from <api::Port as From<u16>>::from\");\n```\n",
+    );
+
+    let output = std::process::Command::new("cargo")
+        .args(["test", "--doc", "--quiet"])
+        .env("SYNCDOC_TEST_CHANNEL", "beta")
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "STDOUT:\n{}\nSTDERR:\n{}",
+        stdout,
+        stderr
+    );
+    assert!(stdout.contains("4 passed"), "STDOUT:\n{}", stdout);
+
+    // Unknown keys are an error on the item, naming the file
+    crate_under_test.write_doc("lib/api/run.md", "Runs {{crate.authors}}\n");
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(
+        !success,
+        "Expected an unknown placeholder to fail the build"
+    );
+    assert!(
+        stderr.contains("unknown placeholder `{{crate.authors}}`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("lib/api/run.md"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_include_directives_pull_in_fragments() {
+    let crate_under_test = TestCrate::new("test_includes");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn run() {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Crate docs\n");
+    crate_under_test.write_doc(
+        "lib/api/run.md",
+        "Runs it.\n\n<!-- syncdoc:include shared/example.md -->\n",
+    );
+    crate_under_test.write_doc(
+        "shared/example.md",
+        "```\nassert_eq!(\"{{item.path}}\", \"api::run\");\n```\n",
+    );
+
+    // The fragment's doctest runs as part of the item's docs, with its placeholders filled in
+    let output = std::process::Command::new("cargo")
+        .args(["test", "--doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "STDOUT:\n{}\nSTDERR:\n{}",
+        stdout,
+        stderr
+    );
+    assert!(stdout.contains("1 passed"), "STDOUT:\n{}", stdout);
+
+    // A missing fragment is an error on the item that includes it
+    fs::remove_file(crate_under_test.root().join("docs/shared/example.md")).unwrap();
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing fragment to fail the build");
+    assert!(
+        stderr.contains("missing include `shared/example.md` in `../docs/lib/api/run.md`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:6:12"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_snippets_quote_example_code() {
+    let crate_under_test = TestCrate::new("test_snippets");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn add(a: u8, b: u8) -> u8 {\n        a + b\n    }\n}\n",
+    )
+    .unwrap();
+    fs::create_dir(crate_under_test.root().join("examples")).unwrap();
+    fs::write(
+        crate_under_test.root().join("examples/add.rs"),
+        "fn main() {\n    // ANCHOR: add\n    let sum = test_snippets::api::add(1, 2);\n    \
+        assert_eq!(sum, 3);\n    // ANCHOR_END: add\n    println!(\"{}\", sum);\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Crate docs\n");
+    crate_under_test.write_doc(
+        "lib/api/add.md",
+        "Adds them up.\n\n<!-- syncdoc:snippet examples/add.rs#add -->\n",
+    );
+
+    // The quoted region runs as the item's doctest
+    let output = std::process::Command::new("cargo")
+        .args(["test", "--doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "STDOUT:\n{}\nSTDERR:\n{}",
+        stdout,
+        stderr
+    );
+    assert!(stdout.contains("1 passed"), "STDOUT:\n{}", stdout);
+
+    // The example is tracked, so losing the anchor is picked up without touching the docs
+    fs::write(
+        crate_under_test.root().join("examples/add.rs"),
+        "fn main() {}\n",
+    )
+    .unwrap();
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing anchor to fail the build");
+    assert!(
+        stderr.contains("no `// ANCHOR: add` in `examples/add.rs`"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_links_between_doc_files_resolve_in_rustdoc() {
+    let crate_under_test = TestCrate::new("test_links");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n#![deny(rustdoc::broken_intra_doc_links)]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub struct Config;\n    pub fn run(_config: Config) {}\n\n    \
+        impl std::fmt::Display for Config {\n        \
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n            \
+        f.write_str(\"config\")\n        }\n    }\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "Start with [`run`](lib/api/run.md).\n");
+    crate_under_test.write_doc("lib/api/Config.md", "Settings\n");
+    crate_under_test.write_doc("lib/api/Config/Display/fmt.md", "Shows it.\n");
+    crate_under_test.write_doc(
+        "lib/api/run.md",
+        "Runs with a [config](Config.md), as in [the crate docs](../../lib.md#usage).\n\n\
+        It [prints](Config/Display/fmt.md) as the [guide](../../guide.md) says.\n",
+    );
+    let cargo_doc = || {
+        let output = std::process::Command::new("cargo")
+            .args(["doc", "--quiet"])
+            .current_dir(crate_under_test.root())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "STDERR:\n{}", stderr);
+        let read_html = |page: &str| {
+            fs::read_to_string(
+                crate_under_test
+                    .root()
+                    .join("target/doc/test_links")
+                    .join(page),
+            )
+            .unwrap()
+        };
+        (read_html("api/fn.run.html"), read_html("index.html"))
+    };
+
+    // Links are left as they are unless rewriting is turned on
+    let (run_html, _) = cargo_doc();
+    assert!(run_html.contains("href=\"Config.md\""), "{}", run_html);
+
+    add_syncdoc_config(&crate_under_test, "rewrite-links = true");
+    let (run_html, index_html) = cargo_doc();
+    assert!(
+        run_html.contains("href=\"struct.Config.html\""),
+        "{}",
+        run_html
+    );
+    assert!(
+        index_html.contains("href=\"api/fn.run.html\""),
+        "{}",
+        index_html
+    );
+    // A trait impl's method and a guide aren't items to link to
+    assert!(
+        run_html.contains("href=\"Config/Display/fmt.md\""),
+        "{}",
+        run_html
+    );
+    assert!(run_html.contains("href=\"../../guide.md\""), "{}", run_html);
+}
+
+#[test]
+fn test_titles_naming_the_item_are_stripped() {
+    let crate_under_test = TestCrate::new("test_titles");
+    add_syncdoc_config(&crate_under_test, "strip-title = true");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub struct Config;\n    pub fn run(_config: Config) {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "# test_titles\n\nThe crate.\n");
+    crate_under_test.write_doc("lib/api/Config.md", "# `Config`\n\nSettings for a run.\n");
+    crate_under_test.write_doc("lib/api/run.md", "# Overview\n\nRuns it.\n");
+
+    let output = std::process::Command::new("cargo")
+        .args(["doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+
+    let read_html = |page: &str| {
+        fs::read_to_string(
+            crate_under_test
+                .root()
+                .join("target/doc/test_titles")
+                .join(page),
+        )
+        .unwrap()
+    };
+    let config_html = read_html("api/struct.Config.html");
+    assert!(
+        config_html.contains("Settings for a run."),
+        "{}",
+        config_html
+    );
+    assert!(!config_html.contains("id=\"config\""), "{}", config_html);
+    let index_html = read_html("index.html");
+    assert!(index_html.contains("The crate."), "{}", index_html);
+    assert!(!index_html.contains("id=\"test_titles\""), "{}", index_html);
+
+    // Only a title naming the item is stripped
+    let run_html = read_html("api/fn.run.html");
+    assert!(run_html.contains("id=\"overview\""), "{}", run_html);
+}
+
+#[test]
+fn test_alerts_become_rustdoc_warning_blocks() {
+    let crate_under_test = TestCrate::new("test_alerts");
+    add_syncdoc_config(&crate_under_test, "admonitions = \"rustdoc\"");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn run() {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "> [!NOTE]\n> Needs a runtime.\n");
+    crate_under_test.write_doc(
+        "lib/api/run.md",
+        "Runs it.\n\n> [!WARNING]\n> Blocks until done.\n",
+    );
+
+    let output = std::process::Command::new("cargo")
+        .args(["doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+
+    let read_html = |page: &str| {
+        fs::read_to_string(
+            crate_under_test
+                .root()
+                .join("target/doc/test_alerts")
+                .join(page),
+        )
+        .unwrap()
+    };
+    let run_html = read_html("api/fn.run.html");
+    assert!(run_html.contains("<div class=\"warning\">"), "{}", run_html);
+    assert!(!run_html.contains("[!WARNING]"), "{}", run_html);
+    let index_html = read_html("index.html");
+    assert!(
+        index_html.contains("<strong>Note</strong>"),
+        "{}",
+        index_html
+    );
+    assert!(!index_html.contains("[!NOTE]"), "{}", index_html);
+}
+
+#[test]
+fn test_local_images_are_embedded() {
+    let crate_under_test = TestCrate::new("test_images");
+    add_syncdoc_config(&crate_under_test, "embed-images = true");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn run() {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib.md", "The crate\n");
+    crate_under_test.write_doc("lib/api/run.md", "Runs it.\n\n![A dot](img/dot.svg)\n");
+    crate_under_test.write_doc(
+        "lib/api/img/dot.svg",
+        "<svg xmlns=\"http://www.w3.org/2000/svg\"><circle r=\"1\"/></svg>",
+    );
+
+    let output = std::process::Command::new("cargo")
+        .args(["doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+
+    let run_html = fs::read_to_string(
+        crate_under_test
+            .root()
+            .join("target/doc/test_images/api/fn.run.html"),
+    )
+    .unwrap();
+    assert!(
+        run_html.contains("src=\"data:image/svg+xml;base64,"),
+        "{}",
+        run_html
+    );
+
+    // A missing image is an error on the item, naming the file
+    crate_under_test.write_doc("lib/api/run.md", "Runs it.\n\n![Gone](img/gone.png)\n");
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a missing image to fail the build");
+    assert!(
+        stderr.contains("missing image `img/gone.png`"),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("lib/api/run.md"), "STDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_locales_fall_back_to_the_default() {
+    let crate_under_test = TestCrate::new("test_locales");
+    let manifest = crate_under_test.root().join("Cargo.toml");
+    let content = fs::read_to_string(&manifest).unwrap();
+    fs::write(
+        &manifest,
+        content.replace("docs-path = \"docs\"", "docs-path = \"docs/{locale}\""),
+    )
+    .unwrap();
+    use_build_helper(&crate_under_test);
+    add_syncdoc_config(&crate_under_test, "locales = [\"en\", \"ja\"]");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#![doc = syncdoc::module_doc!()]\n\n\
+        /// The API\n\
+        #[syncdoc::omnidoc]\n\
+        pub mod api {\n    pub fn run() {}\n    pub fn stop() {}\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("en/lib.md", "The crate\n");
+    crate_under_test.write_doc("en/lib/api/run.md", "Runs it.\n");
+    crate_under_test.write_doc("en/lib/api/stop.md", "Stops it.\n");
+    crate_under_test.write_doc("ja/lib.md", "クレート\n");
+    crate_under_test.write_doc("ja/lib/api/run.md", "実行する。\n");
+
+    let cargo_doc = |locale: &str| {
+        std::process::Command::new("cargo")
+            .args(["doc", "--quiet", "--no-deps"])
+            .env("SYNCDOC_LOCALE", locale)
+            .current_dir(crate_under_test.root())
+            .output()
+            .unwrap()
+    };
+    let read_html = |page: &str| {
+        fs::read_to_string(
+            crate_under_test
+                .root()
+                .join("target/doc/test_locales")
+                .join(page),
+        )
+        .unwrap()
+    };
+
+    // The default locale is built unless the variable picks another
+    let output = cargo_doc("");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+    assert!(read_html("index.html").contains("The crate"));
+    assert!(read_html("api/fn.run.html").contains("Runs it."));
+
+    let output = cargo_doc("ja");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+    // The build helper declares the cfg it sets
+    assert!(!stderr.contains("unexpected"), "STDERR:\n{}", stderr);
+    let index_html = read_html("index.html");
+    assert!(index_html.contains("クレート"), "{}", index_html);
+    let run_html = read_html("api/fn.run.html");
+    assert!(run_html.contains("実行する。"), "{}", run_html);
+    assert!(!run_html.contains("Runs it."), "{}", run_html);
+    // The untranslated item gets the default locale's docs
+    let stop_html = read_html("api/fn.stop.html");
+    assert!(stop_html.contains("Stops it."), "{}", stop_html);
+
+    // A translation added later is picked up
+    crate_under_test.write_doc("ja/lib/api/stop.md", "停止する。\n");
+    let output = cargo_doc("ja");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+    let stop_html = read_html("api/fn.stop.html");
+    assert!(stop_html.contains("停止する。"), "{}", stop_html);
+
+    // A locale that isn't configured is an error
+    let output = cargo_doc("fr");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !output.status.success(),
+        "Expected an unknown locale to fail"
+    );
+    assert!(
+        stderr.contains("unknown locale `fr` in SYNCDOC_LOCALE, expected one of en, ja"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_feature_docs_follow_with_the_feature_on() {
+    let crate_under_test = TestCrate::new("test_feature_docs");
+    let manifest = crate_under_test.root().join("Cargo.toml");
+    let content = fs::read_to_string(&manifest).unwrap();
+    fs::write(&manifest, content + "\n[features]\nserde = []\n").unwrap();
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc]\npub struct Config {\n    pub port: u16,\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib/Config.md", "A config.\n");
+    crate_under_test.write_doc("lib/Config.feature-serde.md", "Serialises with serde.\n");
+    crate_under_test.write_doc("lib/Config/port.md", "The port.\n");
+
+    let config_html = |features: &[&str]| {
+        let output = std::process::Command::new("cargo")
+            .args(["doc", "--quiet"])
+            .args(features)
+            .current_dir(crate_under_test.root())
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "STDERR:\n{}", stderr);
+        fs::read_to_string(
+            crate_under_test
+                .root()
+                .join("target/doc/test_feature_docs/struct.Config.html"),
+        )
+        .unwrap()
+    };
+
+    let without = config_html(&[]);
+    assert!(without.contains("A config."), "{}", without);
+    assert!(!without.contains("Serialises with serde."), "{}", without);
+    let with = config_html(&["--features", "serde"]);
+    assert!(with.contains("A config."), "{}", with);
+    assert!(with.contains("Serialises with serde."), "{}", with);
+}
+
+#[test]
+fn test_cfg_attr_takes_any_cfg_predicate() {
+    let crate_under_test = TestCrate::new("test_cfg_predicates");
+    add_syncdoc_config(&crate_under_test, "cfg-attr = \"all(doc, not(test))\"");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc]\npub fn run() {}\n\n\
+        #[syncdoc::omnidoc(cfg_attr = \"any(doc, docsrs)\")]\npub fn stop() {}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib/run.md", "Runs it.\n");
+    crate_under_test.write_doc("lib/stop.md", "Stops it.\n");
+
+    let output = std::process::Command::new("cargo")
+        .args(["doc", "--quiet"])
+        .current_dir(crate_under_test.root())
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "STDERR:\n{}", stderr);
+    let read_html = |page: &str| {
+        fs::read_to_string(
+            crate_under_test
+                .root()
+                .join("target/doc/test_cfg_predicates")
+                .join(page),
+        )
+        .unwrap()
+    };
+    let run_html = read_html("fn.run.html");
+    assert!(run_html.contains("Runs it."), "{}", run_html);
+    let stop_html = read_html("fn.stop.html");
+    assert!(stop_html.contains("Stops it."), "{}", stop_html);
+}
+
+#[test]
+fn test_methods_of_cfg_alternate_impls_share_a_doc_file() {
+    let crate_under_test = TestCrate::new("test_cfg_alternate_impls");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc]\npub mod m {\n    pub struct T;\n\n    \
+        #[cfg(unix)]\n    impl T {\n        pub fn open() {}\n    }\n\n    \
+        #[cfg(windows)]\n    impl T {\n        pub fn open() {}\n    }\n}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib/m.md", "A module.\n");
+    crate_under_test.write_doc("lib/m/T.md", "A type.\n");
+    crate_under_test.write_doc("lib/m/T/open.md", "Opens it.\n");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(success, "Compilation failed!\nSTDERR:\n{}", stderr);
+}
+
+#[test]
+fn test_malformed_cfg_attr_is_an_error() {
+    let crate_under_test = TestCrate::new("test_cfg_malformed");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc(cfg_attr = \"all(doc,, test)\")]\npub fn run() {}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib/run.md", "Runs it.\n");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a malformed cfg_attr to fail the build");
+    // Reported at the argument itself
+    assert!(
+        stderr.contains(
+            "Invalid cfg_attr: `all(doc,, test)` isn't a cfg predicate: \
+            expected a cfg predicate between commas"
+        ),
+        "STDERR:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("src/lib.rs:1:31"), "STDERR:\n{}", stderr);
+
+    add_syncdoc_config(&crate_under_test, "cfg-attr = \"doc test\"");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc]\npub fn run() {}\n",
+    )
+    .unwrap();
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected a malformed cfg-attr to fail the build");
+    assert!(
+        stderr.contains("Cargo.toml:11:12: invalid cfg-attr, `doc test` isn't a cfg predicate"),
+        "STDERR:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_config_mistake_is_an_error_pointing_into_the_manifest() {
+    let crate_under_test = TestCrate::new("test_config_mistake");
+    add_syncdoc_config(&crate_under_test, "layuot = \"sections\"");
+    fs::write(
+        crate_under_test.root().join("src/lib.rs"),
+        "#[syncdoc::omnidoc]\npub fn run() {}\n",
+    )
+    .unwrap();
+    crate_under_test.write_doc("lib/run.md", "Runs it.\n");
+
+    let (success, stderr) = crate_under_test.cargo_check();
+    assert!(!success, "Expected an unknown config key to fail the build");
+    assert!(
+        stderr.contains(
+            "Cargo.toml:11:1: unknown key `layuot` in [package.metadata.syncdoc], \
+            expected one of docs-path, cfg-attr,"
+        ),
+        "STDERR:\n{}",
+        stderr
+    );
+}
@@ -0,0 +1,53 @@
+//! Which files in the docs directory the docs show as images, to find missing and unused ones
+//!
+//! Docs reference images by paths relative to the doc file (see [`syncdoc_core::images`]), which
+//! are resolved here to paths relative to the docs root. Every file there that isn't markdown is
+//! an asset, and images outside the docs root aren't covered.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use syncdoc_core::images::local_images;
+use syncdoc_core::links::resolve;
+use syncdoc_core::path_utils::doc_files;
+
+/// The assets in a docs directory, by whether the docs reference them
+#[derive(Debug, Default, PartialEq)]
+pub struct AssetReport {
+    /// Each asset the docs reference, with the doc files that reference it
+    pub referenced: BTreeMap<String, BTreeSet<String>>,
+    /// Assets no doc file references
+    pub unreferenced: BTreeSet<String>,
+    /// Referenced images that don't exist, with the doc files that reference them
+    pub missing: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Sorts the files under `docs_root` into referenced and unreferenced assets, and finds missing ones
+///
+/// All paths are relative to the docs root.
+pub fn asset_report(docs_root: &Path) -> std::io::Result<AssetReport> {
+    let (docs, assets): (Vec<String>, Vec<String>) = doc_files(docs_root)?
+        .into_iter()
+        .partition(|file| file.ends_with(".md"));
+
+    let mut report = AssetReport {
+        unreferenced: assets.iter().cloned().collect(),
+        ..AssetReport::default()
+    };
+    for doc in docs {
+        let content = fs::read_to_string(docs_root.join(&doc))?;
+        for image in local_images(&content) {
+            let Some(asset) = resolve(&doc, image) else {
+                continue;
+            };
+            let found =
+                if report.unreferenced.remove(&asset) || report.referenced.contains_key(&asset) {
+                    &mut report.referenced
+                } else {
+                    &mut report.missing
+                };
+            found.entry(asset).or_default().insert(doc.clone());
+        }
+    }
+    Ok(report)
+}
@@ -9,3 +9,11 @@ pub enum DocsPathMode {
     /// Macros will include path: `#[omnidoc(path = "docs")]`, `module_doc!(path = "docs")`
     InlinePaths,
 }
+
+/// Gets whether doc files open with a title naming their item, for the crate a source file is in
+///
+/// The title is left out when docs are injected, and off when the crate has no config for it.
+pub fn get_strip_title(source_file: &std::path::Path) -> bool {
+    syncdoc_core::config::SyncdocConfig::load(&source_file.to_string_lossy())
+        .is_ok_and(|config| config.strip_title)
+}
@@ -1,10 +1,12 @@
 // syncdoc-migrate/src/discover.rs
 
 use crate::config::DocsPathMode;
+use crate::sections::DocLayout;
 use proc_macro2::TokenStream;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use syncdoc_core::config::SyncdocConfig;
 use syncdoc_core::parse::ModuleContent;
 pub(crate) use unsynn::*;
 
@@ -44,6 +46,8 @@ impl std::error::Error for ParseError {}
 #[derive(Debug)]
 pub enum ConfigError {
     IoError(std::io::Error),
+    /// A mistake in the `[package.metadata.syncdoc]` config that's there
+    Invalid(syncdoc_core::config::ConfigError),
     Other(String),
 }
 
@@ -57,6 +61,7 @@ impl std::fmt::Display for ConfigError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ConfigError::IoError(e) => write!(f, "IO error: {}", e),
+            ConfigError::Invalid(e) => write!(f, "{}", e),
             ConfigError::Other(msg) => write!(f, "{}", msg),
         }
     }
@@ -113,6 +118,21 @@ pub fn parse_file(path: &Path) -> std::result::Result<ParsedFile, ParseError> {
     })
 }
 
+/// Reads the syncdoc config of the crate a source file or directory belongs to
+///
+/// `None` when it isn't in a crate, or the crate has no `[package.metadata.syncdoc]` config.
+pub fn read_config(source_file: &Path) -> std::result::Result<Option<SyncdocConfig>, ConfigError> {
+    let Some(manifest_dir) = source_file
+        .parent()
+        .and_then(syncdoc_core::path_utils::find_manifest_dir)
+    else {
+        return Ok(None);
+    };
+    let cargo_toml_path = manifest_dir.join("Cargo.toml");
+    let content = fs::read_to_string(&cargo_toml_path)?;
+    SyncdocConfig::parse(&content, &cargo_toml_path).map_err(ConfigError::Invalid)
+}
+
 /// Gets or creates the docs-path configuration
 ///
 /// Returns a tuple of (path, mode) where mode indicates whether the path
@@ -124,12 +144,15 @@ pub fn get_or_create_docs_path(
     source_file: &Path,
     dry_run: bool,
 ) -> std::result::Result<(String, DocsPathMode), ConfigError> {
+    // A config with a mistake in it isn't added to
+    let config = read_config(source_file)?;
+
     // Try to get existing docs-path
     match syncdoc_core::config::get_docs_path(source_file.to_str().unwrap()) {
         Ok(path) => Ok((path, DocsPathMode::TomlConfig)),
         Err(_) => {
-            // Need to add default docs-path to Cargo.toml
-            if !dry_run {
+            // Need to add default docs-path to Cargo.toml, unless there's a config without one
+            if !dry_run && config.is_none() {
                 let source_dir = source_file.parent().ok_or_else(|| {
                     ConfigError::Other("Source file has no parent directory".to_string())
                 })?;
@@ -139,18 +162,11 @@ pub fn get_or_create_docs_path(
 
                 let cargo_toml_path = manifest_dir.join("Cargo.toml");
 
-                // Read existing content
+                // Append the section
                 let mut content = fs::read_to_string(&cargo_toml_path)?;
-
-                // Check if syncdoc section exists
-                if !content.contains("[package.metadata.syncdoc]") {
-                    // Append the section
-                    content.push_str("\n[package.metadata.syncdoc]\n");
-                    content.push_str("docs-path = \"docs\"\n");
-
-                    // Write back
-                    fs::write(&cargo_toml_path, content)?;
-                }
+                content.push_str("\n[package.metadata.syncdoc]\n");
+                content.push_str("docs-path = \"docs\"\n");
+                fs::write(&cargo_toml_path, content)?;
             }
 
             // We just created/will create TOML config
@@ -158,3 +174,65 @@ pub fn get_or_create_docs_path(
         }
     }
 }
+
+/// Sets the doc layout in the crate's `[package.metadata.syncdoc]` config
+///
+/// Replaces an existing `layout` key, or adds one (and the section, if need be).
+pub fn set_layout(source_file: &Path, layout: DocLayout) -> std::result::Result<(), ConfigError> {
+    let source_dir = source_file
+        .parent()
+        .ok_or_else(|| ConfigError::Other("Source file has no parent directory".to_string()))?;
+
+    let manifest_dir = syncdoc_core::path_utils::find_manifest_dir(source_dir)
+        .ok_or_else(|| ConfigError::Other("Could not find Cargo.toml".to_string()))?;
+
+    let cargo_toml_path = manifest_dir.join("Cargo.toml");
+    let content = fs::read_to_string(&cargo_toml_path)?;
+    let setting = format!("layout = \"{}\"", layout);
+    let has_config = read_config(source_file)?.is_some();
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    match lines
+        .iter()
+        .position(|line| line.trim() == "[package.metadata.syncdoc]")
+    {
+        Some(header) => {
+            let end = lines[header + 1..]
+                .iter()
+                .position(|line| line.trim_start().starts_with('['))
+                .map_or(lines.len(), |offset| header + 1 + offset);
+            let existing = lines[header + 1..end].iter().position(|line| {
+                line.split_once('=')
+                    .is_some_and(|(key, _)| key.trim() == "layout")
+            });
+            match existing {
+                Some(offset) => lines[header + 1 + offset] = setting,
+                None => {
+                    // After the section's last setting, not the blank lines before the next one
+                    let last = lines[header..end]
+                        .iter()
+                        .rposition(|line| !line.trim().is_empty())
+                        .map_or(header, |offset| header + offset);
+                    lines.insert(last + 1, setting);
+                }
+            }
+        }
+        // Only written as an inline table or dotted keys, which are left to be edited by hand
+        None if has_config => {
+            return Err(ConfigError::Other(format!(
+                "[package.metadata.syncdoc] isn't a table of its own, set {} in it by hand",
+                setting
+            )))
+        }
+        None => {
+            lines.push(String::new());
+            lines.push("[package.metadata.syncdoc]".to_string());
+            lines.push(setting);
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    fs::write(&cargo_toml_path, updated)?;
+    Ok(())
+}
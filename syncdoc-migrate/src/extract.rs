@@ -1,29 +1,234 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use syncdoc_core::cfg::CfgPredicate;
+use syncdoc_core::front_matter::FrontMatter;
+use syncdoc_core::helper_attr::{find_item_directive, ItemDirective};
 pub(crate) use syncdoc_core::parse::{Attribute, InnerAttribute};
+use syncdoc_core::parse::{SyncDocArg, SyncDocInner};
 pub(crate) use unsynn::*;
 
 /// Extracts documentation content from a list of attributes
 ///
 /// Returns the concatenated documentation strings if any doc attributes are found,
-/// otherwise returns None.
+/// otherwise returns None. Attributes that front matter can express (`#[deprecated]`,
+/// `#[doc(alias = ...)]` and `#[doc(hidden)]`) are lifted into a front matter block before them.
+/// Docs for a Cargo feature are left to [`extract_feature_docs`], though an item with only
+/// those still has (empty) docs of its own for them to go with.
 pub fn extract_doc_content(attrs: &Option<Many<Attribute>>) -> Option<String> {
     let attrs = attrs.as_ref()?;
 
     let mut doc_strings = Vec::new();
+    let mut front_matter = FrontMatter::default();
+    let mut has_feature_docs = false;
 
     for attr_delimited in &attrs.0 {
         // Extract the actual Attribute from the Delimited wrapper
-        if let Some(doc_content) = extract_from_single_attr(&attr_delimited.value) {
+        if lift_into_front_matter(&attr_delimited.value, &mut front_matter) {
+            continue;
+        }
+        if feature_doc(&attr_delimited.value).is_some() {
+            has_feature_docs = true;
+            continue;
+        }
+        // Whatever a `cfg_attr`'s docs are gated behind, they are read as the item's
+        let doc_content = match cfg_doc(&attr_delimited.value) {
+            Some((_, doc)) => Some(doc),
+            None => extract_from_single_attr(&attr_delimited.value),
+        };
+        if let Some(doc_content) = doc_content {
             // Strip leading space that Rust adds to doc comments
             let trimmed = doc_content.strip_prefix(' ').unwrap_or(&doc_content);
             doc_strings.push(trimmed.to_string());
         }
     }
 
-    if doc_strings.is_empty() {
+    if doc_strings.is_empty() && front_matter.is_empty() && !has_feature_docs {
         None
-    } else {
+    } else if front_matter.is_empty() {
         Some(doc_strings.join("\n").trim().to_string())
+    } else {
+        Some(format!(
+            "{}\n{}",
+            front_matter.render(),
+            doc_strings.join("\n").trim()
+        ))
+    }
+}
+
+/// Extracts the docs an item only has with a Cargo feature on, by feature
+///
+/// These are `#[cfg_attr(feature = "serde", doc = "...")]` attributes, and each feature's lines
+/// are joined as doc comments' are. Features are in the order their docs first appear.
+pub fn extract_feature_docs(attrs: &Option<Many<Attribute>>) -> Vec<(String, String)> {
+    let mut features: Vec<(String, Vec<String>)> = Vec::new();
+    for attr_delimited in attrs.iter().flat_map(|attrs| &attrs.0) {
+        let Some((feature, line)) = feature_doc(&attr_delimited.value) else {
+            continue;
+        };
+        let line = line.strip_prefix(' ').unwrap_or(&line).to_string();
+        match features.iter_mut().find(|(name, _)| *name == feature) {
+            Some((_, lines)) => lines.push(line),
+            None => features.push((feature, vec![line])),
+        }
+    }
+    features
+        .into_iter()
+        .map(|(feature, lines)| (feature, lines.join("\n").trim().to_string()))
+        .collect()
+}
+
+/// The feature and docs of a `#[cfg_attr(feature = "...", doc = "...")]` attribute
+fn feature_doc(attr: &Attribute) -> Option<(String, String)> {
+    let (predicate, doc) = cfg_doc(attr)?;
+    match predicate.as_slice() {
+        [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(feature)]
+            if key == "feature" && eq.as_char() == '=' =>
+        {
+            Some((extract_string_literal(&feature.to_string())?, doc))
+        }
+        _ => None,
+    }
+}
+
+/// The predicate and docs of a `#[cfg_attr(..., doc = "...")]` attribute
+fn cfg_doc(attr: &Attribute) -> Option<(Vec<TokenTree>, String)> {
+    let mut ts = TokenStream::new();
+    unsynn::ToTokens::to_tokens(&attr.content, &mut ts);
+    let content: Vec<TokenTree> = match ts.into_iter().next() {
+        Some(TokenTree::Group(g)) => g.stream().into_iter().collect(),
+        _ => return None,
+    };
+    let [TokenTree::Ident(cfg_attr), TokenTree::Group(args)] = content.as_slice() else {
+        return None;
+    };
+    if cfg_attr != "cfg_attr" || args.delimiter() != Delimiter::Parenthesis {
+        return None;
+    }
+    match split_settings(args.stream()).as_slice() {
+        [predicate, doc] => match doc.as_slice() {
+            [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(doc)]
+                if key == "doc" && eq.as_char() == '=' =>
+            {
+                Some((predicate.clone(), extract_string_literal(&doc.to_string())?))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether an attribute is one that goes into front matter when docs are extracted
+pub(crate) fn is_front_matter_attr(attr: &Attribute) -> bool {
+    lift_into_front_matter(attr, &mut FrontMatter::default())
+}
+
+/// Adds an attribute to the front matter if it is one front matter can express
+///
+/// Returns false (leaving the front matter alone) for any other attribute, including a
+/// `#[doc(...)]` with settings besides `alias` and `hidden`.
+fn lift_into_front_matter(attr: &Attribute, front_matter: &mut FrontMatter) -> bool {
+    let mut ts = TokenStream::new();
+    unsynn::ToTokens::to_tokens(&attr.content, &mut ts);
+    let content: Vec<TokenTree> = match ts.into_iter().next() {
+        Some(TokenTree::Group(g)) => g.stream().into_iter().collect(),
+        _ => return false,
+    };
+
+    match content.as_slice() {
+        [TokenTree::Ident(ident)] if ident == "deprecated" => {
+            front_matter.deprecated = true;
+            true
+        }
+        [TokenTree::Ident(ident), TokenTree::Punct(eq), TokenTree::Literal(note)]
+            if ident == "deprecated" && eq.as_char() == '=' =>
+        {
+            front_matter.deprecated = true;
+            front_matter.note = extract_string_literal(&note.to_string());
+            true
+        }
+        [TokenTree::Ident(ident), TokenTree::Group(args)]
+            if args.delimiter() == Delimiter::Parenthesis
+                && (ident == "deprecated" || ident == "doc") =>
+        {
+            let mut lifted = front_matter.clone();
+            let settings = split_settings(args.stream());
+            let all_lifted = !settings.is_empty()
+                && settings.iter().all(|setting| {
+                    if ident == "deprecated" {
+                        lift_deprecated_setting(setting, &mut lifted)
+                    } else {
+                        lift_doc_setting(setting, &mut lifted)
+                    }
+                });
+            if all_lifted {
+                *front_matter = lifted;
+            }
+            all_lifted
+        }
+        _ => false,
+    }
+}
+
+/// Splits the contents of an attribute's parentheses at its top-level commas
+fn split_settings(args: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut settings = vec![Vec::new()];
+    for token in args {
+        match &token {
+            TokenTree::Punct(comma) if comma.as_char() == ',' => settings.push(Vec::new()),
+            _ => settings.last_mut().unwrap().push(token),
+        }
+    }
+    settings.retain(|setting| !setting.is_empty());
+    settings
+}
+
+/// Lifts `since = "..."` or `note = "..."` from inside `#[deprecated(...)]`
+fn lift_deprecated_setting(setting: &[TokenTree], front_matter: &mut FrontMatter) -> bool {
+    front_matter.deprecated = true;
+    match setting {
+        [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value)]
+            if eq.as_char() == '=' =>
+        {
+            let value = extract_string_literal(&value.to_string());
+            match key.to_string().as_str() {
+                "since" => front_matter.since = value,
+                "note" => front_matter.note = value,
+                _ => return false,
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Lifts `alias = "..."`, `alias("...", ...)` or `hidden` from inside `#[doc(...)]`
+fn lift_doc_setting(setting: &[TokenTree], front_matter: &mut FrontMatter) -> bool {
+    match setting {
+        [TokenTree::Ident(key)] if key == "hidden" => {
+            front_matter.hidden = true;
+            true
+        }
+        [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(alias)]
+            if key == "alias" && eq.as_char() == '=' =>
+        {
+            front_matter
+                .aliases
+                .extend(extract_string_literal(&alias.to_string()));
+            true
+        }
+        [TokenTree::Ident(key), TokenTree::Group(aliases)]
+            if key == "alias" && aliases.delimiter() == Delimiter::Parenthesis =>
+        {
+            for alias in split_settings(aliases.stream()) {
+                match alias.as_slice() {
+                    [TokenTree::Literal(alias)] => front_matter
+                        .aliases
+                        .extend(extract_string_literal(&alias.to_string())),
+                    _ => return false,
+                }
+            }
+            true
+        }
+        _ => false,
     }
 }
 
@@ -70,12 +275,132 @@ pub fn is_doc_attribute_bracket(bracket: &BracketGroup) -> bool {
     // Try to parse as tokens and check first ident
     let tokens: Vec<proc_macro2::TokenTree> = content.into_iter().collect();
 
-    if let Some(proc_macro2::TokenTree::Ident(ident)) = tokens.first() {
-        let ident_str = ident.to_string();
-        // Only check the identifier itself
-        ident_str == "doc" || ident_str == "cfg_attr"
-    } else {
-        false
+    match tokens.as_slice() {
+        [proc_macro2::TokenTree::Ident(ident), ..] if ident == "doc" => true,
+        // A `cfg_attr` is only docs if what it applies is, and not, say, a derive
+        [proc_macro2::TokenTree::Ident(ident), proc_macro2::TokenTree::Group(args)]
+            if ident == "cfg_attr" =>
+        {
+            split_settings(args.stream()).iter().skip(1).any(|attr| {
+                matches!(
+                    attr.as_slice(),
+                    [proc_macro2::TokenTree::Ident(key), proc_macro2::TokenTree::Punct(eq), ..]
+                        if key == "doc" && eq.as_char() == '='
+                )
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Reads the `name = "..."` override from an item's `#[omnidoc(...)]` attribute
+///
+/// The override replaces the item's own name as the stem of its markdown file,
+/// mirroring what the macro does at expansion time.
+pub fn omnidoc_name_override(attrs: &Option<Many<Attribute>>) -> Option<String> {
+    omnidoc_arg(attrs, |arg| match arg {
+        SyncDocArg::Name(name_arg) => Some(name_arg.value.as_str().to_string()),
+        _ => None,
+    })
+}
+
+/// Reads the `cfg_attr = "..."` predicate from an item's `#[omnidoc(...)]` attribute
+///
+/// The item's docs, and those of anything in it, are only there when the predicate holds.
+pub fn omnidoc_cfg_attr(attrs: &Option<Many<Attribute>>) -> Option<CfgPredicate> {
+    omnidoc_arg(attrs, |arg| match arg {
+        SyncDocArg::CfgAttr(cfg_arg) => cfg_arg.value.as_str().parse().ok(),
+        _ => None,
+    })
+}
+
+/// The first argument of an item's `#[omnidoc(...)]` attribute that `pick` takes
+fn omnidoc_arg<T>(
+    attrs: &Option<Many<Attribute>>,
+    pick: impl Fn(SyncDocArg) -> Option<T>,
+) -> Option<T> {
+    for attr_delimited in &attrs.as_ref()?.0 {
+        let mut ts = TokenStream::new();
+        unsynn::ToTokens::to_tokens(&attr_delimited.value.content, &mut ts);
+        let content = match ts.into_iter().next() {
+            Some(proc_macro2::TokenTree::Group(g)) => g.stream(),
+            _ => continue,
+        };
+
+        // Matches both `omnidoc(...)` and `syncdoc::omnidoc(...)`
+        let tokens: Vec<proc_macro2::TokenTree> = content.into_iter().collect();
+        for pair in tokens.windows(2) {
+            if let (proc_macro2::TokenTree::Ident(ident), proc_macro2::TokenTree::Group(args)) =
+                (&pair[0], &pair[1])
+            {
+                if ident != "omnidoc" || args.delimiter() != proc_macro2::Delimiter::Parenthesis {
+                    continue;
+                }
+                if let Ok(parsed) = args.stream().into_token_iter().parse::<SyncDocInner>() {
+                    let found = parsed
+                        .args
+                        .into_iter()
+                        .flat_map(|list| list.0)
+                        .find_map(|arg| pick(arg.value));
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// The predicate an item's docs are all gated behind, as `#[cfg_attr(..., doc = "...")]` lines
+///
+/// None if any of its docs aren't, or they aren't all behind the same predicate. Docs for a
+/// Cargo feature are left out, as they go to the item's companion files.
+pub fn docs_cfg_attr(attrs: &Option<Many<Attribute>>) -> Option<CfgPredicate> {
+    let mut gated_by = None;
+    for attr_delimited in &attrs.as_ref()?.0 {
+        let attr = &attr_delimited.value;
+        if feature_doc(attr).is_some() {
+            continue;
+        }
+        match cfg_doc(attr) {
+            Some((predicate, _)) => {
+                let predicate = CfgPredicate::parse(predicate.into_iter().collect()).ok()?;
+                match &gated_by {
+                    Some(gate) if *gate != predicate => return None,
+                    Some(_) => {}
+                    None => gated_by = Some(predicate),
+                }
+            }
+            None if extract_from_single_attr(attr).is_some() => return None,
+            None => {}
+        }
+    }
+    gated_by
+}
+
+/// The stem an item's markdown file is stored under: its name unless overridden
+pub(crate) fn doc_stem(attrs: &Option<Many<Attribute>>, name: &Ident) -> String {
+    omnidoc_name_override(attrs).unwrap_or_else(|| name.to_string())
+}
+
+/// Checks for a `#[syncdoc(skip)]` helper, which keeps an item's docs out of markdown
+pub(crate) fn skips_docs(attrs: &Option<Many<Attribute>>) -> bool {
+    find_item_directive(attrs) == Some(ItemDirective::Skip)
+}
+
+/// The markdown path for an item, honouring a `#[syncdoc(path = "...")]` redirect
+///
+/// Redirects are relative to the docs root, so `default` is only used without one.
+pub(crate) fn redirected_path(
+    attrs: &Option<Many<Attribute>>,
+    docs_root: &str,
+    default: String,
+) -> String {
+    match find_item_directive(attrs) {
+        Some(ItemDirective::Path(path)) => format!("{}/{}", docs_root, path),
+        _ => default,
     }
 }
 
@@ -0,0 +1,59 @@
+//! Which docs use which shared fragments, to show what editing a fragment affects
+//!
+//! Docs pull fragments in with include directives (see [`syncdoc_core::include`]), and fragments
+//! can include other fragments, so a fragment's users are all the docs that reach it.
+
+use crate::sections::DocLayout;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use syncdoc_core::include::includes;
+use syncdoc_core::path_utils::doc_files;
+use syncdoc_core::sections::Sections;
+
+/// Each fragment the docs include, and the docs that use it, directly or through other fragments
+///
+/// Docs are named by their path relative to the docs root, and in the sections layout by their
+/// section as well, as in `lib.md#api::run`. A fragment that includes another is one of its users.
+pub fn fragment_users(
+    docs_root: &Path,
+    layout: DocLayout,
+) -> std::io::Result<BTreeMap<String, BTreeSet<String>>> {
+    let files = doc_files(docs_root)?
+        .into_iter()
+        .filter(|file| file.ends_with(".md"));
+
+    // The fragments each file includes, and each doc (a file, or a section of one)
+    let mut file_includes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut docs: Vec<(String, Vec<String>)> = Vec::new();
+    for relative in files {
+        let content = fs::read_to_string(docs_root.join(&relative))?;
+        let owned = |markdown: &str| includes(markdown).into_iter().map(str::to_string).collect();
+        file_includes.insert(relative.clone(), owned(&content));
+        match layout {
+            DocLayout::PerItem => docs.push((relative.clone(), owned(&content))),
+            DocLayout::Sections => {
+                let sections = Sections::parse(&content);
+                docs.push((relative.clone(), owned(&sections.preamble)));
+                for (key, section) in &sections.items {
+                    docs.push((format!("{}#{}", relative, key), owned(section)));
+                }
+            }
+        }
+    }
+
+    let mut users: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (doc, direct) in docs {
+        let mut pending = direct;
+        let mut reached = BTreeSet::new();
+        while let Some(fragment) = pending.pop() {
+            if reached.insert(fragment.clone()) {
+                pending.extend(file_includes.get(&fragment).into_iter().flatten().cloned());
+            }
+        }
+        for fragment in reached {
+            users.entry(fragment).or_default().insert(doc.clone());
+        }
+    }
+    Ok(users)
+}
@@ -1,23 +1,30 @@
 // syncdoc-migrate/src/lib.rs
 
+pub mod assets;
 pub mod config;
 pub mod discover;
 mod extract;
+pub mod fragments;
+pub mod locales;
 mod report;
 pub mod restore;
 pub mod rewrite;
+pub mod sections;
 pub mod write;
 
 // Re-export core's macro
 pub use syncdoc_core::syncdoc_debug;
 
-pub use config::DocsPathMode;
-pub use discover::{discover_rust_files, get_or_create_docs_path, parse_file, ParsedFile};
+pub use config::{get_strip_title, DocsPathMode};
+pub use discover::{
+    discover_rust_files, get_or_create_docs_path, parse_file, read_config, set_layout, ParsedFile,
+};
 pub use extract::{extract_doc_content, has_doc_attrs};
 pub use restore::restore_file;
 pub use rewrite::{inject_module_doc_attr, inject_omnidoc_attr, rewrite_file, strip_doc_attrs};
+pub use sections::{get_layout, module_title, DocLayout};
 pub use write::{
-    extract_all_docs, find_expected_doc_paths, write_extracts, DocExtract, WriteReport,
+    add_titles, extract_all_docs, find_expected_doc_paths, write_extracts, DocExtract, WriteReport,
 };
 
 #[cfg(test)]
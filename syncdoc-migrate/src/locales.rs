@@ -0,0 +1,68 @@
+//! How much of the docs each locale has, for crates whose `docs-path` has a `{locale}` in it
+//!
+//! Every locale's tree is expected to have the doc files the source calls for, as the default
+//! locale's does. Items missing from a translation fall back to the default locale's docs when
+//! they are built, so the coverage says what is still to translate rather than what will break.
+
+use crate::discover::ParsedFile;
+use crate::sections::{doc_exists, module_base};
+use crate::write::find_expected_doc_paths;
+use std::path::Path;
+use syncdoc_core::config::SyncdocConfig;
+
+/// How many of the doc files the source calls for a locale's docs directory has
+#[derive(Debug, Default, PartialEq)]
+pub struct Coverage {
+    /// The number of doc files (or sections) the source calls for
+    pub expected: usize,
+    /// The ones the docs directory doesn't have, relative to it
+    pub missing: Vec<String>,
+}
+
+impl Coverage {
+    /// The number of expected doc files that are there
+    pub fn present(&self) -> usize {
+        self.expected - self.missing.len()
+    }
+}
+
+/// Each configured locale with its docs directory, in the order the config lists them
+///
+/// Empty when the crate `source` is in has no locales.
+pub fn locale_docs_paths(source: &Path) -> Result<Vec<(String, String)>, String> {
+    let source = source.to_string_lossy();
+    let config = SyncdocConfig::load(&source).map_err(|e| e.to_string())?;
+    let Some(locales) = &config.locales else {
+        return Ok(Vec::new());
+    };
+    locales
+        .all
+        .iter()
+        .map(|locale| {
+            let docs_path = config
+                .docs_path_from(&source, Some(locale))
+                .map_err(|e| e.to_string())?;
+            Ok((locale.clone(), docs_path))
+        })
+        .collect()
+}
+
+/// Finds which of the doc files the parsed source calls for are missing under `docs_root`
+///
+/// In the sections layout a section in the module's file counts as the item's doc file.
+pub fn translation_coverage(parsed_files: &[ParsedFile], docs_root: &str) -> Coverage {
+    let mut coverage = Coverage::default();
+    let prefix = format!("{}/", docs_root);
+    for parsed in parsed_files {
+        let base = module_base(parsed, docs_root);
+        for extract in find_expected_doc_paths(parsed, docs_root) {
+            coverage.expected += 1;
+            if !doc_exists(&extract.markdown_path, &base) {
+                let path = extract.markdown_path.to_string_lossy();
+                let path = path.strip_prefix(&prefix).unwrap_or(&path);
+                coverage.missing.push(path.to_string());
+            }
+        }
+    }
+    coverage
+}
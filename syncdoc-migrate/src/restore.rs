@@ -19,39 +19,126 @@ pub fn restore_file(parsed: &ParsedFile, docs_root: &str) -> Option<String> {
     .ok()
 }
 
+/// An item's docs read back from its doc file, with the docs it has for Cargo features
+pub(crate) struct ItemDocs {
+    content: String,
+    /// Each feature's docs, from the doc file's companions
+    features: Vec<(String, String)>,
+}
+
+impl ItemDocs {
+    /// The docs from `content` and the companions of the doc file at `md_path`, if there are any
+    fn read(content: Option<String>, md_path: &str) -> Option<ItemDocs> {
+        use syncdoc_core::features::find_feature_docs;
+
+        let features: Vec<_> = find_feature_docs(std::path::Path::new(md_path))
+            .into_iter()
+            .filter_map(|(feature, path)| Some((feature, std::fs::read_to_string(path).ok()?)))
+            .collect();
+        if content.is_none() && features.is_empty() {
+            return None;
+        }
+        Some(ItemDocs {
+            content: content.unwrap_or_default(),
+            features,
+        })
+    }
+}
+
 pub(crate) fn read_item_markdown(
     context: &[String],
     item_name: &str,
     docs_root: &str,
-) -> Option<String> {
+) -> Option<ItemDocs> {
     let mut path_parts = vec![docs_root.to_string()];
     path_parts.extend(context.iter().cloned());
     path_parts.push(format!("{}.md", item_name));
 
     let md_path = path_parts.join("/");
-    std::fs::read_to_string(&md_path).ok()
+    let content = std::fs::read_to_string(&md_path).ok();
+    let content = content
+        .map(|content| untitled(content, &md_path, item_name))
+        .or_else(|| {
+            // In the sections layout the item is a heading in its module's file instead
+            let base = format!("{}/{}", docs_root, context.first()?);
+            crate::sections::read_section(&md_path, &base)
+        });
+    ItemDocs::read(content, &md_path)
+}
+
+/// Reads an item's markdown as directed by its `#[syncdoc(...)]` helper attribute, if any
+pub(crate) fn read_directed_markdown(
+    attrs: &Option<unsynn::Many<syncdoc_core::parse::Attribute>>,
+    context: &[String],
+    item_name: &str,
+    docs_root: &str,
+) -> Option<ItemDocs> {
+    use syncdoc_core::helper_attr::{find_item_directive, ItemDirective};
+
+    match find_item_directive(attrs) {
+        Some(ItemDirective::Skip) => None,
+        Some(ItemDirective::Path(_)) => read_redirected_markdown(attrs, docs_root),
+        None => read_item_markdown(context, item_name, docs_root),
+    }
+}
+
+/// Reads the markdown a `#[syncdoc(path = "...")]` helper attribute points to, if any
+pub(crate) fn read_redirected_markdown(
+    attrs: &Option<unsynn::Many<syncdoc_core::parse::Attribute>>,
+    docs_root: &str,
+) -> Option<ItemDocs> {
+    use syncdoc_core::helper_attr::{find_item_directive, ItemDirective};
+
+    match find_item_directive(attrs) {
+        Some(ItemDirective::Path(path)) => {
+            let md_path = format!("{}/{}", docs_root, path);
+            let name = std::path::Path::new(&path).file_stem()?.to_string_lossy();
+            let content = std::fs::read_to_string(&md_path)
+                .ok()
+                .map(|content| untitled(content, &md_path, &name));
+            ItemDocs::read(content, &md_path)
+        }
+        _ => None,
+    }
 }
 
 pub(crate) fn read_module_doc(parsed: &ParsedFile, docs_root: &str) -> Option<String> {
-    let module_path = syncdoc_core::path_utils::extract_module_path(&parsed.path.to_string_lossy());
-
-    let file_stem = parsed
-        .path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("module");
-
-    let md_path = if module_path.is_empty() {
-        format!("{}/{}.md", docs_root, file_stem)
-    } else {
-        format!("{}/{}.md", docs_root, module_path)
-    };
+    use crate::sections::{get_layout, module_base, module_title, DocLayout};
 
-    std::fs::read_to_string(&md_path).ok()
+    let md_path = format!("{}.md", module_base(parsed, docs_root));
+    let content = std::fs::read_to_string(&md_path).ok()?;
+
+    match get_layout(&parsed.path) {
+        DocLayout::PerItem => Some(untitled(content, &md_path, &module_title(parsed))),
+        DocLayout::Sections => {
+            let preamble = syncdoc_core::sections::Sections::parse(&content).preamble;
+            (!preamble.is_empty()).then_some(preamble)
+        }
+    }
 }
 
-pub(crate) fn generate_doc_comments(content: &str) -> TokenStream {
+/// A doc file's content without the title migrate adds, when its crate is set to `strip-title`
+fn untitled(content: String, md_path: &str, name: &str) -> String {
+    use syncdoc_core::title::strip_title;
+
+    if !crate::config::get_strip_title(std::path::Path::new(md_path)) {
+        return content;
+    }
+    strip_title(&content, name).unwrap_or(content)
+}
+
+/// Turns an item's markdown back into doc comments, and its front matter back into attributes
+///
+/// Each feature's docs become `#[cfg_attr(feature = "...", doc = "...")]` lines after them.
+pub(crate) fn generate_doc_comments(docs: &ItemDocs) -> TokenStream {
     use quote::quote;
+    use syncdoc_core::front_matter::FrontMatter;
+
+    // Malformed front matter is kept as docs, rather than losing it
+    let (attributes, content) = match FrontMatter::parse(&docs.content) {
+        Ok((front_matter, body)) => (front_matter.attributes(false), body),
+        Err(_) => (TokenStream::new(), docs.content.as_str()),
+    };
     let lines: Vec<_> = content.trim_end().lines().collect();
     let mut output = TokenStream::new();
 
@@ -59,7 +146,14 @@ pub(crate) fn generate_doc_comments(content: &str) -> TokenStream {
         let comment = format!("/// {}", line);
         output.extend(quote! { #[doc = #comment] });
     }
+    for (feature, content) in &docs.features {
+        for line in content.trim_end().lines() {
+            let doc = format!(" {}", line);
+            output.extend(quote! { #[cfg_attr(feature = #feature, doc = #doc)] });
+        }
+    }
 
+    output.extend(attributes);
     output
 }
 
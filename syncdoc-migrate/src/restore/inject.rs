@@ -3,10 +3,14 @@
 //! Injects inline doc comments by reading from markdown files, removing omnidoc attributes.
 
 use crate::discover::ParsedFile;
+use crate::extract::{doc_stem, extract_string_literal, omnidoc_cfg_attr, omnidoc_name_override};
 use crate::syncdoc_debug;
-use proc_macro2::TokenStream;
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
 use quote::quote;
+use syncdoc_core::cfg::CfgPredicate;
+use syncdoc_core::helper_attr::{is_helper_attr, item_attributes};
 use syncdoc_core::parse::*;
+use syncdoc_core::path_utils::named_impl_context;
 use unsynn::*;
 
 pub fn inject_all_doc_comments(
@@ -79,20 +83,25 @@ pub(crate) fn inject_item_docs(
     syncdoc_debug!("\n=== INJECT_ITEM_DOCS ===");
     syncdoc_debug!("Context: {:?}", context);
 
-    match item {
+    let output = match item {
         ModuleItem::TraitMethod(method) => inject_trait_method_docs(method, &context, docs_root),
         ModuleItem::Function(func) => inject_function_docs(func, &context, docs_root),
         ModuleItem::Struct(s) => inject_struct_docs(s, &context, docs_root),
+        ModuleItem::Union(u) => inject_union_docs(u, &context, docs_root),
         ModuleItem::Enum(e) => inject_enum_docs(e, &context, docs_root),
         ModuleItem::Module(m) => inject_module_docs(m, context, docs_root),
         ModuleItem::Trait(t) => inject_trait_docs(t, context, docs_root),
         ModuleItem::ImplBlock(i) => inject_impl_docs(i, context, docs_root),
+        ModuleItem::ExternBlock(e) => inject_extern_block_docs(e, context, docs_root),
         ModuleItem::TypeAlias(ta) => {
             let mut output = TokenStream::new();
 
-            if let Some(content) =
-                super::read_item_markdown(&context, &ta.name.to_string(), docs_root)
-            {
+            if let Some(content) = super::read_directed_markdown(
+                &ta.attributes,
+                &context,
+                &doc_stem(&ta.attributes, &ta.name),
+                docs_root,
+            ) {
                 output.extend(super::generate_doc_comments(&content));
             }
 
@@ -106,8 +115,15 @@ pub(crate) fn inject_item_docs(
             if let Some(generics) = &ta.generics {
                 unsynn::ToTokens::to_tokens(generics, &mut output);
             }
-            unsynn::ToTokens::to_tokens(&ta._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&ta.target, &mut output);
+            if let Some(bounds) = &ta.bounds {
+                unsynn::ToTokens::to_tokens(bounds, &mut output);
+            }
+            if let Some(where_clause) = &ta.where_clause {
+                unsynn::ToTokens::to_tokens(where_clause, &mut output);
+            }
+            if let Some(target) = &ta.target {
+                unsynn::ToTokens::to_tokens(target, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&ta._semi, &mut output);
 
             output
@@ -116,9 +132,12 @@ pub(crate) fn inject_item_docs(
             syncdoc_debug!("Processing Const: {}", c.name);
             let mut output = TokenStream::new();
 
-            if let Some(content) =
-                super::read_item_markdown(&context, &c.name.to_string(), docs_root)
-            {
+            if let Some(content) = super::read_directed_markdown(
+                &c.attributes,
+                &context,
+                &doc_stem(&c.attributes, &c.name),
+                docs_root,
+            ) {
                 output.extend(super::generate_doc_comments(&content));
             }
 
@@ -131,8 +150,9 @@ pub(crate) fn inject_item_docs(
             quote::ToTokens::to_tokens(&c.name, &mut output);
             unsynn::ToTokens::to_tokens(&c._colon, &mut output);
             unsynn::ToTokens::to_tokens(&c.const_type, &mut output);
-            unsynn::ToTokens::to_tokens(&c._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&c.value, &mut output);
+            if let Some(value) = &c.value {
+                unsynn::ToTokens::to_tokens(value, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&c._semi, &mut output);
 
             output
@@ -141,9 +161,12 @@ pub(crate) fn inject_item_docs(
             syncdoc_debug!("Processing Static: {}", s.name);
             let mut output = TokenStream::new();
 
-            if let Some(content) =
-                super::read_item_markdown(&context, &s.name.to_string(), docs_root)
-            {
+            if let Some(content) = super::read_directed_markdown(
+                &s.attributes,
+                &context,
+                &doc_stem(&s.attributes, &s.name),
+                docs_root,
+            ) {
                 output.extend(super::generate_doc_comments(&content));
             }
 
@@ -152,19 +175,42 @@ pub(crate) fn inject_item_docs(
             if let Some(vis) = &s.visibility {
                 quote::ToTokens::to_tokens(vis, &mut output);
             }
+            unsynn::ToTokens::to_tokens(&s._static, &mut output);
             if let Some(mut_kw) = &s.mut_kw {
                 unsynn::ToTokens::to_tokens(mut_kw, &mut output);
             }
-            unsynn::ToTokens::to_tokens(&s._static, &mut output);
             quote::ToTokens::to_tokens(&s.name, &mut output);
             unsynn::ToTokens::to_tokens(&s._colon, &mut output);
             unsynn::ToTokens::to_tokens(&s.static_type, &mut output);
-            unsynn::ToTokens::to_tokens(&s._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&s.value, &mut output);
+            if let Some(value) = &s.value {
+                unsynn::ToTokens::to_tokens(value, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&s._semi, &mut output);
 
             output
         }
+        ModuleItem::MacroRules(m) => {
+            syncdoc_debug!("Processing MacroRules: {}", m.name);
+            let mut output = TokenStream::new();
+
+            if let Some(content) = super::read_directed_markdown(
+                &m.attributes,
+                &context,
+                &doc_stem(&m.attributes, &m.name),
+                docs_root,
+            ) {
+                output.extend(super::generate_doc_comments(&content));
+            }
+
+            add_non_omnidoc_attrs(&m.attributes, &mut output);
+
+            unsynn::ToTokens::to_tokens(&m._macro_rules, &mut output);
+            unsynn::ToTokens::to_tokens(&m._bang, &mut output);
+            quote::ToTokens::to_tokens(&m.name, &mut output);
+            unsynn::ToTokens::to_tokens(&m.body, &mut output);
+
+            output
+        }
         ModuleItem::Other(t) => {
             syncdoc_debug!("Processing Other item");
             syncdoc_debug!("Other item tokens: {:?}", t);
@@ -172,6 +218,59 @@ pub(crate) fn inject_item_docs(
             t.to_tokens(&mut ts);
             ts
         }
+    };
+
+    // Docs the macro gated behind a predicate stay gated behind it inline
+    match item_attributes(item).and_then(omnidoc_cfg_attr) {
+        Some(cfg) => gate_doc_comments(output, &cfg),
+        None => output,
+    }
+}
+
+/// Turns the doc comments in `tokens`, nested ones too, into `#[cfg_attr(cfg, doc = "...")]`
+fn gate_doc_comments(tokens: TokenStream, cfg: &CfgPredicate) -> TokenStream {
+    let mut output = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(pound) if pound.as_char() == '#' => {
+                let line = match tokens.peek() {
+                    Some(TokenTree::Group(attr)) => doc_comment_line(attr),
+                    _ => None,
+                };
+                match line {
+                    Some(line) => {
+                        tokens.next();
+                        let doc = format!(" {}", line);
+                        output.extend(quote! { #[cfg_attr(#cfg, doc = #doc)] });
+                    }
+                    None => output.extend([TokenTree::Punct(pound)]),
+                }
+            }
+            TokenTree::Group(group) => {
+                let mut gated =
+                    Group::new(group.delimiter(), gate_doc_comments(group.stream(), cfg));
+                gated.set_span(group.span());
+                output.extend([TokenTree::Group(gated)]);
+            }
+            other => output.extend([other]),
+        }
+    }
+    output
+}
+
+/// The line of a `#[doc = "/// ..."]` attribute, as restore writes doc comments
+fn doc_comment_line(attr: &Group) -> Option<String> {
+    let tokens: Vec<TokenTree> = attr.stream().into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Ident(doc), TokenTree::Punct(eq), TokenTree::Literal(line)]
+            if attr.delimiter() == Delimiter::Bracket && doc == "doc" && eq.as_char() == '=' =>
+        {
+            let line = extract_string_literal(&line.to_string())?;
+            line.strip_prefix("///")
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).to_string())
+        }
+        _ => None,
     }
 }
 
@@ -182,13 +281,21 @@ pub(crate) fn inject_trait_method_docs(
 ) -> TokenStream {
     let mut output = TokenStream::new();
 
-    if let Some(content) = super::read_item_markdown(context, &method.name.to_string(), docs_root) {
+    if let Some(content) = super::read_directed_markdown(
+        &method.attributes,
+        context,
+        &doc_stem(&method.attributes, &method.name),
+        docs_root,
+    ) {
         output.extend(super::generate_doc_comments(&content));
     }
 
     add_non_omnidoc_attrs(&method.attributes, &mut output);
 
     // Manually reconstruct method signature WITHOUT attributes
+    if let Some(vis) = &method.visibility {
+        quote::ToTokens::to_tokens(vis, &mut output);
+    }
     if let Some(const_kw) = &method.const_kw {
         unsynn::ToTokens::to_tokens(const_kw, &mut output);
     }
@@ -225,7 +332,12 @@ pub(crate) fn inject_function_docs(
 ) -> TokenStream {
     let mut output = TokenStream::new();
 
-    if let Some(content) = super::read_item_markdown(context, &func.name.to_string(), docs_root) {
+    if let Some(content) = super::read_directed_markdown(
+        &func.attributes,
+        context,
+        &doc_stem(&func.attributes, &func.name),
+        docs_root,
+    ) {
         output.extend(super::generate_doc_comments(&content));
     }
 
@@ -270,9 +382,11 @@ pub(crate) fn inject_struct_docs(
     docs_root: &str,
 ) -> TokenStream {
     let mut output = TokenStream::new();
-    let struct_name = struct_sig.name.to_string();
+    let struct_name = doc_stem(&struct_sig.attributes, &struct_sig.name);
 
-    if let Some(content) = super::read_item_markdown(context, &struct_name, docs_root) {
+    if let Some(content) =
+        super::read_directed_markdown(&struct_sig.attributes, context, &struct_name, docs_root)
+    {
         output.extend(super::generate_doc_comments(&content));
     }
 
@@ -296,6 +410,13 @@ pub(crate) fn inject_struct_docs(
             let restored_fields = inject_struct_fields(fields, &struct_name, context, docs_root);
             output.extend(wrap_in_braces(restored_fields));
         }
+        StructBody::Tuple(tuple) => {
+            let mut field_context = context.to_vec();
+            field_context.push(struct_name.clone());
+            let restored_fields = inject_tuple_fields(&tuple.first, &field_context, docs_root);
+            output.extend(wrap_in_parens(restored_fields));
+            unsynn::ToTokens::to_tokens(&tuple.second, &mut output);
+        }
         other => {
             quote::ToTokens::to_tokens(other, &mut output);
         }
@@ -304,6 +425,41 @@ pub(crate) fn inject_struct_docs(
     output
 }
 
+pub(crate) fn inject_union_docs(
+    union_sig: &UnionSig,
+    context: &[String],
+    docs_root: &str,
+) -> TokenStream {
+    let mut output = TokenStream::new();
+    let union_name = doc_stem(&union_sig.attributes, &union_sig.name);
+
+    if let Some(content) =
+        super::read_directed_markdown(&union_sig.attributes, context, &union_name, docs_root)
+    {
+        output.extend(super::generate_doc_comments(&content));
+    }
+
+    add_non_omnidoc_attrs(&union_sig.attributes, &mut output);
+
+    // Manually reconstruct union WITHOUT attributes
+    if let Some(vis) = &union_sig.visibility {
+        quote::ToTokens::to_tokens(vis, &mut output);
+    }
+    union_sig._union.to_tokens(&mut output);
+    quote::ToTokens::to_tokens(&union_sig.name, &mut output);
+    if let Some(generics) = &union_sig.generics {
+        generics.to_tokens(&mut output);
+    }
+    if let Some(where_clause) = &union_sig.where_clause {
+        where_clause.to_tokens(&mut output);
+    }
+
+    let restored_fields = inject_struct_fields(&union_sig.fields, &union_name, context, docs_root);
+    output.extend(wrap_in_braces(restored_fields));
+
+    output
+}
+
 pub(crate) fn inject_struct_fields(
     fields: &BraceGroupContaining<Option<CommaDelimitedVec<StructField>>>,
     struct_name: &str,
@@ -319,8 +475,12 @@ pub(crate) fn inject_struct_fields(
 
             let mut field_context = context.to_vec();
             field_context.push(struct_name.to_string());
-            if let Some(content) = super::read_item_markdown(&field_context, &field_name, docs_root)
-            {
+            if let Some(content) = super::read_directed_markdown(
+                &field.attributes,
+                &field_context,
+                &field_name,
+                docs_root,
+            ) {
                 output.extend(super::generate_doc_comments(&content));
             }
 
@@ -349,9 +509,11 @@ pub(crate) fn inject_enum_docs(
     docs_root: &str,
 ) -> TokenStream {
     let mut output = TokenStream::new();
-    let enum_name = enum_sig.name.to_string();
+    let enum_name = doc_stem(&enum_sig.attributes, &enum_sig.name);
 
-    if let Some(content) = super::read_item_markdown(context, &enum_name, docs_root) {
+    if let Some(content) =
+        super::read_directed_markdown(&enum_sig.attributes, context, &enum_name, docs_root)
+    {
         output.extend(super::generate_doc_comments(&content));
     }
 
@@ -392,9 +554,12 @@ pub(crate) fn inject_enum_variants(
 
             let mut variant_context = context.to_vec();
             variant_context.push(enum_name.to_string());
-            if let Some(content) =
-                super::read_item_markdown(&variant_context, &variant_name, docs_root)
-            {
+            if let Some(content) = super::read_directed_markdown(
+                &variant.attributes,
+                &variant_context,
+                &variant_name,
+                docs_root,
+            ) {
                 output.extend(super::generate_doc_comments(&content));
             }
 
@@ -419,6 +584,14 @@ pub(crate) fn inject_enum_variants(
                             fields_containing.to_tokens(&mut output);
                         }
                     }
+                    EnumVariantData::Tuple(fields_containing) => {
+                        let mut field_context = context.to_vec();
+                        field_context.push(enum_name.to_string());
+                        field_context.push(variant_name.clone());
+                        let restored_fields =
+                            inject_tuple_fields(fields_containing, &field_context, docs_root);
+                        output.extend(wrap_in_parens(restored_fields));
+                    }
                     other => {
                         quote::ToTokens::to_tokens(other, &mut output);
                     }
@@ -450,7 +623,9 @@ pub(crate) fn inject_enum_variant_fields(
         let mut field_context = context.to_vec();
         field_context.push(enum_name.to_string());
         field_context.push(variant_name.to_string());
-        if let Some(content) = super::read_item_markdown(&field_context, &field_name, docs_root) {
+        if let Some(content) =
+            super::read_directed_markdown(&field.attributes, &field_context, &field_name, docs_root)
+        {
             output.extend(super::generate_doc_comments(&content));
         }
 
@@ -472,13 +647,51 @@ pub(crate) fn inject_enum_variant_fields(
     output
 }
 
+/// Restores docs on positional fields, which are stored by index under `field_context`
+pub(crate) fn inject_tuple_fields(
+    fields: &ParenthesisGroupContaining<Option<CommaDelimitedVec<TupleField>>>,
+    field_context: &[String],
+    docs_root: &str,
+) -> TokenStream {
+    let mut output = TokenStream::new();
+
+    if let Some(fields_cdv) = fields.content.as_ref() {
+        for (idx, field_delimited) in fields_cdv.0.iter().enumerate() {
+            let field = &field_delimited.value;
+
+            if let Some(content) = super::read_directed_markdown(
+                &field.attributes,
+                field_context,
+                &idx.to_string(),
+                docs_root,
+            ) {
+                output.extend(super::generate_doc_comments(&content));
+            }
+
+            add_non_omnidoc_attrs(&field.attributes, &mut output);
+
+            // Manually reconstruct field WITHOUT attributes
+            if let Some(vis) = &field.visibility {
+                quote::ToTokens::to_tokens(vis, &mut output);
+            }
+            unsynn::ToTokens::to_tokens(&field.field_type, &mut output);
+
+            if idx < fields_cdv.0.len() - 1 {
+                output.extend(quote! { , });
+            }
+        }
+    }
+
+    output
+}
+
 pub(crate) fn inject_module_docs(
     module: &ModuleSig,
     context: Vec<String>,
     docs_root: &str,
 ) -> TokenStream {
     let mut output = TokenStream::new();
-    let module_name = module.name.to_string();
+    let module_name = doc_stem(&module.attributes, &module.name);
 
     if let Some(content) = super::read_item_markdown(&context, &module_name, docs_root) {
         output.extend(super::generate_doc_comments(&content));
@@ -514,18 +727,22 @@ pub(crate) fn inject_trait_docs(
     docs_root: &str,
 ) -> TokenStream {
     let mut output = TokenStream::new();
-    let trait_name = trait_def.name.to_string();
+    let trait_name = doc_stem(&trait_def.attributes, &trait_def.name);
 
     syncdoc_debug!("\n=== INJECT TRAIT DEBUG ===");
     syncdoc_debug!("Trait name: {}", trait_name);
     syncdoc_debug!("Context: {:?}", context);
     syncdoc_debug!("Docs root: {}", docs_root);
 
-    let md_content = super::read_item_markdown(&context, &trait_name, docs_root);
+    let md_content =
+        super::read_directed_markdown(&trait_def.attributes, &context, &trait_name, docs_root);
     syncdoc_debug!("Found markdown: {}", md_content.is_some());
-    if let Some(ref content) = md_content {
-        syncdoc_debug!("Content length: {}", content.len());
-        syncdoc_debug!("Content: {:?}", &content[..content.len().min(100)]);
+    if let Some(ref docs) = md_content {
+        syncdoc_debug!("Content length: {}", docs.content.len());
+        syncdoc_debug!(
+            "Content: {:?}",
+            &docs.content[..docs.content.len().min(100)]
+        );
     }
     syncdoc_debug!("========================\n");
 
@@ -590,40 +807,8 @@ pub(crate) fn inject_impl_docs(
         where_clause.to_tokens(&mut output);
     }
 
-    let impl_context = if let Some(for_trait) = &impl_block.for_trait {
-        let trait_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        let type_name = if let Some(first) = for_trait.second.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        vec![type_name, trait_name]
-    } else {
-        let type_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-        vec![type_name]
-    };
+    let impl_context =
+        named_impl_context(impl_block, omnidoc_name_override(&impl_block.attributes));
 
     let mut new_context = context;
     new_context.extend(impl_context);
@@ -641,10 +826,48 @@ pub(crate) fn inject_impl_docs(
     output
 }
 
+pub(crate) fn inject_extern_block_docs(
+    extern_block: &ExternBlockSig,
+    context: Vec<String>,
+    docs_root: &str,
+) -> TokenStream {
+    let mut output = TokenStream::new();
+
+    // The block has no doc file, so any inline docs are left alone
+    if let Some(attrs) = &extern_block.attributes {
+        for attr in &attrs.0 {
+            if !super::is_omnidoc_attr(&attr.value) {
+                quote::ToTokens::to_tokens(&attr.value, &mut output);
+            }
+        }
+    }
+
+    if let Some(unsafe_kw) = &extern_block.unsafe_kw {
+        unsafe_kw.to_tokens(&mut output);
+    }
+    extern_block._extern.to_tokens(&mut output);
+    if let Some(abi) = &extern_block.abi {
+        abi.to_tokens(&mut output);
+    }
+
+    let mut extern_content = TokenStream::new();
+    for item_delimited in &extern_block.items.content.items.0 {
+        extern_content.extend(inject_item_docs(
+            &item_delimited.value,
+            context.clone(),
+            docs_root,
+        ));
+    }
+
+    output.extend(wrap_in_braces(extern_content));
+    output
+}
+
 pub(crate) fn add_non_omnidoc_attrs(attrs: &Option<Many<Attribute>>, output: &mut TokenStream) {
     if let Some(attr_list) = attrs {
         for attr in &attr_list.0 {
-            if !super::is_omnidoc_attr(&attr.value) {
+            // Helper attributes are only meaningful inside an omnidoc scope
+            if !super::is_omnidoc_attr(&attr.value) && !is_helper_attr(&attr.value) {
                 quote::ToTokens::to_tokens(&attr.value, output);
             }
         }
@@ -655,3 +878,8 @@ pub(crate) fn wrap_in_braces(content: TokenStream) -> TokenStream {
     let group = proc_macro2::Group::new(proc_macro2::Delimiter::Brace, content);
     std::iter::once(proc_macro2::TokenTree::Group(group)).collect()
 }
+
+pub(crate) fn wrap_in_parens(content: TokenStream) -> TokenStream {
+    let group = proc_macro2::Group::new(proc_macro2::Delimiter::Parenthesis, content);
+    std::iter::once(proc_macro2::TokenTree::Group(group)).collect()
+}
@@ -10,10 +10,13 @@ pub(crate) use unsynn::*;
 
 use crate::config::DocsPathMode;
 use crate::discover::ParsedFile;
+use crate::extract::docs_cfg_attr;
 use crate::rewrite::inject::has_module_doc_macro;
 pub(crate) use proc_macro2::TokenStream;
 use reformat::rewrite_preserving_format;
 use strip::strip_doc_attrs_from_items;
+use syncdoc_core::config::SyncdocConfig;
+use syncdoc_core::helper_attr::item_attributes;
 use syncdoc_core::parse::ModuleItem;
 
 pub fn rewrite_file(
@@ -27,6 +30,22 @@ pub fn rewrite_file(
         return None;
     }
 
+    // The predicates items' docs are gated behind, before stripping takes the docs away
+    let configured_cfg = SyncdocConfig::load(&parsed.path.to_string_lossy())
+        .ok()
+        .and_then(|config| config.cfg_attr);
+    let docs_cfgs: Vec<_> = parsed
+        .content
+        .items
+        .0
+        .iter()
+        .map(|item| {
+            item_attributes(&item.value)
+                .and_then(docs_cfg_attr)
+                .filter(|cfg| Some(cfg) != configured_cfg.as_ref())
+        })
+        .collect();
+
     let mut output = if strip {
         strip_doc_attrs_from_items(&parsed.content)
     } else {
@@ -67,7 +86,7 @@ pub fn rewrite_file(
             }
 
             // Then handle regular items
-            for item_delimited in &content.items.0 {
+            for (i, item_delimited) in content.items.0.iter().enumerate() {
                 let mut item_ts = TokenStream::new();
                 quote::ToTokens::to_tokens(&item_delimited.value, &mut item_ts);
 
@@ -76,17 +95,21 @@ pub fn rewrite_file(
                     ModuleItem::Function(_)
                         | ModuleItem::Enum(_)
                         | ModuleItem::Struct(_)
+                        | ModuleItem::Union(_)
                         | ModuleItem::Module(_)
                         | ModuleItem::Trait(_)
                         | ModuleItem::ImplBlock(_)
                         | ModuleItem::TypeAlias(_)
                         | ModuleItem::Const(_)
                         | ModuleItem::Static(_)
+                        | ModuleItem::MacroRules(_)
+                        | ModuleItem::ExternBlock(_)
                 );
 
                 if should_annotate {
                     // inject_omnidoc_attr now handles idempotency internally
-                    annotated.extend(inject_omnidoc_attr(item_ts, docs_root, docs_mode));
+                    let cfg_attr = docs_cfgs.get(i).and_then(Option::as_ref);
+                    annotated.extend(inject_omnidoc_attr(item_ts, docs_root, docs_mode, cfg_attr));
                 } else {
                     annotated.extend(item_ts);
                 }
@@ -3,6 +3,7 @@
 use crate::config::DocsPathMode;
 use proc_macro2::TokenStream;
 use quote::quote;
+use syncdoc_core::cfg::CfgPredicate;
 
 /// Checks if a token stream already contains an omnidoc attribute
 pub(crate) fn has_omnidoc_attr(item: &TokenStream) -> bool {
@@ -20,21 +21,34 @@ pub fn has_module_doc_macro(item: &TokenStream) -> bool {
 ///
 /// If `mode` is `TomlConfig`, omits the path parameter.
 /// If `mode` is `InlinePaths`, includes the path parameter.
+/// A `cfg_attr` predicate the item's docs were gated behind is passed on as `cfg_attr = "..."`.
 ///
 /// **Idempotent**: Returns the original item unchanged if it already has an omnidoc attribute.
-pub fn inject_omnidoc_attr(item: TokenStream, docs_root: &str, mode: DocsPathMode) -> TokenStream {
+pub fn inject_omnidoc_attr(
+    item: TokenStream,
+    docs_root: &str,
+    mode: DocsPathMode,
+    cfg_attr: Option<&CfgPredicate>,
+) -> TokenStream {
     // Skip if already has omnidoc attribute (idempotency)
     if has_omnidoc_attr(&item) {
         return item;
     }
 
-    let attr = match mode {
-        DocsPathMode::InlinePaths => quote! {
+    let cfg_attr = cfg_attr.map(|cfg| cfg.to_string());
+    let attr = match (mode, cfg_attr) {
+        (DocsPathMode::InlinePaths, None) => quote! {
             #[syncdoc::omnidoc(path = #docs_root)]
         },
-        DocsPathMode::TomlConfig => quote! {
+        (DocsPathMode::InlinePaths, Some(cfg)) => quote! {
+            #[syncdoc::omnidoc(path = #docs_root, cfg_attr = #cfg)]
+        },
+        (DocsPathMode::TomlConfig, None) => quote! {
             #[syncdoc::omnidoc]
         },
+        (DocsPathMode::TomlConfig, Some(cfg)) => quote! {
+            #[syncdoc::omnidoc(cfg_attr = #cfg)]
+        },
     };
 
     // Simply prepend the attribute before the entire item
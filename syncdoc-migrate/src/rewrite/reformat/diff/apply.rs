@@ -217,6 +217,7 @@ pub(crate) fn preserve_non_doc_lines<'a>(
         // Preserve non-doc attributes and comments
         if (trimmed.starts_with("#[")
             && !no_spaces.starts_with("#[doc")
+            && !moves_with_docs(&no_spaces)
             && !no_spaces.contains("omnidoc"))
             || (no_spaces.starts_with("#![") && !no_spaces.starts_with("#![doc"))
             || is_non_doc_comment
@@ -247,10 +248,18 @@ pub fn apply_diff_restore(original: &str, hunks: &[DiffHunk], formatted_after: &
         hunks,
         formatted_after,
         hunk::is_restore_related_hunk,
-        |s| strip_all_doc_attr_bookends(&s),
+        |s| strip_helper_attr_lines(&strip_all_doc_attr_bookends(&s)),
     )
 }
 
+/// Drops `#[syncdoc(...)]` helper attribute lines, which mean nothing once omnidoc is gone
+pub(crate) fn strip_helper_attr_lines(source: &str) -> String {
+    source
+        .split_inclusive('\n')
+        .filter(|line| !line.trim_start().replace(' ', "").starts_with("#[syncdoc("))
+        .collect()
+}
+
 /// Check if a line from the transformed version should be skipped
 /// because it's a non-doc attribute that we've already preserved from original
 pub(crate) fn should_skip_from_transformed(line: &str) -> bool {
@@ -263,6 +272,7 @@ pub(crate) fn should_skip_from_transformed(line: &str) -> bool {
     // Must check no_spaces version since rustfmt may add spaces: "# [facet" -> "#[facet"
     if no_spaces.starts_with("#[")
         && !no_spaces.starts_with("#[doc")
+        && !moves_with_docs(&no_spaces)
         && !no_spaces.contains("omnidoc")
         && !no_spaces.contains("syncdoc::omnidoc")
     {
@@ -282,6 +292,16 @@ pub(crate) fn should_skip_from_transformed(line: &str) -> bool {
     false
 }
 
+/// Whether an attribute line moves to and from doc files along with the docs
+///
+/// `#[doc(alias)]` and `#[doc(hidden)]` are already `#[doc` lines, which leaves `#[deprecated]`
+/// for front matter. Docs under a `cfg_attr` are docs too, whether a feature's, which go to and
+/// from its companion file, or gated behind an `#[omnidoc(cfg_attr = "...")]` predicate.
+pub(crate) fn moves_with_docs(no_spaces: &str) -> bool {
+    no_spaces.starts_with("#[deprecated")
+        || (no_spaces.starts_with("#[cfg_attr(") && no_spaces.contains(",doc="))
+}
+
 /// Checks if a hunk contains module-level documentation (inner attributes)
 pub(crate) fn is_module_level_hunk(hunk: &DiffHunk, after_lines: &[&str]) -> bool {
     let after_end = hunk.after_start + hunk.after_count;
@@ -23,6 +23,7 @@ pub fn is_doc_related_hunk(hunk: &DiffHunk, original_lines: &[&str], after_lines
                 || line.starts_with("//!")
                 || no_spaces.starts_with("#[doc=")
                 || no_spaces.starts_with("#![doc=")
+                || super::apply::moves_with_docs(&no_spaces)
             {
                 return true;
             }
@@ -307,6 +308,7 @@ pub fn is_restore_related_hunk(
                 || line.starts_with("//!")
                 || line.contains(r#"#[doc = "///"#)
                 || line.contains(r#"#[doc = "//!"#)
+                || super::apply::moves_with_docs(&line.replace(' ', ""))
         }
     });
 
@@ -1,9 +1,10 @@
 // syncdoc-migrate/src/rewrite/strip.rs
 
-use crate::extract::{is_inner_doc_attr, is_outer_doc_attr};
+use crate::extract::{is_front_matter_attr, is_inner_doc_attr, is_outer_doc_attr, skips_docs};
 use crate::syncdoc_debug;
 use proc_macro2::TokenStream;
 use quote::quote;
+use syncdoc_core::helper_attr::item_attributes;
 use syncdoc_core::parse::{Attribute, InnerAttribute, ModuleItem};
 use unsynn::*;
 
@@ -74,16 +75,27 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             ModuleItem::Function(_) => "Function",
             ModuleItem::Enum(_) => "Enum",
             ModuleItem::Struct(_) => "Struct",
+            ModuleItem::Union(_) => "Union",
             ModuleItem::Module(_) => "Module",
             ModuleItem::ImplBlock(_) => "ImplBlock",
             ModuleItem::Trait(_) => "Trait",
             ModuleItem::TypeAlias(_) => "TypeAlias",
             ModuleItem::Const(_) => "Const",
             ModuleItem::Static(_) => "Static",
+            ModuleItem::MacroRules(_) => "MacroRules",
+            ModuleItem::ExternBlock(_) => "ExternBlock",
             ModuleItem::Other(_) => "Other",
         }
     );
     // syncdoc_debug!("Processing item: {:?}", item);
+
+    // Skipped items keep their inline docs since they get no markdown file
+    if item_attributes(item).is_some_and(skips_docs) {
+        let mut output = TokenStream::new();
+        unsynn::ToTokens::to_tokens(item, &mut output);
+        return output;
+    }
+
     match item {
         ModuleItem::TraitMethod(method) => {
             let stripped_attrs = strip_doc_attrs_from_attr_list(&method.attributes);
@@ -95,6 +107,9 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             }
 
             // Add rest of method signature
+            if let Some(vis) = &method.visibility {
+                quote::ToTokens::to_tokens(vis, &mut output);
+            }
             if let Some(const_kw) = &method.const_kw {
                 unsynn::ToTokens::to_tokens(const_kw, &mut output);
             }
@@ -237,7 +252,13 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
                     }
                 }
                 syncdoc_core::parse::StructBody::Tuple(tuple) => {
-                    unsynn::ToTokens::to_tokens(tuple, &mut output);
+                    if let Some(fields_cdv) = tuple.first.content.as_ref() {
+                        let processed_fields = strip_doc_attrs_from_tuple_fields(fields_cdv);
+                        output.extend(wrap_in_parens(processed_fields));
+                        unsynn::ToTokens::to_tokens(&tuple.second, &mut output);
+                    } else {
+                        unsynn::ToTokens::to_tokens(tuple, &mut output);
+                    }
                 }
                 syncdoc_core::parse::StructBody::Unit(semi) => {
                     unsynn::ToTokens::to_tokens(semi, &mut output);
@@ -247,6 +268,37 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             output
         }
 
+        ModuleItem::Union(union_sig) => {
+            let mut output = TokenStream::new();
+
+            let stripped_attrs = strip_doc_attrs_from_attr_list(&union_sig.attributes);
+            for attr in stripped_attrs {
+                quote::ToTokens::to_tokens(&attr, &mut output);
+            }
+
+            if let Some(vis) = &union_sig.visibility {
+                quote::ToTokens::to_tokens(vis, &mut output);
+            }
+            unsynn::ToTokens::to_tokens(&union_sig._union, &mut output);
+            quote::ToTokens::to_tokens(&union_sig.name, &mut output);
+            if let Some(generics) = &union_sig.generics {
+                unsynn::ToTokens::to_tokens(generics, &mut output);
+            }
+            if let Some(where_clause) = &union_sig.where_clause {
+                unsynn::ToTokens::to_tokens(where_clause, &mut output);
+            }
+
+            // Strip docs from fields
+            if let Some(fields_cdv) = union_sig.fields.content.as_ref() {
+                let processed_fields = strip_doc_attrs_from_fields(fields_cdv);
+                output.extend(wrap_in_braces(processed_fields));
+            } else {
+                unsynn::ToTokens::to_tokens(&union_sig.fields, &mut output);
+            }
+
+            output
+        }
+
         ModuleItem::Module(module) => {
             let mut output = TokenStream::new();
 
@@ -368,8 +420,15 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             if let Some(generics) = &type_alias.generics {
                 unsynn::ToTokens::to_tokens(generics, &mut output);
             }
-            unsynn::ToTokens::to_tokens(&type_alias._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&type_alias.target, &mut output);
+            if let Some(bounds) = &type_alias.bounds {
+                unsynn::ToTokens::to_tokens(bounds, &mut output);
+            }
+            if let Some(where_clause) = &type_alias.where_clause {
+                unsynn::ToTokens::to_tokens(where_clause, &mut output);
+            }
+            if let Some(target) = &type_alias.target {
+                unsynn::ToTokens::to_tokens(target, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&type_alias._semi, &mut output);
 
             output
@@ -390,8 +449,9 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             quote::ToTokens::to_tokens(&const_sig.name, &mut output);
             unsynn::ToTokens::to_tokens(&const_sig._colon, &mut output);
             unsynn::ToTokens::to_tokens(&const_sig.const_type, &mut output);
-            unsynn::ToTokens::to_tokens(&const_sig._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&const_sig.value, &mut output);
+            if let Some(value) = &const_sig.value {
+                unsynn::ToTokens::to_tokens(value, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&const_sig._semi, &mut output);
 
             output
@@ -408,20 +468,68 @@ pub(crate) fn strip_doc_attrs_from_item(item: &ModuleItem) -> TokenStream {
             if let Some(vis) = &static_sig.visibility {
                 quote::ToTokens::to_tokens(vis, &mut output);
             }
+            unsynn::ToTokens::to_tokens(&static_sig._static, &mut output);
             if let Some(mut_kw) = &static_sig.mut_kw {
                 unsynn::ToTokens::to_tokens(mut_kw, &mut output);
             }
-            unsynn::ToTokens::to_tokens(&static_sig._static, &mut output);
             quote::ToTokens::to_tokens(&static_sig.name, &mut output);
             unsynn::ToTokens::to_tokens(&static_sig._colon, &mut output);
             unsynn::ToTokens::to_tokens(&static_sig.static_type, &mut output);
-            unsynn::ToTokens::to_tokens(&static_sig._eq, &mut output);
-            unsynn::ToTokens::to_tokens(&static_sig.value, &mut output);
+            if let Some(value) = &static_sig.value {
+                unsynn::ToTokens::to_tokens(value, &mut output);
+            }
             unsynn::ToTokens::to_tokens(&static_sig._semi, &mut output);
 
             output
         }
 
+        ModuleItem::MacroRules(macro_sig) => {
+            let mut output = TokenStream::new();
+
+            let stripped_attrs = strip_doc_attrs_from_attr_list(&macro_sig.attributes);
+            for attr in stripped_attrs {
+                quote::ToTokens::to_tokens(&attr, &mut output);
+            }
+
+            unsynn::ToTokens::to_tokens(&macro_sig._macro_rules, &mut output);
+            unsynn::ToTokens::to_tokens(&macro_sig._bang, &mut output);
+            quote::ToTokens::to_tokens(&macro_sig.name, &mut output);
+            unsynn::ToTokens::to_tokens(&macro_sig.body, &mut output);
+
+            output
+        }
+
+        ModuleItem::ExternBlock(extern_block) => {
+            let mut output = TokenStream::new();
+
+            // The block has no doc file, so it keeps its docs
+            if let Some(attrs) = &extern_block.attributes {
+                unsynn::ToTokens::to_tokens(attrs, &mut output);
+            }
+
+            if let Some(unsafe_kw) = &extern_block.unsafe_kw {
+                unsynn::ToTokens::to_tokens(unsafe_kw, &mut output);
+            }
+            unsynn::ToTokens::to_tokens(&extern_block._extern, &mut output);
+            if let Some(abi) = &extern_block.abi {
+                unsynn::ToTokens::to_tokens(abi, &mut output);
+            }
+
+            // Recursively process the foreign items
+            let body_stream = extract_brace_group_containing_content(&extern_block.items);
+            if let Ok(content) = body_stream
+                .into_token_iter()
+                .parse::<syncdoc_core::parse::ModuleContent>()
+            {
+                let processed = strip_doc_attrs_from_items(&content);
+                output.extend(wrap_in_braces(processed));
+            } else {
+                unsynn::ToTokens::to_tokens(&extern_block.items, &mut output);
+            }
+
+            output
+        }
+
         ModuleItem::Other(token) => {
             let mut output = TokenStream::new();
             unsynn::ToTokens::to_tokens(token, &mut output);
@@ -437,13 +545,15 @@ pub(crate) fn strip_doc_attrs_from_attr_list(
     let Some(attr_list) = attrs else {
         return Vec::new();
     };
+    let keep_docs = skips_docs(attrs);
 
     attr_list
         .0
         .iter()
         .filter_map(|attr_delimited| {
             let attr = &attr_delimited.value;
-            if is_outer_doc_attr(attr) {
+            // `#[deprecated]` goes too, as it moves into the doc file's front matter
+            if (is_outer_doc_attr(attr) || is_front_matter_attr(attr)) && !keep_docs {
                 None // Filter out doc attributes
             } else {
                 Some(attr.clone()) // Keep non-doc attributes
@@ -476,6 +586,14 @@ pub(crate) fn strip_doc_attrs_from_variants(
                         unsynn::ToTokens::to_tokens(fields_containing, &mut variant_output);
                     }
                 }
+                syncdoc_core::parse::EnumVariantData::Tuple(fields_containing) => {
+                    if let Some(fields_cdv) = fields_containing.content.as_ref() {
+                        let processed_fields = strip_doc_attrs_from_tuple_fields(fields_cdv);
+                        variant_output.extend(wrap_in_parens(processed_fields));
+                    } else {
+                        unsynn::ToTokens::to_tokens(fields_containing, &mut variant_output);
+                    }
+                }
                 _ => {
                     quote::ToTokens::to_tokens(data, &mut variant_output);
                 }
@@ -525,6 +643,32 @@ pub(crate) fn strip_doc_attrs_from_fields(
     output
 }
 
+pub(crate) fn strip_doc_attrs_from_tuple_fields(
+    fields: &CommaDelimitedVec<syncdoc_core::parse::TupleField>,
+) -> TokenStream {
+    let mut output = TokenStream::new();
+
+    for (idx, field_delimited) in fields.0.iter().enumerate() {
+        let field = &field_delimited.value;
+
+        let stripped_attrs = strip_doc_attrs_from_attr_list(&field.attributes);
+        for attr in stripped_attrs {
+            quote::ToTokens::to_tokens(&attr, &mut output);
+        }
+
+        if let Some(vis) = &field.visibility {
+            quote::ToTokens::to_tokens(vis, &mut output);
+        }
+        unsynn::ToTokens::to_tokens(&field.field_type, &mut output);
+
+        if idx < fields.0.len() - 1 {
+            output.extend(quote! { , });
+        }
+    }
+
+    output
+}
+
 pub(crate) fn extract_brace_group_containing_content<T: unsynn::ToTokens>(
     brace_group_containing: &BraceGroupContaining<T>,
 ) -> TokenStream {
@@ -537,3 +681,8 @@ pub(crate) fn wrap_in_braces(content: TokenStream) -> TokenStream {
     let group = proc_macro2::Group::new(proc_macro2::Delimiter::Brace, content);
     std::iter::once(proc_macro2::TokenTree::Group(group)).collect()
 }
+
+pub(crate) fn wrap_in_parens(content: TokenStream) -> TokenStream {
+    let group = proc_macro2::Group::new(proc_macro2::Delimiter::Parenthesis, content);
+    std::iter::once(proc_macro2::TokenTree::Group(group)).collect()
+}
@@ -0,0 +1,198 @@
+//! Reading and writing docs in the sections layout, where each module's docs are one file
+//!
+//! The per-item paths everything else works with are folded into (or split back out of) the
+//! module's sections file here, using the keys from [`syncdoc_core::sections`].
+
+use crate::discover::ParsedFile;
+use crate::write::{find_expected_doc_paths, DocExtract};
+use std::fs;
+use std::path::{Path, PathBuf};
+pub use syncdoc_core::config::DocLayout;
+use syncdoc_core::features::feature_of;
+use syncdoc_core::sections::{item_path, section_of, Sections};
+
+/// Gets the doc layout for the crate a source file or directory belongs to
+///
+/// Falls back to the per-item layout when the crate has no config for it.
+pub fn get_layout(source_file: &Path) -> DocLayout {
+    syncdoc_core::config::SyncdocConfig::load(&source_file.to_string_lossy())
+        .map(|config| config.layout)
+        .unwrap_or_default()
+}
+
+/// The path a source file's item docs are kept under, such as `docs/types` for `src/types.rs`
+///
+/// The module's own docs are at this path plus `.md`, which in the sections layout is also
+/// where its items' sections are.
+pub fn module_base(parsed: &ParsedFile, docs_root: &str) -> String {
+    let module_path = syncdoc_core::path_utils::extract_module_path(&parsed.path.to_string_lossy());
+    if module_path.is_empty() {
+        let file_stem = parsed
+            .path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("module");
+        format!("{}/{}", docs_root, file_stem)
+    } else {
+        format!("{}/{}", docs_root, module_path)
+    }
+}
+
+/// The name a module's own doc file is titled with: the module's, or the crate's at its root
+pub fn module_title(parsed: &ParsedFile) -> String {
+    let source_file = parsed.path.to_string_lossy();
+    let module_path = syncdoc_core::path_utils::rust_module_path(&source_file);
+    match module_path.rsplit("::").next() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => syncdoc_core::config::get_package_value(&source_file, "name")
+            .ok()
+            .flatten()
+            .unwrap_or_default(),
+    }
+}
+
+/// Reads a module's sections file, or an empty one if it doesn't exist yet
+fn read_sections(base: &str) -> Sections {
+    fs::read_to_string(format!("{}.md", base))
+        .map(|content| Sections::parse(&content))
+        .unwrap_or_default()
+}
+
+/// Folds a file's extracts into its module's sections file, merging with what is already there
+///
+/// Extracts for the module itself become the preamble, and those for its items replace or add
+/// their sections. Extracts outside the module, like `#[syncdoc(path = ...)]` redirects, are
+/// kept as their own files.
+pub fn fold_into_sections(extracts: Vec<DocExtract>, base: &str) -> Vec<DocExtract> {
+    let module_doc = PathBuf::from(format!("{}.md", base));
+    let mut sections = read_sections(base);
+    let mut location = None;
+    let mut kept = Vec::new();
+
+    for extract in extracts {
+        // A feature's docs stay in their companion file, beside where the item's would be
+        let key = section_of(&extract.markdown_path.to_string_lossy(), base)
+            .filter(|_| feature_of(&extract.markdown_path).is_none())
+            .map(|(_, key)| key);
+        if extract.markdown_path == module_doc {
+            sections.preamble = extract.content.trim_matches('\n').to_string();
+        } else if let Some(key) = key {
+            sections.set(&key, &extract.content);
+        } else {
+            kept.push(extract);
+            continue;
+        }
+        location.get_or_insert(extract.source_location);
+    }
+
+    if let Some(location) = location {
+        kept.insert(0, DocExtract::new(module_doc, sections.render(), location));
+    }
+    kept
+}
+
+/// Whether an item's docs exist, either as its own file or as a section of its module's file
+pub fn doc_exists(markdown_path: &Path, base: &str) -> bool {
+    if markdown_path.exists() {
+        return true;
+    }
+    match section_of(&markdown_path.to_string_lossy(), base) {
+        Some((_, key)) => read_sections(base).get(&key).is_some(),
+        None => false,
+    }
+}
+
+/// Reads an item's docs from the section for it in its module's file, if there is one
+pub fn read_section(markdown_path: &str, base: &str) -> Option<String> {
+    let (file, key) = section_of(markdown_path, base)?;
+    let content = fs::read_to_string(file).ok()?;
+    Sections::parse(&content).get(&key).map(str::to_string)
+}
+
+/// Gathers a source file's per-item doc files into its module's sections file
+///
+/// Returns the sections file to write and the per-item files it replaces. Items are added in
+/// source order, after any sections the file already has.
+pub fn to_sections(parsed: &ParsedFile, docs_root: &str) -> (Vec<DocExtract>, Vec<PathBuf>) {
+    let base = module_base(parsed, docs_root);
+    let location = format!("{}:1", parsed.path.display());
+    let mut sections = read_sections(&base);
+    let mut replaced = Vec::new();
+
+    for expected in find_expected_doc_paths(parsed, docs_root) {
+        let path = expected.markdown_path.to_string_lossy().to_string();
+        let Some((_, key)) = section_of(&path, &base) else {
+            continue;
+        };
+        if let Ok(content) = fs::read_to_string(&expected.markdown_path) {
+            sections.set(&key, &content);
+            replaced.push(expected.markdown_path);
+        }
+    }
+
+    if replaced.is_empty() {
+        return (Vec::new(), replaced);
+    }
+    let module_doc = PathBuf::from(format!("{}.md", base));
+    (
+        vec![DocExtract::new(module_doc, sections.render(), location)],
+        replaced,
+    )
+}
+
+/// Splits a source file's sections file back out into a file per item
+///
+/// The module's own docs are left in the sections file's place.
+pub fn to_per_item(parsed: &ParsedFile, docs_root: &str) -> Vec<DocExtract> {
+    let base = module_base(parsed, docs_root);
+    let Ok(content) = fs::read_to_string(format!("{}.md", base)) else {
+        return Vec::new();
+    };
+    let sections = Sections::parse(&content);
+    if sections.items.is_empty() {
+        return Vec::new();
+    }
+
+    let location = format!("{}:1", parsed.path.display());
+    let mut extracts = vec![DocExtract::new(
+        PathBuf::from(format!("{}.md", base)),
+        sections.preamble.clone(),
+        location.clone(),
+    )];
+    for (key, content) in &sections.items {
+        extracts.push(DocExtract::new(
+            PathBuf::from(item_path(&base, key)),
+            content.clone(),
+            location.clone(),
+        ));
+    }
+    extracts
+}
+
+/// Removes per-item doc files replaced by sections, and any directories that leaves empty
+///
+/// Returns how many files were (or in a dry run, would be) removed.
+pub fn remove_replaced(
+    replaced: &[PathBuf],
+    docs_root: &str,
+    dry_run: bool,
+) -> std::io::Result<usize> {
+    let docs_root = Path::new(docs_root);
+    for path in replaced {
+        if dry_run {
+            println!("Would remove: {}", path.display());
+            continue;
+        }
+        fs::remove_file(path)?;
+        let mut dir = path.parent();
+        while let Some(parent) =
+            dir.filter(|parent| parent.starts_with(docs_root) && *parent != docs_root)
+        {
+            if fs::remove_dir(parent).is_err() {
+                break;
+            }
+            dir = parent.parent();
+        }
+    }
+    Ok(replaced.len())
+}
@@ -0,0 +1,51 @@
+use crate::assets::asset_report;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use tempfile::TempDir;
+
+/// A docs directory with the given files
+fn setup_docs(docs: &[(&str, &str)]) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    for (path, content) in docs {
+        let path = temp.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    temp
+}
+
+fn set(paths: &[&str]) -> BTreeSet<String> {
+    paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn test_assets_are_sorted_by_whether_docs_reference_them() {
+    let docs = setup_docs(&[
+        ("lib.md", "![Architecture](img/arch.png)\n"),
+        (
+            "lib/api/run.md",
+            "![Architecture](../../img/arch.png) ![Flow](flow.svg) ![Remote](https://a.io/b.png)\n",
+        ),
+        ("lib/api/flow.md", "![Gone](gone.png)\n"),
+        ("img/arch.png", "png"),
+        ("img/old.png", "png"),
+    ]);
+
+    let report = asset_report(docs.path()).unwrap();
+
+    assert_eq!(
+        report.referenced,
+        BTreeMap::from([(
+            "img/arch.png".to_string(),
+            set(&["lib.md", "lib/api/run.md"])
+        )])
+    );
+    assert_eq!(report.unreferenced, set(&["img/old.png"]));
+    assert_eq!(
+        report.missing,
+        BTreeMap::from([
+            ("lib/api/flow.svg".to_string(), set(&["lib/api/run.md"])),
+            ("lib/api/gone.png".to_string(), set(&["lib/api/flow.md"])),
+        ])
+    );
+}
@@ -1,4 +1,5 @@
 use crate::discover::*;
+use crate::sections::DocLayout;
 use braces::{brace_paths, BraceConfig};
 use insta::assert_snapshot;
 use std::fs;
@@ -89,3 +90,27 @@ fn test_parse_invalid_returns_error() {
         _ => panic!("Expected ParseError::ParseFailed"),
     }
 }
+
+#[test]
+fn test_set_layout_replaces_or_adds_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest = temp_dir.path().join("Cargo.toml");
+    fs::create_dir(temp_dir.path().join("src")).unwrap();
+    let source = temp_dir.path().join("src/lib.rs");
+
+    fs::write(
+        &manifest,
+        "[package]\nname = \"test\"\n\n[package.metadata.syncdoc]\ndocs-path = \"docs\"\n\n[dependencies]\n",
+    )
+    .unwrap();
+    set_layout(&source, DocLayout::Sections).unwrap();
+    assert_eq!(
+        fs::read_to_string(&manifest).unwrap(),
+        "[package]\nname = \"test\"\n\n[package.metadata.syncdoc]\ndocs-path = \"docs\"\nlayout = \"sections\"\n\n[dependencies]\n"
+    );
+
+    set_layout(&source, DocLayout::PerItem).unwrap();
+    assert!(fs::read_to_string(&manifest)
+        .unwrap()
+        .contains("docs-path = \"docs\"\nlayout = \"per-item\"\n\n[dependencies]"));
+}
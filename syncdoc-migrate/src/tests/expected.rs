@@ -84,7 +84,7 @@ fn test_find_expected_enum_with_variants() {
         "docs",
     );
 
-    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Status/{Active,Inactive,Error,}}.md");
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Status/{Active,Inactive,Error/{0,},}}.md");
 }
 
 #[test]
@@ -231,7 +231,7 @@ fn test_find_expected_only_functions_with_bodies() {
         "docs",
     );
 
-    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,MyTrait/{required,with_default,}}.md");
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,MyTrait/{required,with_default,},external_fn}.md");
 }
 
 #[test]
@@ -326,3 +326,166 @@ fn test_find_expected_regular_impl_vs_trait_impl() {
         @"docs/{test,MyStruct/{new,MyTrait/trait_method,},MyTrait/{trait_method,}}.md"
     );
 }
+
+#[test]
+fn test_find_expected_follows_name_override() {
+    let paths = parse_and_get_paths(
+        r#"
+        #[syncdoc::omnidoc(name = "ServerConfig")]
+        pub struct Config {
+            pub port: u16,
+        }
+
+        #[syncdoc::omnidoc(name = "LegacyReader")]
+        impl Reader {
+            pub fn read(&self) {}
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,ServerConfig/{port,},LegacyReader/read}.md");
+}
+
+#[test]
+fn test_find_expected_follows_helper_attrs() {
+    let paths = parse_and_get_paths(
+        r#"
+        pub struct Config {
+            pub port: u16,
+            #[syncdoc(skip)]
+            pub scratch: Vec<u8>,
+        }
+
+        #[syncdoc(skip)]
+        pub struct Shim {
+            pub inner: u8,
+        }
+
+        #[syncdoc(path = "shared/ctor.md")]
+        pub fn new_config() -> Config { todo!() }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Config/{port,},shared/ctor}.md");
+}
+
+#[test]
+fn test_find_expected_tuple_fields() {
+    let paths = parse_and_get_paths(
+        r#"
+        pub struct Meters(pub f64);
+
+        pub enum Shape {
+            Circle(f64),
+            Rect(f64, f64),
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Meters/{0,},Shape/{Circle/{0,},Rect/{0,1,},}}.md");
+}
+
+#[test]
+fn test_find_expected_union() {
+    let paths = parse_and_get_paths(
+        r#"
+        #[repr(C)]
+        pub union Value {
+            pub i: u32,
+            pub f: f32,
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Value/{i,f,}}.md");
+}
+
+#[test]
+fn test_find_expected_macro_rules() {
+    let paths = parse_and_get_paths(
+        r#"
+        mod helpers {
+            macro_rules! square {
+                ($x:expr) => { $x * $x };
+            }
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,helpers/{square,}}.md");
+}
+
+#[test]
+fn test_find_expected_extern_block() {
+    let paths = parse_and_get_paths(
+        r#"
+        extern "C" {
+            pub fn abs(x: i32) -> i32;
+            pub static errno: i32;
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test.md,abs.md,errno.md}");
+}
+
+#[test]
+fn test_find_expected_associated_items() {
+    let paths = parse_and_get_paths(
+        r#"
+        pub trait Parser {
+            type Output: Clone;
+            const MAX_DEPTH: usize;
+        }
+
+        impl Parser for Json {
+            type Output = Value;
+            const MAX_DEPTH: usize = 64;
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{test,Parser/{Output,MAX_DEPTH,},Json/Parser/{Output,MAX_DEPTH}}.md");
+}
+
+#[test]
+fn test_find_expected_generic_impls() {
+    let paths = parse_and_get_paths(
+        r#"
+        impl From<A> for Foo {
+            fn from(a: A) -> Self { Foo }
+        }
+
+        impl From<B> for Foo {
+            fn from(b: B) -> Self { Foo }
+        }
+
+        impl<T: Debug> Describe for &T {
+            fn describe(&self) -> String { String::new() }
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(get_path_refs(&paths).join("\n"), @r"
+    docs/test.md
+    docs/Foo/From[A]/from.md
+    docs/Foo/From[B]/from.md
+    docs/&T/Describe/describe.md
+    ");
+}
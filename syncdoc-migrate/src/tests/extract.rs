@@ -141,3 +141,126 @@ fn test_unescape_rust_string() {
     assert_eq!(unescape_rust_string(r#"quote\'"#), "quote'");
     assert_eq!(unescape_rust_string("no escapes"), "no escapes");
 }
+
+#[test]
+fn test_omnidoc_name_override() {
+    let attrs = parse_attrs(quote! {
+        #[cfg(feature = "v2")]
+        #[syncdoc::omnidoc(name = "read_v2")]
+    });
+    assert_eq!(omnidoc_name_override(&attrs), Some("read_v2".to_string()));
+
+    let attrs = parse_attrs(quote! {
+        #[omnidoc(path = "docs", name = "read_v2")]
+    });
+    assert_eq!(omnidoc_name_override(&attrs), Some("read_v2".to_string()));
+
+    let attrs = parse_attrs(quote! {
+        #[syncdoc::omnidoc]
+        #[doc = "name = \"not_an_override\""]
+    });
+    assert_eq!(omnidoc_name_override(&attrs), None);
+}
+
+#[test]
+fn test_extract_lifts_attributes_into_front_matter() {
+    let attrs = parse_attrs(quote! {
+        #[doc(alias = "get")]
+        #[deprecated(since = "1.4", note = "use bar")]
+        #[doc = " Fetches the value"]
+    });
+
+    assert_eq!(
+        extract_doc_content(&attrs),
+        Some(
+            "---\ndeprecated: \"use bar\"\nsince: \"1.4\"\naliases: [\"get\"]\n---\n\nFetches the value"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn test_extract_keeps_feature_docs_apart() {
+    let attrs = parse_attrs(quote! {
+        #[doc = " Settings for a run"]
+        #[cfg_attr(feature = "serde", doc = " With `serde` it can be")]
+        #[cfg_attr(feature = "serde", doc = " read from a file.")]
+        #[cfg_attr(feature = "tokio", doc = " With `tokio` it's async.")]
+        #[cfg_attr(feature = "serde", derive(Serialize))]
+    });
+
+    assert_eq!(
+        extract_doc_content(&attrs),
+        Some("Settings for a run".to_string())
+    );
+    assert_eq!(
+        extract_feature_docs(&attrs),
+        [
+            (
+                "serde".to_string(),
+                "With `serde` it can be\nread from a file.".to_string()
+            ),
+            ("tokio".to_string(), "With `tokio` it's async.".to_string()),
+        ]
+    );
+
+    // Only the conditional docs are docs to strip, not the conditional derive
+    let attrs = attrs.unwrap();
+    let is_doc: Vec<_> = attrs
+        .0
+        .iter()
+        .map(|a| is_outer_doc_attr(&a.value))
+        .collect();
+    assert_eq!(is_doc, [true, true, true, true, false]);
+}
+
+#[test]
+fn test_extract_item_with_only_feature_docs() {
+    let attrs = parse_attrs(quote! {
+        #[cfg_attr(feature = "serde", doc = " Serializable")]
+    });
+
+    assert_eq!(extract_doc_content(&attrs), Some(String::new()));
+}
+
+#[test]
+fn test_docs_cfg_attr_needs_every_line_gated_alike() {
+    let gated = parse_attrs(quote! {
+        #[cfg_attr(all(doc, not(test)), doc = " Settings for a run")]
+        #[cfg_attr(all(doc, not(test)), doc = " with `feature = \"x\"`.")]
+        #[cfg_attr(feature = "serde", doc = " Serializable")]
+        #[derive(Debug)]
+    });
+    assert_eq!(
+        extract_doc_content(&gated),
+        Some("Settings for a run\nwith `feature = \"x\"`.".to_string())
+    );
+    assert_eq!(
+        docs_cfg_attr(&gated).map(|cfg| cfg.to_string()),
+        Some("all(doc, not(test))".to_string())
+    );
+
+    let mixed = parse_attrs(quote! {
+        #[cfg_attr(docsrs, doc = " Settings for a run")]
+        #[doc = " and more."]
+    });
+    assert_eq!(docs_cfg_attr(&mixed), None);
+    let differing = parse_attrs(quote! {
+        #[cfg_attr(docsrs, doc = " Settings for a run")]
+        #[cfg_attr(doc, doc = " and more.")]
+    });
+    assert_eq!(docs_cfg_attr(&differing), None);
+}
+
+#[test]
+fn test_omnidoc_cfg_attr_reads_the_predicate() {
+    let attrs = parse_attrs(quote! {
+        #[syncdoc::omnidoc(name = "run_v2", cfg_attr = "any(doc, docsrs)")]
+    });
+
+    assert_eq!(omnidoc_name_override(&attrs), Some("run_v2".to_string()));
+    assert_eq!(
+        omnidoc_cfg_attr(&attrs).map(|cfg| cfg.to_string()),
+        Some("any(doc, docsrs)".to_string())
+    );
+}
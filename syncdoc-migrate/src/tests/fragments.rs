@@ -0,0 +1,80 @@
+use crate::fragments::fragment_users;
+use crate::sections::DocLayout;
+use std::collections::BTreeSet;
+use std::fs;
+use tempfile::TempDir;
+
+/// A docs directory with the given files
+fn setup_docs(docs: &[(&str, &str)]) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    for (path, content) in docs {
+        let path = temp.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    temp
+}
+
+fn set(docs: &[&str]) -> BTreeSet<String> {
+    docs.iter().map(|doc| doc.to_string()).collect()
+}
+
+#[test]
+fn test_users_include_those_through_other_fragments() {
+    let docs = setup_docs(&[
+        (
+            "lib/read.md",
+            "Reads.\n\n<!-- syncdoc:include shared/safety.md -->\n",
+        ),
+        (
+            "lib/write.md",
+            "Writes.\n\n<!-- syncdoc:include shared/aligned.md -->\n",
+        ),
+        ("lib/len.md", "The length.\n"),
+        (
+            "shared/safety.md",
+            "# Safety\n\n<!-- syncdoc:include shared/aligned.md -->\n",
+        ),
+        ("shared/aligned.md", "It must be aligned.\n"),
+    ]);
+
+    let users = fragment_users(docs.path(), DocLayout::PerItem).unwrap();
+
+    assert_eq!(users.len(), 2);
+    assert_eq!(users["shared/safety.md"], set(&["lib/read.md"]));
+    assert_eq!(
+        users["shared/aligned.md"],
+        set(&["lib/read.md", "lib/write.md", "shared/safety.md"])
+    );
+}
+
+#[test]
+fn test_sections_are_named_users() {
+    let docs = setup_docs(&[
+        (
+            "lib.md",
+            "Crate docs\n\n## `read`\n\n<!-- syncdoc:include shared/safety.md -->\n\n## `len`\n\nThe length.\n",
+        ),
+        ("shared/safety.md", "# Safety\n"),
+    ]);
+
+    let users = fragment_users(docs.path(), DocLayout::Sections).unwrap();
+
+    assert_eq!(users["shared/safety.md"], set(&["lib.md#read"]));
+}
+
+#[test]
+fn test_cycles_end_the_walk() {
+    let docs = setup_docs(&[
+        ("lib/read.md", "<!-- syncdoc:include shared/a.md -->\n"),
+        ("shared/a.md", "<!-- syncdoc:include shared/b.md -->\n"),
+        ("shared/b.md", "<!-- syncdoc:include shared/a.md -->\n"),
+    ]);
+
+    let users = fragment_users(docs.path(), DocLayout::PerItem).unwrap();
+
+    assert_eq!(
+        users["shared/a.md"],
+        set(&["lib/read.md", "shared/a.md", "shared/b.md"])
+    );
+}
@@ -11,7 +11,7 @@ fn test_inject_omnidoc_before_visibility() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths, None);
     let output_str = output.to_string();
 
     eprintln!("{}", output_str);
@@ -34,7 +34,7 @@ fn test_inject_omnidoc_before_derive() {
         pub struct MyStruct;
     };
 
-    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths, None);
     let output_str = output.to_string();
 
     eprintln!("{}", output_str);
@@ -51,7 +51,7 @@ fn test_inject_omnidoc_no_visibility() {
         fn private_func() {}
     };
 
-    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths, None);
     let output_str = output.to_string();
 
     assert!(output_str.contains("omnidoc"));
@@ -64,7 +64,7 @@ fn test_inject_omnidoc_toml_config_mode() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::TomlConfig);
+    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::TomlConfig, None);
     let output_str = output.to_string();
 
     eprintln!("{}", output_str);
@@ -75,6 +75,29 @@ fn test_inject_omnidoc_toml_config_mode() {
     assert!(!output_str.contains("\"docs\""));
 }
 
+#[test]
+fn test_inject_omnidoc_passes_on_the_docs_predicate() {
+    let cfg: syncdoc_core::cfg::CfgPredicate = "all(doc, not(test))".parse().unwrap();
+    let input = quote! {
+        pub fn test() {}
+    };
+
+    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::TomlConfig, Some(&cfg));
+    assert_eq!(
+        output.to_string(),
+        quote! {
+            #[syncdoc::omnidoc(cfg_attr = "all(doc, not(test))")]
+            pub fn test() {}
+        }
+        .to_string()
+    );
+
+    let output = inject_omnidoc_attr(input, "docs", DocsPathMode::InlinePaths, Some(&cfg));
+    assert!(output
+        .to_string()
+        .contains("omnidoc (path = \"docs\" , cfg_attr = \"all(doc, not(test))\")"));
+}
+
 #[test]
 fn test_inject_omnidoc_idempotent() {
     let input = quote! {
@@ -82,7 +105,7 @@ fn test_inject_omnidoc_idempotent() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths, None);
 
     // Should return unchanged (no duplicate attribute)
     assert_eq!(output.to_string(), input.to_string());
@@ -95,7 +118,7 @@ fn test_inject_omnidoc_idempotent_with_path() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths, None);
 
     // Should return unchanged
     assert_eq!(output.to_string(), input.to_string());
@@ -108,7 +131,7 @@ fn test_inject_omnidoc_idempotent_short_form() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::TomlConfig);
+    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::TomlConfig, None);
 
     // Should return unchanged (recognizes short form too)
     assert_eq!(output.to_string(), input.to_string());
@@ -123,7 +146,7 @@ fn test_inject_omnidoc_with_other_attrs() {
         pub fn test() {}
     };
 
-    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths);
+    let output = inject_omnidoc_attr(input.clone(), "docs", DocsPathMode::InlinePaths, None);
 
     // Should return unchanged even with other attributes
     assert_eq!(output.to_string(), input.to_string());
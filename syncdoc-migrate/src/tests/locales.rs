@@ -0,0 +1,72 @@
+use crate::discover::parse_file;
+use crate::locales::{locale_docs_paths, translation_coverage};
+use std::fs;
+use tempfile::TempDir;
+
+const SOURCE: &str = "#![doc = syncdoc::module_doc!()]
+
+#[syncdoc::omnidoc]
+pub struct Config {
+    pub port: u16,
+}
+
+#[syncdoc::omnidoc]
+pub fn run() {}
+";
+
+/// A crate with docs in English and Japanese, and the given doc files
+fn setup_crate(docs: &[(&str, &str)]) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[package]\nname = \"test\"\nversion = \"0.1.0\"\n\n\
+        [package.metadata.syncdoc]\ndocs-path = \"docs/{locale}\"\nlocales = [\"en\", \"ja\"]\n",
+    )
+    .unwrap();
+    fs::create_dir(temp.path().join("src")).unwrap();
+    fs::write(temp.path().join("src/lib.rs"), SOURCE).unwrap();
+    for (path, content) in docs {
+        let full_path = temp.path().join("docs").join(path);
+        fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        fs::write(full_path, content).unwrap();
+    }
+    temp
+}
+
+#[test]
+fn test_locale_docs_paths_fill_in_each_locale() {
+    let temp = setup_crate(&[]);
+
+    let paths = locale_docs_paths(&temp.path().join("src/lib.rs")).unwrap();
+
+    assert_eq!(
+        paths,
+        [
+            ("en".to_string(), "../docs/en".to_string()),
+            ("ja".to_string(), "../docs/ja".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_translation_coverage_lists_untranslated_docs() {
+    let temp = setup_crate(&[
+        ("en/lib.md", "Crate docs\n"),
+        ("en/lib/Config.md", "A config\n"),
+        ("en/lib/Config/port.md", "The port\n"),
+        ("en/lib/run.md", "Runs it\n"),
+        ("ja/lib.md", "クレートのドキュメント\n"),
+        ("ja/lib/run.md", "実行する\n"),
+    ]);
+    let parsed = [parse_file(&temp.path().join("src/lib.rs")).unwrap()];
+    let docs_root = |locale: &str| temp.path().join("docs").join(locale);
+
+    let en = translation_coverage(&parsed, &docs_root("en").to_string_lossy());
+    let ja = translation_coverage(&parsed, &docs_root("ja").to_string_lossy());
+
+    assert_eq!(en.expected, 4);
+    assert!(en.missing.is_empty());
+    assert_eq!(ja.expected, 4);
+    assert_eq!(ja.present(), 2);
+    assert_eq!(ja.missing, ["lib/Config.md", "lib/Config/port.md"]);
+}
@@ -1,12 +1,16 @@
+mod assets;
 mod bookend;
 mod diff;
 mod discover;
 mod expected;
 mod extract;
+mod fragments;
 mod inject;
+mod locales;
 mod reformat;
 mod restore;
 mod rewrite;
+mod sections;
 mod strip;
 mod write;
 
@@ -508,3 +508,363 @@ pub enum ChunkType {
         "Should not contain module_doc!"
     );
 }
+
+#[test]
+fn test_restore_follows_name_override() {
+    let source = r#"
+#[cfg(not(feature = "v2"))]
+#[syncdoc::omnidoc]
+pub fn read() {}
+
+#[cfg(feature = "v2")]
+#[syncdoc::omnidoc(name = "read_v2")]
+pub fn read() {}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("read.md", "Reads with the v1 protocol\n"),
+            ("read_v2.md", "Reads with the v2 protocol\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    let v1 = restored.find("/// Reads with the v1 protocol").unwrap();
+    let v2 = restored.find("/// Reads with the v2 protocol").unwrap();
+    assert!(v1 < v2);
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_follows_helper_attrs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+impl Reader {
+    #[syncdoc(path = "shared/ctor.md")]
+    pub fn new() -> Self { Reader }
+
+    /// Only used by tests
+    #[syncdoc(skip)]
+    fn shim(&self) {}
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("shared/ctor.md", "Creates a reader\n"),
+            ("Reader/shim.md", "Should not be read\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Creates a reader"));
+    assert!(restored.contains("/// Only used by tests"));
+    assert!(!restored.contains("Should not be read"));
+    assert!(!restored.contains("syncdoc("));
+}
+
+#[test]
+fn test_restore_tuple_fields() {
+    let source = r#"
+#[syncdoc::omnidoc]
+pub struct Meters(pub f64);
+
+#[syncdoc::omnidoc]
+pub enum Shape {
+    Rect(f64, f64),
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Meters/0.md", "The raw value\n"),
+            ("Shape/Rect/1.md", "Height\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// The raw value"));
+    assert!(restored.contains("/// Height"));
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_union_docs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+#[repr(C)]
+pub union Value {
+    pub i: u32,
+    pub f: f32,
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Value.md", "An FFI value\n"),
+            ("Value/i.md", "As an integer\n"),
+            ("Value/f.md", "As a float\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// An FFI value"));
+    assert!(restored.contains("/// As an integer"));
+    assert!(restored.contains("/// As a float"));
+    assert!(restored.contains("#[repr(C)]"));
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_macro_rules_docs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+mod helpers {
+    #[macro_export]
+    macro_rules! square {
+        ($x:expr) => { $x * $x };
+    }
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("helpers.md", "Helper macros\n"),
+            ("helpers/square.md", "Squares an expression\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Squares an expression"));
+    assert!(restored.contains("#[macro_export]"));
+    assert!(restored.contains("macro_rules! square"));
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_extern_block_docs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+extern "C" {
+    pub fn abs(x: i32) -> i32;
+    pub static errno: i32;
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[("abs.md", "Absolute value\n"), ("errno.md", "Last error\n")],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Absolute value"));
+    assert!(restored.contains("/// Last error"));
+    assert!(restored.contains("pub static errno: i32;"));
+    assert!(!restored.contains("syncdoc"));
+}
+
+#[test]
+fn test_restore_associated_item_docs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+pub trait Parser {
+    type Output: Clone;
+    const MAX_DEPTH: usize;
+}
+
+#[syncdoc::omnidoc]
+impl Parser for Json {
+    type Output = Value;
+    const MAX_DEPTH: usize = 64;
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Parser.md", "Parses input\n"),
+            ("Parser/Output.md", "The parsed value\n"),
+            ("Parser/MAX_DEPTH.md", "Deepest nesting accepted\n"),
+            ("Json/Parser/Output.md", "A JSON value\n"),
+            ("Json/Parser/MAX_DEPTH.md", "JSON nests up to 64 levels\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// The parsed value"));
+    assert!(restored.contains("/// Deepest nesting accepted"));
+    assert!(restored.contains("/// A JSON value"));
+    assert!(restored.contains("/// JSON nests up to 64 levels"));
+    assert!(restored.contains("type Output: Clone;"));
+    assert!(restored.contains("const MAX_DEPTH: usize;"));
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_generic_impl_docs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+impl From<A> for Foo {
+    fn from(a: A) -> Self { Foo }
+}
+
+#[syncdoc::omnidoc]
+impl From<B> for Foo {
+    fn from(b: B) -> Self { Foo }
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Foo/From[A]/from.md", "Converts from an A\n"),
+            ("Foo/From[B]/from.md", "Converts from a B\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Converts from an A"));
+    assert!(restored.contains("/// Converts from a B"));
+    assert!(!restored.contains("omnidoc"));
+}
+
+#[test]
+fn test_restore_turns_front_matter_back_into_attributes() {
+    let source = r#"
+#[syncdoc::omnidoc]
+pub fn fetch() {}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[(
+            "fetch.md",
+            "---\ndeprecated: \"use bar\"\nhidden: true\n---\n\nFetches the value\n",
+        )],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Fetches the value"));
+    assert!(restored.contains("#[deprecated(note = \"use bar\")]"));
+    assert!(restored.contains("#[doc(hidden)]"));
+    assert!(!restored.contains("---"));
+}
+
+#[test]
+fn test_restore_keeps_github_alerts() {
+    let source = r#"
+#[syncdoc::omnidoc]
+pub fn free() {}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[(
+            "free.md",
+            "Frees the buffer\n\n> [!WARNING]\n> Don't use it after\n",
+        )],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// > [!WARNING]"), "{}", restored);
+    assert!(
+        restored.contains("/// > Don't use it after"),
+        "{}",
+        restored
+    );
+    assert!(!restored.contains("class=\"warning\""), "{}", restored);
+}
+
+#[test]
+fn test_restore_feature_docs_as_cfg_attrs() {
+    let source = r#"
+#[syncdoc::omnidoc]
+pub struct Config;
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Config.md", "Settings for a run\n"),
+            (
+                "Config.feature-serde.md",
+                "With `serde` it can be\nread from a file.\n",
+            ),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(restored.contains("/// Settings for a run"), "{}", restored);
+    assert!(
+        restored.contains(
+            "#[cfg_attr(feature = \"serde\", doc = \" With `serde` it can be\")]\n\
+            #[cfg_attr(feature = \"serde\", doc = \" read from a file.\")]\n\
+            pub struct Config;"
+        ),
+        "{}",
+        restored
+    );
+}
+
+#[test]
+fn test_restore_keeps_docs_behind_the_omnidoc_predicate() {
+    let source = r#"
+#[syncdoc::omnidoc(cfg_attr = "all(doc, not(test))")]
+pub struct Config {
+    pub port: u16,
+}
+"#;
+
+    let (temp, source_path) = setup_test_with_docs(
+        source,
+        &[
+            ("Config.md", "Settings for a run\n"),
+            ("Config/port.md", "The port\n"),
+        ],
+    );
+
+    let parsed = parse_file(&source_path).unwrap();
+    let restored = restore_file(&parsed, temp.path().join("docs").to_str().unwrap()).unwrap();
+
+    assert!(
+        restored.contains(
+            "#[cfg_attr(all(doc, not(test)), doc = \" Settings for a run\")]\npub struct Config {"
+        ),
+        "{}",
+        restored
+    );
+    assert!(
+        restored.contains("    #[cfg_attr(all(doc, not(test)), doc = \" The port\")]\n"),
+        "{}",
+        restored
+    );
+    assert!(!restored.contains("omnidoc"), "{}", restored);
+    assert!(!restored.contains("///"), "{}", restored);
+}
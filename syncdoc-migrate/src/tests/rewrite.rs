@@ -155,3 +155,35 @@ pub struct Foo;
         "Should have blank line after Options enum"
     );
 }
+
+#[test]
+fn test_cut_removes_attributes_lifted_into_front_matter() {
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("test.rs");
+    let source = "/// Fetches it
+#[doc(alias = \"get\")]
+#[deprecated(since = \"1.4\", note = \"use bar\")]
+#[inline]
+pub fn fetch() {}
+
+#[deprecated]
+pub fn old() {}
+";
+    fs::write(&file_path, source).unwrap();
+    let parsed = crate::discover::parse_file(&file_path).unwrap();
+
+    let rewritten = rewrite_file(&parsed, "docs", DocsPathMode::TomlConfig, true, true).unwrap();
+    assert_eq!(
+        rewritten,
+        "#[syncdoc::omnidoc]
+#[inline]
+pub fn fetch() {}
+
+#[syncdoc::omnidoc]
+pub fn old() {}
+"
+    );
+}
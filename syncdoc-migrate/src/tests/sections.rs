@@ -0,0 +1,143 @@
+use crate::discover::{parse_file, ParsedFile};
+use crate::restore::restore_file;
+use crate::sections::*;
+use crate::write::{write_extracts, DocExtract};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+const SOURCE: &str = "#![doc = syncdoc::module_doc!()]
+
+#[syncdoc::omnidoc]
+pub struct Config {
+    pub port: u16,
+}
+
+#[syncdoc::omnidoc]
+pub fn run() {}
+";
+
+/// A crate using the sections layout, with `src/lib.rs` and the given doc files
+fn setup_crate(docs: &[(&str, &str)]) -> (TempDir, ParsedFile, String) {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[package]\nname = \"test\"\nversion = \"0.1.0\"\n\n\
+        [package.metadata.syncdoc]\ndocs-path = \"docs\"\nlayout = \"sections\"\n",
+    )
+    .unwrap();
+    fs::create_dir(temp.path().join("src")).unwrap();
+    fs::write(temp.path().join("src/lib.rs"), SOURCE).unwrap();
+
+    let docs_root = temp.path().join("docs");
+    for (path, content) in docs {
+        let full_path = docs_root.join(path);
+        fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        fs::write(full_path, content).unwrap();
+    }
+
+    let parsed = parse_file(&temp.path().join("src/lib.rs")).unwrap();
+    (temp, parsed, docs_root.to_string_lossy().to_string())
+}
+
+#[test]
+fn test_fold_into_sections_merges_with_existing_file() {
+    let (_temp, parsed, docs_root) =
+        setup_crate(&[("lib.md", "Old crate docs\n\n## `run`\n\nRuns it\n")]);
+    let base = module_base(&parsed, &docs_root);
+    let extract = |path: &str, content: &str| {
+        DocExtract::new(
+            PathBuf::from(format!("{}/{}", docs_root, path)),
+            content.to_string(),
+            "src/lib.rs:1".to_string(),
+        )
+    };
+
+    let folded = fold_into_sections(
+        vec![
+            extract("lib.md", "Crate docs"),
+            extract("lib/Config.md", "A config"),
+            extract("shared/port.md", "The port"),
+        ],
+        &base,
+    );
+
+    assert_eq!(folded.len(), 2);
+    assert_eq!(
+        folded[0].markdown_path,
+        PathBuf::from(format!("{}.md", base))
+    );
+    assert_eq!(
+        folded[0].content,
+        "Crate docs\n\n## `run`\n\nRuns it\n\n## `Config`\n\nA config\n"
+    );
+    // A redirect outside the module keeps its own file
+    assert!(folded[1].markdown_path.ends_with("shared/port.md"));
+}
+
+#[test]
+fn test_doc_exists_checks_files_then_sections() {
+    let (_temp, parsed, docs_root) = setup_crate(&[("lib.md", "## `run`\n")]);
+    let base = module_base(&parsed, &docs_root);
+
+    assert!(doc_exists(
+        &PathBuf::from(format!("{}/run.md", base)),
+        &base
+    ));
+    assert!(!doc_exists(
+        &PathBuf::from(format!("{}/Config.md", base)),
+        &base
+    ));
+}
+
+#[test]
+fn test_convert_round_trips_between_layouts() {
+    let per_item = [
+        ("lib.md", "Crate docs\n"),
+        ("lib/Config.md", "A config\n"),
+        ("lib/Config/port.md", "The port\n"),
+        ("lib/run.md", "Runs it\n"),
+    ];
+    let (_temp, parsed, docs_root) = setup_crate(&per_item);
+
+    let (extracts, replaced) = to_sections(&parsed, &docs_root);
+    assert_eq!(replaced.len(), 3);
+    assert_eq!(
+        extracts[0].content,
+        "Crate docs\n\n## `Config`\n\nA config\n\n### `Config::port`\n\nThe port\n\n## `run`\n\nRuns it\n"
+    );
+    write_extracts(&extracts, false).unwrap();
+    remove_replaced(&replaced, &docs_root, false).unwrap();
+    assert!(!PathBuf::from(format!("{}/lib", docs_root)).exists());
+
+    write_extracts(&to_per_item(&parsed, &docs_root), false).unwrap();
+    for (path, content) in per_item {
+        let written = fs::read_to_string(format!("{}/{}", docs_root, path)).unwrap();
+        assert_eq!(written, content, "{}", path);
+    }
+}
+
+#[test]
+fn test_restore_reads_sections() {
+    let (_temp, parsed, docs_root) = setup_crate(&[(
+        "lib.md",
+        "Crate docs\n\n## `Config`\n\nA config\n\n### `Config::port`\n\nThe port\n\n## `run`\n\nRuns it\n",
+    )]);
+
+    let restored = restore_file(&parsed, &docs_root).unwrap();
+
+    assert_eq!(
+        restored,
+        "//! Crate docs
+
+/// A config
+pub struct Config {
+    /// The port
+    pub port: u16,
+}
+
+/// Runs it
+pub fn run() {}
+"
+    );
+}
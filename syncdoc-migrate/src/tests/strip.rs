@@ -90,3 +90,68 @@ fn test_strip_mixed_inner_outer_docs() {
     assert!(!output_str.contains("Inner"));
     assert!(!output_str.contains("Outer"));
 }
+
+#[test]
+fn test_strip_keeps_docs_on_skipped_items() {
+    let input = quote! {
+        #[doc = "Only used by tests"]
+        #[syncdoc(skip)]
+        fn shim() {}
+
+        pub struct Config {
+            #[doc = "Scratch space"]
+            #[syncdoc(skip)]
+            scratch: Vec<u8>,
+            #[doc = "Port to bind"]
+            port: u16,
+        }
+    };
+
+    let output = strip_doc_attrs(input).to_string();
+
+    assert!(output.contains("Only used by tests"));
+    assert!(output.contains("Scratch space"));
+    assert!(!output.contains("Port to bind"));
+}
+
+#[test]
+fn test_strip_tuple_field_docs() {
+    let input = quote! {
+        pub struct Meters(
+            #[doc = "The raw value"]
+            #[serde(default)]
+            pub f64,
+        );
+
+        pub enum Shape {
+            Rect(#[doc = "Width"] f64, f64),
+        }
+    };
+
+    let output = strip_doc_attrs(input).to_string();
+
+    assert!(!output.contains("The raw value"));
+    assert!(!output.contains("Width"));
+    assert!(output.contains("serde"));
+}
+
+#[test]
+fn test_strip_extern_block_keeps_block_docs() {
+    let input = quote! {
+        #[doc = "Bindings to libc"]
+        extern "C" {
+            #[doc = "Absolute value"]
+            pub fn abs(x: i32) -> i32;
+            #[doc = "Last error"]
+            pub static mut errno: i32;
+        }
+    };
+
+    let output_str = strip_doc_attrs(input).to_string();
+
+    assert!(output_str.contains("Bindings to libc"));
+    assert!(!output_str.contains("Absolute value"));
+    assert!(!output_str.contains("Last error"));
+    assert!(output_str.contains("pub fn abs"));
+    assert!(output_str.contains("pub static mut errno : i32 ;"));
+}
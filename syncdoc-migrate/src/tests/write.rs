@@ -163,3 +163,217 @@ fn test_build_path_no_context() {
 
     assert_eq!(path, "docs/func.md");
 }
+
+#[test]
+fn test_extract_follows_name_override() {
+    let paths = parse_and_get_paths(
+        r#"
+        /// Reads with the v1 protocol
+        #[cfg(not(feature = "v2"))]
+        #[syncdoc::omnidoc]
+        pub fn read() {}
+
+        /// Reads with the v2 protocol
+        #[cfg(feature = "v2")]
+        #[syncdoc::omnidoc(name = "read_v2")]
+        pub fn read() {}
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{read,read_v2}.md");
+}
+
+#[test]
+fn test_extract_follows_helper_attrs() {
+    let paths = parse_and_get_paths(
+        r#"
+        impl Reader {
+            /// Creates a reader
+            #[syncdoc(path = "shared/ctor.md")]
+            pub fn new() -> Self { Reader }
+
+            /// Only used by tests
+            #[syncdoc(skip)]
+            fn shim(&self) {}
+
+            /// Reads a value
+            pub fn read(&self) {}
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{shared/ctor,Reader/read}.md");
+}
+
+#[test]
+fn test_extract_tuple_fields_by_index() {
+    let paths = parse_and_get_paths(
+        r#"
+        /// Distance in meters
+        pub struct Meters(
+            /// The raw value
+            pub f64,
+        );
+
+        pub enum Shape {
+            Rect(
+                /// Width
+                f64,
+                /// Height
+                f64,
+            ),
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{Meters/{0,},Shape/Rect/{0,1}}.md");
+}
+
+#[test]
+fn test_extract_union_docs() {
+    let paths = parse_and_get_paths(
+        r#"
+        /// An FFI value
+        #[repr(C)]
+        pub union Value {
+            /// As an integer
+            pub i: u32,
+            /// As a float
+            pub f: f32,
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/Value/{i,f,}.md");
+}
+
+#[test]
+fn test_extract_macro_rules_docs() {
+    let paths = parse_and_get_paths(
+        r#"
+        mod helpers {
+            /// Squares an expression
+            #[macro_export]
+            macro_rules! square {
+                ($x:expr) => { $x * $x };
+            }
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/helpers/square.md");
+}
+
+#[test]
+fn test_extract_extern_block_docs() {
+    let paths = parse_and_get_paths(
+        r#"
+        /// Bindings to libc
+        extern "C" {
+            /// Absolute value
+            pub fn abs(x: i32) -> i32;
+            /// Last error
+            pub static errno: i32;
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{abs,errno}.md");
+}
+
+#[test]
+fn test_extract_associated_item_docs() {
+    let paths = parse_and_get_paths(
+        r#"
+        pub trait Parser {
+            /// The parsed value
+            type Output: Clone;
+            /// Deepest nesting accepted
+            const MAX_DEPTH: usize;
+        }
+
+        impl Parser for Json {
+            /// A JSON value
+            type Output = Value;
+            /// JSON nests up to 64 levels
+            const MAX_DEPTH: usize = 64;
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(to_braces(&get_path_refs(&paths)), @"docs/{Parser/{Output,MAX_DEPTH},Json/Parser/{Output,MAX_DEPTH}}.md");
+}
+
+#[test]
+fn test_extract_generic_impl_docs() {
+    let paths = parse_and_get_paths(
+        r#"
+        impl From<A> for Foo {
+            /// Converts from an A
+            fn from(a: A) -> Self { Foo }
+        }
+
+        impl From<B> for Foo {
+            /// Converts from a B
+            fn from(b: B) -> Self { Foo }
+        }
+
+        impl Foo<u8> {
+            /// A byte-sized Foo
+            fn new() -> Self { Foo }
+        }
+        "#,
+        "test.rs",
+        "docs",
+    );
+
+    assert_snapshot!(get_path_refs(&paths).join("\n"), @r"
+    docs/Foo/From[A]/from.md
+    docs/Foo/From[B]/from.md
+    docs/Foo[u8]/new.md
+    ");
+}
+
+#[test]
+fn test_extract_feature_docs_into_companions() {
+    let (_temp_dir, file_path) = setup_test_file(
+        r#"
+        /// Settings for a run
+        #[cfg_attr(feature = "serde", doc = " Serializable with `serde`.")]
+        pub struct Config;
+        "#,
+        "test.rs",
+    );
+    let parsed = crate::discover::parse_file(&file_path).unwrap();
+
+    let extracts = extract_all_docs(&parsed, "docs");
+
+    let files: Vec<_> = extracts
+        .iter()
+        .map(|e| (e.markdown_path.to_str().unwrap(), e.content.as_str()))
+        .collect();
+    assert_eq!(
+        files,
+        [
+            ("docs/Config.md", "Settings for a run\n"),
+            (
+                "docs/Config.feature-serde.md",
+                "Serializable with `serde`.\n"
+            ),
+        ]
+    );
+}
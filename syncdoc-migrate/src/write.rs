@@ -1,13 +1,24 @@
 //! List all the files we expect to be produced from code with omnidoc attributes.
 
 use crate::discover::ParsedFile;
-use crate::extract::extract_doc_content;
+use crate::extract::{
+    doc_stem, extract_doc_content, extract_feature_docs, omnidoc_name_override, redirected_path,
+    skips_docs,
+};
+use crate::sections::{module_base, module_title};
 use std::collections::HashMap;
 use std::fs;
 pub(crate) use std::path::{Path, PathBuf};
+use syncdoc_core::features::{feature_doc_path, feature_of};
+use syncdoc_core::helper_attr::item_attributes;
+use syncdoc_core::parse::Attribute;
 use syncdoc_core::parse::{
-    EnumSig, EnumVariantData, ImplBlockSig, ModuleItem, ModuleSig, StructSig, TraitSig,
+    EnumSig, EnumVariantData, ExternBlockSig, ImplBlockSig, ModuleItem, ModuleSig, StructSig,
+    TraitSig, TupleField, UnionSig,
 };
+use syncdoc_core::path_utils::named_impl_context;
+use syncdoc_core::title::add_title;
+use unsynn::{CommaDelimitedVec, Many, ParenthesisGroupContaining};
 
 pub(crate) mod expected;
 pub use expected::find_expected_doc_paths;
@@ -37,6 +48,24 @@ impl DocExtract {
     }
 }
 
+/// An item's extract, followed by one for each feature it has docs for, in companion files
+///
+/// See [`syncdoc_core::features`] for where the companions go.
+fn with_feature_docs(attrs: &Option<Many<Attribute>>, extract: DocExtract) -> Vec<DocExtract> {
+    let path = extract.markdown_path.to_string_lossy().to_string();
+    let companions = extract_feature_docs(attrs)
+        .into_iter()
+        .map(|(feature, content)| {
+            DocExtract::new(
+                PathBuf::from(feature_doc_path(&path, &feature)),
+                content,
+                extract.source_location.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    std::iter::once(extract).chain(companions).collect()
+}
+
 /// Report of write operation results
 #[derive(Debug, Default)]
 pub struct WriteReport {
@@ -105,29 +134,54 @@ pub(crate) fn extract_item_docs(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    if item_attributes(item).is_some_and(skips_docs) {
+        return extracts;
+    }
 
     match item {
         ModuleItem::TraitMethod(method_sig) => {
             if let Some(content) = extract_doc_content(&method_sig.attributes) {
-                let path = build_path(base_path, &context, &method_sig.name.to_string());
+                let path = redirected_path(
+                    &method_sig.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&method_sig.attributes, &method_sig.name),
+                    ),
+                );
                 let location = format!(
                     "{}:{}",
                     source_file.display(),
                     method_sig.name.span().start().line
                 );
-                extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+                extracts.extend(with_feature_docs(
+                    &method_sig.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
             }
         }
 
         ModuleItem::Function(func_sig) => {
             if let Some(content) = extract_doc_content(&func_sig.attributes) {
-                let path = build_path(base_path, &context, &func_sig.name.to_string());
+                let path = redirected_path(
+                    &func_sig.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&func_sig.attributes, &func_sig.name),
+                    ),
+                );
                 let location = format!(
                     "{}:{}",
                     source_file.display(),
                     func_sig.name.span().start().line
                 );
-                extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+                extracts.extend(with_feature_docs(
+                    &func_sig.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
             }
         }
 
@@ -166,42 +220,116 @@ pub(crate) fn extract_item_docs(
             ));
         }
 
+        ModuleItem::Union(union_sig) => {
+            extracts.extend(extract_union_docs(
+                union_sig,
+                context,
+                base_path,
+                source_file,
+            ));
+        }
+
         ModuleItem::TypeAlias(type_alias) => {
             if let Some(content) = extract_doc_content(&type_alias.attributes) {
-                let path = build_path(base_path, &context, &type_alias.name.to_string());
+                let path = redirected_path(
+                    &type_alias.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&type_alias.attributes, &type_alias.name),
+                    ),
+                );
                 let location = format!(
                     "{}:{}",
                     source_file.display(),
                     type_alias.name.span().start().line
                 );
-                extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+                extracts.extend(with_feature_docs(
+                    &type_alias.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
             }
         }
 
         ModuleItem::Const(const_sig) => {
             if let Some(content) = extract_doc_content(&const_sig.attributes) {
-                let path = build_path(base_path, &context, &const_sig.name.to_string());
+                let path = redirected_path(
+                    &const_sig.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&const_sig.attributes, &const_sig.name),
+                    ),
+                );
                 let location = format!(
                     "{}:{}",
                     source_file.display(),
                     const_sig.name.span().start().line
                 );
-                extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+                extracts.extend(with_feature_docs(
+                    &const_sig.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
             }
         }
 
         ModuleItem::Static(static_sig) => {
             if let Some(content) = extract_doc_content(&static_sig.attributes) {
-                let path = build_path(base_path, &context, &static_sig.name.to_string());
+                let path = redirected_path(
+                    &static_sig.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&static_sig.attributes, &static_sig.name),
+                    ),
+                );
                 let location = format!(
                     "{}:{}",
                     source_file.display(),
                     static_sig.name.span().start().line
                 );
-                extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+                extracts.extend(with_feature_docs(
+                    &static_sig.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
+            }
+        }
+
+        ModuleItem::MacroRules(macro_sig) => {
+            if let Some(content) = extract_doc_content(&macro_sig.attributes) {
+                let path = redirected_path(
+                    &macro_sig.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &doc_stem(&macro_sig.attributes, &macro_sig.name),
+                    ),
+                );
+                let location = format!(
+                    "{}:{}",
+                    source_file.display(),
+                    macro_sig.name.span().start().line
+                );
+                extracts.extend(with_feature_docs(
+                    &macro_sig.attributes,
+                    DocExtract::new(PathBuf::from(path), content, location),
+                ));
             }
         }
 
+        ModuleItem::ExternBlock(extern_block) => {
+            extracts.extend(extract_extern_block_docs(
+                extern_block,
+                context,
+                base_path,
+                source_file,
+            ));
+        }
+
         // No documentation to extract from other items
         ModuleItem::Other(_) => {}
     }
@@ -218,48 +346,8 @@ pub(crate) fn extract_impl_docs(
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
 
-    // Determine the context path for the impl block
-    // If this is `impl Trait for Type`, context is [Type, Trait]
-    // If this is `impl Type`, context is [Type]
-    let impl_context = if let Some(for_trait) = &impl_block.for_trait {
-        // This is `impl Trait for Type`
-        // target_type contains the TRAIT name (before "for")
-        let trait_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        // for_trait.second contains the TYPE name (after "for")
-        let type_name = if let Some(first) = for_trait.second.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        // Context is Type/Trait
-        vec![type_name, trait_name]
-    } else {
-        // This is `impl Type`, extract Type from target_type
-        let type_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-        vec![type_name]
-    };
+    let impl_context =
+        named_impl_context(impl_block, omnidoc_name_override(&impl_block.attributes));
 
     let mut new_context = context;
     new_context.extend(impl_context);
@@ -286,21 +374,25 @@ pub(crate) fn extract_module_docs(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    let module_name = doc_stem(&module.attributes, &module.name);
 
     // Extract module's own documentation if present
     if let Some(content) = extract_doc_content(&module.attributes) {
-        let path = build_path(base_path, &context, &module.name.to_string());
+        let path = build_path(base_path, &context, &module_name);
         let location = format!(
             "{}:{}",
             source_file.display(),
             module.name.span().start().line
         );
-        extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+        extracts.extend(with_feature_docs(
+            &module.attributes,
+            DocExtract::new(PathBuf::from(path), content, location),
+        ));
     }
 
     // Update context with module name
     let mut new_context = context;
-    new_context.push(module.name.to_string());
+    new_context.push(module_name);
 
     // Access parsed items directly
     let module_content = &module.items.content;
@@ -316,6 +408,30 @@ pub(crate) fn extract_module_docs(
     extracts
 }
 
+/// Extracts documentation from an extern block and its foreign items
+///
+/// Foreign items belong to the enclosing module, so the context is unchanged. The block
+/// itself has no doc file, as rustdoc doesn't show docs on extern blocks.
+pub(crate) fn extract_extern_block_docs(
+    extern_block: &ExternBlockSig,
+    context: Vec<String>,
+    base_path: &str,
+    source_file: &Path,
+) -> Vec<DocExtract> {
+    let mut extracts = Vec::new();
+
+    for item_delimited in &extern_block.items.content.items.0 {
+        extracts.extend(extract_item_docs(
+            &item_delimited.value,
+            context.clone(),
+            base_path,
+            source_file,
+        ));
+    }
+
+    extracts
+}
+
 /// Extracts documentation from a trait and its methods
 pub(crate) fn extract_trait_docs(
     trait_def: &TraitSig,
@@ -324,21 +440,29 @@ pub(crate) fn extract_trait_docs(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    let trait_name = doc_stem(&trait_def.attributes, &trait_def.name);
 
     // Extract trait's own documentation if present
     if let Some(content) = extract_doc_content(&trait_def.attributes) {
-        let path = build_path(base_path, &context, &trait_def.name.to_string());
+        let path = redirected_path(
+            &trait_def.attributes,
+            base_path,
+            build_path(base_path, &context, &trait_name),
+        );
         let location = format!(
             "{}:{}",
             source_file.display(),
             trait_def.name.span().start().line
         );
-        extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+        extracts.extend(with_feature_docs(
+            &trait_def.attributes,
+            DocExtract::new(PathBuf::from(path), content, location),
+        ));
     }
 
     // Update context with trait name
     let mut new_context = context;
-    new_context.push(trait_def.name.to_string());
+    new_context.push(trait_name);
 
     // Access parsed items directly
     let module_content = &trait_def.items.content;
@@ -362,36 +486,53 @@ pub(crate) fn extract_enum_docs(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
-    let enum_name = enum_sig.name.to_string();
+    let enum_name = doc_stem(&enum_sig.attributes, &enum_sig.name);
 
     // Extract enum's own documentation
     if let Some(content) = extract_doc_content(&enum_sig.attributes) {
-        let path = build_path(base_path, &context, &enum_name);
+        let path = redirected_path(
+            &enum_sig.attributes,
+            base_path,
+            build_path(base_path, &context, &enum_name),
+        );
         let location = format!(
             "{}:{}",
             source_file.display(),
             enum_sig.name.span().start().line
         );
-        extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+        extracts.extend(with_feature_docs(
+            &enum_sig.attributes,
+            DocExtract::new(PathBuf::from(path), content, location),
+        ));
     }
 
     // Access parsed variants directly
     if let Some(variants_cdv) = enum_sig.variants.content.as_ref() {
         for variant_delimited in &variants_cdv.0 {
             let variant = &variant_delimited.value;
+            if skips_docs(&variant.attributes) {
+                continue;
+            }
             if let Some(content) = extract_doc_content(&variant.attributes) {
-                let path = build_path(
+                let path = redirected_path(
+                    &variant.attributes,
                     base_path,
-                    &context,
-                    &format!("{}/{}", enum_name, variant.name),
+                    build_path(
+                        base_path,
+                        &context,
+                        &format!("{}/{}", enum_name, variant.name),
+                    ),
                 );
-                extracts.push(DocExtract::new(
-                    PathBuf::from(path),
-                    content,
-                    format!(
-                        "{}:{}",
-                        source_file.display(),
-                        variant.name.span().start().line
+                extracts.extend(with_feature_docs(
+                    &variant.attributes,
+                    DocExtract::new(
+                        PathBuf::from(path),
+                        content,
+                        format!(
+                            "{}:{}",
+                            source_file.display(),
+                            variant.name.span().start().line
+                        ),
                     ),
                 ));
             }
@@ -401,25 +542,63 @@ pub(crate) fn extract_enum_docs(
                 if let Some(fields_cdv) = fields_containing.content.as_ref() {
                     for field_delimited in &fields_cdv.0 {
                         let field = &field_delimited.value;
+                        if skips_docs(&field.attributes) {
+                            continue;
+                        }
                         if let Some(content) = extract_doc_content(&field.attributes) {
-                            let path = build_path(
+                            let path = redirected_path(
+                                &field.attributes,
                                 base_path,
-                                &context,
-                                &format!("{}/{}/{}", enum_name, variant.name, field.name),
+                                build_path(
+                                    base_path,
+                                    &context,
+                                    &format!("{}/{}/{}", enum_name, variant.name, field.name),
+                                ),
                             );
-                            extracts.push(DocExtract::new(
-                                PathBuf::from(path),
-                                content,
-                                format!(
-                                    "{}:{}",
-                                    source_file.display(),
-                                    field.name.span().start().line
+                            extracts.extend(with_feature_docs(
+                                &field.attributes,
+                                DocExtract::new(
+                                    PathBuf::from(path),
+                                    content,
+                                    format!(
+                                        "{}:{}",
+                                        source_file.display(),
+                                        field.name.span().start().line
+                                    ),
                                 ),
                             ));
                         }
                     }
                 }
             }
+
+            // Positional fields of tuple variants are documented by index
+            if let Some(EnumVariantData::Tuple(fields_containing)) = &variant.data {
+                for (index, field) in indexed_tuple_fields(fields_containing) {
+                    if skips_docs(&field.attributes) {
+                        continue;
+                    }
+                    if let Some(content) = extract_doc_content(&field.attributes) {
+                        let path = redirected_path(
+                            &field.attributes,
+                            base_path,
+                            build_path(
+                                base_path,
+                                &context,
+                                &format!("{}/{}/{}", enum_name, variant.name, index),
+                            ),
+                        );
+                        extracts.extend(with_feature_docs(
+                            &field.attributes,
+                            DocExtract::new(
+                                PathBuf::from(path),
+                                content,
+                                format!("{}:{}", source_file.display(), tuple_field_line(field)),
+                            ),
+                        ));
+                    }
+                }
+            }
         }
     }
 
@@ -434,31 +613,136 @@ pub(crate) fn extract_struct_docs(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
-    let struct_name = struct_sig.name.to_string();
+    let struct_name = doc_stem(&struct_sig.attributes, &struct_sig.name);
 
     // Extract struct's own documentation
     if let Some(content) = extract_doc_content(&struct_sig.attributes) {
-        let path = build_path(base_path, &context, &struct_name);
+        let path = redirected_path(
+            &struct_sig.attributes,
+            base_path,
+            build_path(base_path, &context, &struct_name),
+        );
         let location = format!(
             "{}:{}",
             source_file.display(),
             struct_sig.name.span().start().line
         );
-        extracts.push(DocExtract::new(PathBuf::from(path), content, location));
+        extracts.extend(with_feature_docs(
+            &struct_sig.attributes,
+            DocExtract::new(PathBuf::from(path), content, location),
+        ));
     }
 
-    // Extract field documentation (only for named fields)
+    // Extract named field documentation
     if let syncdoc_core::parse::StructBody::Named(fields_containing) = &struct_sig.body {
         if let Some(fields_cdv) = fields_containing.content.as_ref() {
             for field_delimited in &fields_cdv.0 {
                 let field = &field_delimited.value;
+                if skips_docs(&field.attributes) {
+                    continue;
+                }
                 if let Some(content) = extract_doc_content(&field.attributes) {
-                    let path = build_path(
+                    let path = redirected_path(
+                        &field.attributes,
                         base_path,
-                        &context,
-                        &format!("{}/{}", struct_name, field.name),
+                        build_path(
+                            base_path,
+                            &context,
+                            &format!("{}/{}", struct_name, field.name),
+                        ),
                     );
-                    extracts.push(DocExtract::new(
+                    extracts.extend(with_feature_docs(
+                        &field.attributes,
+                        DocExtract::new(
+                            PathBuf::from(path),
+                            content,
+                            format!(
+                                "{}:{}",
+                                source_file.display(),
+                                field.name.span().start().line
+                            ),
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    // Positional fields are documented by index
+    if let syncdoc_core::parse::StructBody::Tuple(tuple) = &struct_sig.body {
+        for (index, field) in indexed_tuple_fields(&tuple.first) {
+            if skips_docs(&field.attributes) {
+                continue;
+            }
+            if let Some(content) = extract_doc_content(&field.attributes) {
+                let path = redirected_path(
+                    &field.attributes,
+                    base_path,
+                    build_path(base_path, &context, &format!("{}/{}", struct_name, index)),
+                );
+                extracts.extend(with_feature_docs(
+                    &field.attributes,
+                    DocExtract::new(
+                        PathBuf::from(path),
+                        content,
+                        format!("{}:{}", source_file.display(), tuple_field_line(field)),
+                    ),
+                ));
+            }
+        }
+    }
+
+    extracts
+}
+
+/// Extracts documentation from a union and its fields
+pub(crate) fn extract_union_docs(
+    union_sig: &UnionSig,
+    context: Vec<String>,
+    base_path: &str,
+    source_file: &Path,
+) -> Vec<DocExtract> {
+    let mut extracts = Vec::new();
+    let union_name = doc_stem(&union_sig.attributes, &union_sig.name);
+
+    // Extract union's own documentation
+    if let Some(content) = extract_doc_content(&union_sig.attributes) {
+        let path = redirected_path(
+            &union_sig.attributes,
+            base_path,
+            build_path(base_path, &context, &union_name),
+        );
+        let location = format!(
+            "{}:{}",
+            source_file.display(),
+            union_sig.name.span().start().line
+        );
+        extracts.extend(with_feature_docs(
+            &union_sig.attributes,
+            DocExtract::new(PathBuf::from(path), content, location),
+        ));
+    }
+
+    // Extract field documentation
+    if let Some(fields_cdv) = union_sig.fields.content.as_ref() {
+        for field_delimited in &fields_cdv.0 {
+            let field = &field_delimited.value;
+            if skips_docs(&field.attributes) {
+                continue;
+            }
+            if let Some(content) = extract_doc_content(&field.attributes) {
+                let path = redirected_path(
+                    &field.attributes,
+                    base_path,
+                    build_path(
+                        base_path,
+                        &context,
+                        &format!("{}/{}", union_name, field.name),
+                    ),
+                );
+                extracts.extend(with_feature_docs(
+                    &field.attributes,
+                    DocExtract::new(
                         PathBuf::from(path),
                         content,
                         format!(
@@ -466,8 +750,8 @@ pub(crate) fn extract_struct_docs(
                             source_file.display(),
                             field.name.span().start().line
                         ),
-                    ));
-                }
+                    ),
+                ));
             }
         }
     }
@@ -475,6 +759,29 @@ pub(crate) fn extract_struct_docs(
     extracts
 }
 
+/// Opens each extract with a title naming its item, for crates set to `strip-title`
+///
+/// An item's title is its doc file's name, and the module's own doc file is titled with
+/// [`module_title`]. Extracts that already open with their title are left as they are.
+pub fn add_titles(extracts: &mut [DocExtract], parsed: &ParsedFile, docs_root: &str) {
+    let module_doc = PathBuf::from(format!("{}.md", module_base(parsed, docs_root)));
+    // A feature's docs follow the item's, so they don't repeat its title
+    for extract in extracts
+        .iter_mut()
+        .filter(|extract| feature_of(&extract.markdown_path).is_none())
+    {
+        let name = if extract.markdown_path == module_doc {
+            module_title(parsed)
+        } else {
+            match extract.markdown_path.file_stem() {
+                Some(stem) => stem.to_string_lossy().to_string(),
+                None => continue,
+            }
+        };
+        extract.content = add_title(&extract.content, &name);
+    }
+}
+
 /// Writes documentation extracts to markdown files
 ///
 /// If `dry_run` is true, validates paths and reports what would be written
@@ -537,3 +844,25 @@ pub(crate) fn build_path(base_path: &str, context: &[String], item_name: &str) -
     parts.push(format!("{}.md", item_name));
     parts.join("/")
 }
+
+/// Positional fields paired with the index their docs are stored under
+pub(crate) fn indexed_tuple_fields(
+    fields: &ParenthesisGroupContaining<Option<CommaDelimitedVec<TupleField>>>,
+) -> impl Iterator<Item = (usize, &TupleField)> {
+    fields
+        .content
+        .iter()
+        .flat_map(|fields_cdv| fields_cdv.0.iter())
+        .map(|field_delimited| &field_delimited.value)
+        .enumerate()
+}
+
+/// Line a positional field starts on, for reporting its source location
+pub(crate) fn tuple_field_line(field: &TupleField) -> usize {
+    let mut tokens = proc_macro2::TokenStream::new();
+    quote::ToTokens::to_tokens(field, &mut tokens);
+    tokens
+        .into_iter()
+        .next()
+        .map_or(0, |token| token.span().start().line)
+}
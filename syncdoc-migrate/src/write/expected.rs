@@ -5,11 +5,15 @@
 //! are present. It's used to identify missing documentation files that should
 //! be created.
 
-use crate::write::DocExtract;
+use crate::extract::{doc_stem, omnidoc_name_override, redirected_path, skips_docs};
+use crate::write::{indexed_tuple_fields, tuple_field_line, DocExtract};
 pub(crate) use std::path::{Path, PathBuf};
+use syncdoc_core::helper_attr::item_attributes;
 use syncdoc_core::parse::{
-    EnumSig, EnumVariantData, ImplBlockSig, ModuleItem, ModuleSig, StructSig, TraitSig,
+    EnumSig, EnumVariantData, ExternBlockSig, ImplBlockSig, ModuleItem, ModuleSig, StructSig,
+    TraitSig, UnionSig,
 };
+use syncdoc_core::path_utils::named_impl_context;
 
 use super::ParsedFile;
 
@@ -66,10 +70,21 @@ pub(crate) fn find_item_paths(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    if item_attributes(item).is_some_and(skips_docs) {
+        return extracts;
+    }
 
     match item {
         ModuleItem::TraitMethod(method_sig) => {
-            let path = build_path(base_path, &context, &method_sig.name.to_string());
+            let path = redirected_path(
+                &method_sig.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&method_sig.attributes, &method_sig.name),
+                ),
+            );
             let location = format!(
                 "{}:{}",
                 source_file.display(),
@@ -83,7 +98,15 @@ pub(crate) fn find_item_paths(
         }
 
         ModuleItem::Function(func_sig) => {
-            let path = build_path(base_path, &context, &func_sig.name.to_string());
+            let path = redirected_path(
+                &func_sig.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&func_sig.attributes, &func_sig.name),
+                ),
+            );
             let location = format!(
                 "{}:{}",
                 source_file.display(),
@@ -121,8 +144,20 @@ pub(crate) fn find_item_paths(
             ));
         }
 
+        ModuleItem::Union(union_sig) => {
+            extracts.extend(find_union_paths(union_sig, context, base_path, source_file));
+        }
+
         ModuleItem::TypeAlias(type_alias) => {
-            let path = build_path(base_path, &context, &type_alias.name.to_string());
+            let path = redirected_path(
+                &type_alias.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&type_alias.attributes, &type_alias.name),
+                ),
+            );
             let location = format!(
                 "{}:{}",
                 source_file.display(),
@@ -136,7 +171,15 @@ pub(crate) fn find_item_paths(
         }
 
         ModuleItem::Const(const_sig) => {
-            let path = build_path(base_path, &context, &const_sig.name.to_string());
+            let path = redirected_path(
+                &const_sig.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&const_sig.attributes, &const_sig.name),
+                ),
+            );
             let location = format!(
                 "{}:{}",
                 source_file.display(),
@@ -150,7 +193,15 @@ pub(crate) fn find_item_paths(
         }
 
         ModuleItem::Static(static_sig) => {
-            let path = build_path(base_path, &context, &static_sig.name.to_string());
+            let path = redirected_path(
+                &static_sig.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&static_sig.attributes, &static_sig.name),
+                ),
+            );
             let location = format!(
                 "{}:{}",
                 source_file.display(),
@@ -163,6 +214,37 @@ pub(crate) fn find_item_paths(
             ));
         }
 
+        ModuleItem::MacroRules(macro_sig) => {
+            let path = redirected_path(
+                &macro_sig.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &doc_stem(&macro_sig.attributes, &macro_sig.name),
+                ),
+            );
+            let location = format!(
+                "{}:{}",
+                source_file.display(),
+                macro_sig.name.span().start().line
+            );
+            extracts.push(DocExtract::new(
+                PathBuf::from(path),
+                String::new(),
+                location,
+            ));
+        }
+
+        ModuleItem::ExternBlock(extern_block) => {
+            extracts.extend(find_extern_block_paths(
+                extern_block,
+                context,
+                base_path,
+                source_file,
+            ));
+        }
+
         ModuleItem::Other(_) => {}
     }
 
@@ -177,48 +259,8 @@ pub(crate) fn find_impl_paths(
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
 
-    // Determine the context path for the impl block
-    // If this is `impl Trait for Type`, context is [Type, Trait]
-    // If this is `impl Type`, context is [Type]
-    let impl_context = if let Some(for_trait) = &impl_block.for_trait {
-        // This is `impl Trait for Type`
-        // target_type contains the TRAIT name (before "for")
-        let trait_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        // for_trait.second contains the TYPE name (after "for")
-        let type_name = if let Some(first) = for_trait.second.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-
-        // Context is Type/Trait
-        vec![type_name, trait_name]
-    } else {
-        // This is `impl Type`, extract Type from target_type
-        let type_name = if let Some(first) = impl_block.target_type.0.first() {
-            if let proc_macro2::TokenTree::Ident(ident) = &first.value.second {
-                ident.to_string()
-            } else {
-                "Unknown".to_string()
-            }
-        } else {
-            "Unknown".to_string()
-        };
-        vec![type_name]
-    };
+    let impl_context =
+        named_impl_context(impl_block, omnidoc_name_override(&impl_block.attributes));
 
     let mut new_context = context;
     new_context.extend(impl_context);
@@ -243,8 +285,9 @@ pub(crate) fn find_module_paths(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    let module_name = doc_stem(&module.attributes, &module.name);
 
-    let path = build_path(base_path, &context, &module.name.to_string());
+    let path = build_path(base_path, &context, &module_name);
     let location = format!(
         "{}:{}",
         source_file.display(),
@@ -257,7 +300,7 @@ pub(crate) fn find_module_paths(
     ));
 
     let mut new_context = context;
-    new_context.push(module.name.to_string());
+    new_context.push(module_name);
 
     let module_content = &module.items.content;
     for item_delimited in &module_content.items.0 {
@@ -272,6 +315,26 @@ pub(crate) fn find_module_paths(
     extracts
 }
 
+pub(crate) fn find_extern_block_paths(
+    extern_block: &ExternBlockSig,
+    context: Vec<String>,
+    base_path: &str,
+    source_file: &Path,
+) -> Vec<DocExtract> {
+    let mut extracts = Vec::new();
+
+    for item_delimited in &extern_block.items.content.items.0 {
+        extracts.extend(find_item_paths(
+            &item_delimited.value,
+            context.clone(),
+            base_path,
+            source_file,
+        ));
+    }
+
+    extracts
+}
+
 pub(crate) fn find_trait_paths(
     trait_def: &TraitSig,
     context: Vec<String>,
@@ -279,8 +342,13 @@ pub(crate) fn find_trait_paths(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
+    let trait_name = doc_stem(&trait_def.attributes, &trait_def.name);
 
-    let path = build_path(base_path, &context, &trait_def.name.to_string());
+    let path = redirected_path(
+        &trait_def.attributes,
+        base_path,
+        build_path(base_path, &context, &trait_name),
+    );
     let location = format!(
         "{}:{}",
         source_file.display(),
@@ -293,7 +361,7 @@ pub(crate) fn find_trait_paths(
     ));
 
     let mut new_context = context;
-    new_context.push(trait_def.name.to_string());
+    new_context.push(trait_name);
 
     let module_content = &trait_def.items.content;
     for item_delimited in &module_content.items.0 {
@@ -315,9 +383,13 @@ pub(crate) fn find_enum_paths(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
-    let enum_name = enum_sig.name.to_string();
+    let enum_name = doc_stem(&enum_sig.attributes, &enum_sig.name);
 
-    let path = build_path(base_path, &context, &enum_name);
+    let path = redirected_path(
+        &enum_sig.attributes,
+        base_path,
+        build_path(base_path, &context, &enum_name),
+    );
     let location = format!(
         "{}:{}",
         source_file.display(),
@@ -333,10 +405,17 @@ pub(crate) fn find_enum_paths(
     if let Some(variants_cdv) = enum_sig.variants.content.as_ref() {
         for variant_delimited in &variants_cdv.0 {
             let variant = &variant_delimited.value;
-            let path = build_path(
+            if skips_docs(&variant.attributes) {
+                continue;
+            }
+            let path = redirected_path(
+                &variant.attributes,
                 base_path,
-                &context,
-                &format!("{}/{}", enum_name, variant.name),
+                build_path(
+                    base_path,
+                    &context,
+                    &format!("{}/{}", enum_name, variant.name),
+                ),
             );
             extracts.push(DocExtract::new(
                 PathBuf::from(path),
@@ -353,10 +432,17 @@ pub(crate) fn find_enum_paths(
                 if let Some(fields_cdv) = fields_containing.content.as_ref() {
                     for field_delimited in &fields_cdv.0 {
                         let field = &field_delimited.value;
-                        let path = build_path(
+                        if skips_docs(&field.attributes) {
+                            continue;
+                        }
+                        let path = redirected_path(
+                            &field.attributes,
                             base_path,
-                            &context,
-                            &format!("{}/{}/{}", enum_name, variant.name, field.name),
+                            build_path(
+                                base_path,
+                                &context,
+                                &format!("{}/{}/{}", enum_name, variant.name, field.name),
+                            ),
                         );
                         extracts.push(DocExtract::new(
                             PathBuf::from(path),
@@ -370,6 +456,29 @@ pub(crate) fn find_enum_paths(
                     }
                 }
             }
+
+            // Handle tuple variant fields, named by index
+            if let Some(EnumVariantData::Tuple(fields_containing)) = &variant.data {
+                for (index, field) in indexed_tuple_fields(fields_containing) {
+                    if skips_docs(&field.attributes) {
+                        continue;
+                    }
+                    let path = redirected_path(
+                        &field.attributes,
+                        base_path,
+                        build_path(
+                            base_path,
+                            &context,
+                            &format!("{}/{}/{}", enum_name, variant.name, index),
+                        ),
+                    );
+                    extracts.push(DocExtract::new(
+                        PathBuf::from(path),
+                        String::new(),
+                        format!("{}:{}", source_file.display(), tuple_field_line(field)),
+                    ));
+                }
+            }
         }
     }
 
@@ -383,9 +492,13 @@ pub(crate) fn find_struct_paths(
     source_file: &Path,
 ) -> Vec<DocExtract> {
     let mut extracts = Vec::new();
-    let struct_name = struct_sig.name.to_string();
+    let struct_name = doc_stem(&struct_sig.attributes, &struct_sig.name);
 
-    let path = build_path(base_path, &context, &struct_name);
+    let path = redirected_path(
+        &struct_sig.attributes,
+        base_path,
+        build_path(base_path, &context, &struct_name),
+    );
     let location = format!(
         "{}:{}",
         source_file.display(),
@@ -401,10 +514,17 @@ pub(crate) fn find_struct_paths(
         if let Some(fields_cdv) = fields_containing.content.as_ref() {
             for field_delimited in &fields_cdv.0 {
                 let field = &field_delimited.value;
-                let path = build_path(
+                if skips_docs(&field.attributes) {
+                    continue;
+                }
+                let path = redirected_path(
+                    &field.attributes,
                     base_path,
-                    &context,
-                    &format!("{}/{}", struct_name, field.name),
+                    build_path(
+                        base_path,
+                        &context,
+                        &format!("{}/{}", struct_name, field.name),
+                    ),
                 );
                 extracts.push(DocExtract::new(
                     PathBuf::from(path),
@@ -419,6 +539,79 @@ pub(crate) fn find_struct_paths(
         }
     }
 
+    if let syncdoc_core::parse::StructBody::Tuple(tuple) = &struct_sig.body {
+        for (index, field) in indexed_tuple_fields(&tuple.first) {
+            if skips_docs(&field.attributes) {
+                continue;
+            }
+            let path = redirected_path(
+                &field.attributes,
+                base_path,
+                build_path(base_path, &context, &format!("{}/{}", struct_name, index)),
+            );
+            extracts.push(DocExtract::new(
+                PathBuf::from(path),
+                String::new(),
+                format!("{}:{}", source_file.display(), tuple_field_line(field)),
+            ));
+        }
+    }
+
+    extracts
+}
+
+pub(crate) fn find_union_paths(
+    union_sig: &UnionSig,
+    context: Vec<String>,
+    base_path: &str,
+    source_file: &Path,
+) -> Vec<DocExtract> {
+    let mut extracts = Vec::new();
+    let union_name = doc_stem(&union_sig.attributes, &union_sig.name);
+
+    let path = redirected_path(
+        &union_sig.attributes,
+        base_path,
+        build_path(base_path, &context, &union_name),
+    );
+    let location = format!(
+        "{}:{}",
+        source_file.display(),
+        union_sig.name.span().start().line
+    );
+    extracts.push(DocExtract::new(
+        PathBuf::from(path),
+        String::new(),
+        location,
+    ));
+
+    if let Some(fields_cdv) = union_sig.fields.content.as_ref() {
+        for field_delimited in &fields_cdv.0 {
+            let field = &field_delimited.value;
+            if skips_docs(&field.attributes) {
+                continue;
+            }
+            let path = redirected_path(
+                &field.attributes,
+                base_path,
+                build_path(
+                    base_path,
+                    &context,
+                    &format!("{}/{}", union_name, field.name),
+                ),
+            );
+            extracts.push(DocExtract::new(
+                PathBuf::from(path),
+                String::new(),
+                format!(
+                    "{}:{}",
+                    source_file.display(),
+                    field.name.span().start().line
+                ),
+            ));
+        }
+    }
+
     extracts
 }
 
@@ -24,9 +24,14 @@ pub mod cli {
     use report::{aggregate_results, print_summary};
 
     use std::io;
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
+    use syncdoc_migrate::assets::asset_report;
+    use syncdoc_migrate::fragments::fragment_users;
+    use syncdoc_migrate::locales::{locale_docs_paths, translation_coverage};
+    use syncdoc_migrate::sections::remove_replaced;
     use syncdoc_migrate::{
-        discover_rust_files, get_or_create_docs_path, write_extracts, DocsPathMode,
+        discover_rust_files, get_layout, get_or_create_docs_path, parse_file, read_config,
+        set_layout, write_extracts, DocLayout, DocsPathMode,
     };
 
     /// Entry point for the `syncdoc` command-line interface.
@@ -62,12 +67,34 @@ pub mod cli {
             std::process::exit(1);
         }
 
+        // Converting only moves docs between files, so it doesn't touch the source
+        if args.convert.is_some() && (args.restore || args.strip_docs || args.annotate) {
+            eprintln!("Error: --convert cannot be used with --restore, --migrate, --cut, or --add");
+            std::process::exit(1);
+        }
+
+        // Listing fragments, assets or translations only reads the docs
+        if (args.fragments || args.assets || args.locales)
+            && (args.restore || args.strip_docs || args.annotate || args.convert.is_some())
+        {
+            eprintln!(
+                "Error: --fragments, --assets and --locales cannot be used with --restore, --migrate, --cut, --add, or --convert"
+            );
+            std::process::exit(1);
+        }
+
         let source_path = Path::new(&args.source);
         if !source_path.exists() {
             eprintln!("Error: Source path does not exist: {}", args.source);
             std::process::exit(1);
         }
 
+        // A mistake in the config is reported before anything is read or written with it
+        if let Err(e) = read_config(source_path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+
         // Get docs root path and mode
         let (docs_root, docs_mode) = if args.inline_paths || args.docs.is_some() {
             // Explicit --inline-paths or --docs flag means inline mode
@@ -76,7 +103,9 @@ pub mod cli {
             (docs_root, DocsPathMode::InlinePaths)
         } else {
             // Try to get from Cargo.toml, or use/create default
-            match get_or_create_docs_path(source_path, args.dry_run) {
+            // Listing fragments, assets or translations doesn't set up config that isn't there yet
+            let read_only = args.dry_run || args.fragments || args.assets || args.locales;
+            match get_or_create_docs_path(source_path, read_only) {
                 Ok((path, mode)) => (path, mode),
                 Err(e) => {
                     eprintln!("Warning: Failed to get docs path from Cargo.toml: {}", e);
@@ -86,19 +115,49 @@ pub mod cli {
             }
         };
 
+        // Docs are written in the configured layout, or when converting, the one converted to
+        let layout = match args.convert.as_deref() {
+            Some(target) => match target.parse::<DocLayout>() {
+                Ok(layout) => layout,
+                Err(_) => {
+                    eprintln!(
+                        "Error: --convert expects \"per-item\" or \"sections\", got \"{}\"",
+                        target
+                    );
+                    std::process::exit(1);
+                }
+            },
+            None => get_layout(source_path),
+        };
+
         if args.verbose {
             eprintln!("Source directory: {}", source_path.display());
             eprintln!("Docs root: {}", docs_root);
             eprintln!("Docs mode: {:?}", docs_mode);
+            eprintln!("Layout: {}", layout);
             eprintln!("Strip docs: {}", args.strip_docs);
             eprintln!("Annotate: {}", args.annotate);
             eprintln!("Restore: {}", args.restore);
             eprintln!();
         }
 
+        if args.fragments {
+            print_fragment_users(Path::new(&docs_root), layout)?;
+            return Ok(());
+        }
+        if args.assets {
+            print_asset_report(Path::new(&docs_root))?;
+            return Ok(());
+        }
+
         // Discover Rust files
         let rust_files = discover_rust_files(source_path)?;
 
+        if args.locales {
+            print_locale_coverage(source_path, &rust_files);
+            return Ok(());
+        }
+
         if rust_files.is_empty() {
             if args.verbose {
                 eprintln!("No Rust files found in source directory, nothing to process.");
@@ -126,14 +185,108 @@ pub mod cli {
         }
 
         // Process files in parallel using thread::scope
-        let results = sync_all(&rust_files, &args, &docs_root, docs_mode);
+        let results = sync_all(&rust_files, &args, &docs_root, docs_mode, layout);
         let agg = aggregate_results(results);
 
         write_extracts(&agg.all_extracts, args.dry_run)?;
+
+        // After converting, the old files go and the config points the macros at the new layout
+        if args.convert.is_some() {
+            remove_replaced(&agg.replaced, &docs_root, args.dry_run)?;
+            if !args.dry_run {
+                if let Err(e) = set_layout(source_path, layout) {
+                    eprintln!("Warning: Failed to set layout in Cargo.toml: {}", e);
+                }
+            }
+        }
         print_summary(&agg, &args, args.dry_run, args.verbose);
 
         Ok(())
     }
+
+    /// Prints each fragment included by the docs, followed by the docs that use it
+    fn print_fragment_users(docs_root: &Path, layout: DocLayout) -> io::Result<()> {
+        let users = fragment_users(docs_root, layout)?;
+        if users.is_empty() {
+            println!(
+                "No fragments are included by the docs in {}",
+                docs_root.display()
+            );
+        }
+        for (fragment, docs) in users {
+            println!("{}:", fragment);
+            for doc in docs {
+                println!("  {}", doc);
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints how many of the expected doc files each locale has, then the ones it's missing
+    fn print_locale_coverage(source_path: &Path, rust_files: &[PathBuf]) {
+        let locales = match locale_docs_paths(source_path) {
+            Ok(locales) => locales,
+            Err(e) => {
+                eprintln!("Error: Failed to get locales from Cargo.toml: {}", e);
+                std::process::exit(1);
+            }
+        };
+        if locales.is_empty() {
+            println!("No locales are set in the [package.metadata.syncdoc] config");
+            return;
+        }
+        // Files that don't parse have no expected docs to count, as when migrating
+        let parsed: Vec<_> = rust_files
+            .iter()
+            .filter_map(|file| parse_file(file).ok())
+            .collect();
+        for (locale, docs_root) in locales {
+            let coverage = translation_coverage(&parsed, &docs_root);
+            let percent = match coverage.expected {
+                0 => 100,
+                expected => coverage.present() * 100 / expected,
+            };
+            println!(
+                "{}: {}/{} ({}%)",
+                locale,
+                coverage.present(),
+                coverage.expected,
+                percent
+            );
+            for path in &coverage.missing {
+                println!("  {}", path);
+            }
+        }
+    }
+
+    /// Prints the assets under the docs directory that are referenced, unreferenced or missing
+    ///
+    /// Referenced and missing assets are followed by the docs that show them.
+    fn print_asset_report(docs_root: &Path) -> io::Result<()> {
+        let report = asset_report(docs_root)?;
+        let groups = [
+            ("Referenced", &report.referenced),
+            ("Missing", &report.missing),
+        ];
+        for (heading, assets) in groups {
+            if !assets.is_empty() {
+                println!("{}:", heading);
+            }
+            for (asset, docs) in assets {
+                println!("  {}", asset);
+                for doc in docs {
+                    println!("    {}", doc);
+                }
+            }
+        }
+        if !report.unreferenced.is_empty() {
+            println!("Unreferenced:");
+        }
+        for asset in &report.unreferenced {
+            println!("  {}", asset);
+        }
+        Ok(())
+    }
 }
 
 /// Hint replacement CLI for when the cli module is used without building the cli feature.
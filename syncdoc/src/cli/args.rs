@@ -30,6 +30,22 @@ pub struct Args {
     #[facet(named, short = 'r', long, default)]
     pub restore: bool,
 
+    /// Convert the docs tree to another layout (per-item or sections)
+    #[facet(named, long, default)]
+    pub convert: Option<String>,
+
+    /// List each shared fragment and the docs that include it
+    #[facet(named, long, default)]
+    pub fragments: bool,
+
+    /// List the assets under the docs directory, by whether the docs show them
+    #[facet(named, long, default)]
+    pub assets: bool,
+
+    /// Show how much of the docs each locale has translated
+    #[facet(named, long, default)]
+    pub locales: bool,
+
     /// Preview changes without writing files
     #[facet(named, short = 'n', long, default)]
     pub dry_run: bool,
@@ -65,6 +81,11 @@ pub fn print_usage() {
     println!("  -t, --touch        Touch empty markdown files for any that don't exist");
     println!("      --inline-paths Use inline path= parameters instead of Cargo.toml");
     println!("  -r, --restore      Restore inline doc comments from markdown files");
+    println!("      --convert <layout>");
+    println!("                     Convert the docs tree to 'per-item' or 'sections' files");
+    println!("      --fragments    List each shared fragment and the docs that include it");
+    println!("      --assets       List the docs dir's assets that are used, unused or missing");
+    println!("      --locales      Show each locale's share of the docs, and what it's missing");
     println!("  -n, --dry-run      Preview changes without writing files");
     println!("  -v, --verbose      Show verbose output");
     println!("  -h, --help         Show this help message");
@@ -84,4 +105,16 @@ pub fn print_usage() {
     println!();
     println!("  # Restore documentation from markdown back to source");
     println!("  syncdoc --restore");
+    println!();
+    println!("  # Gather each module's docs into one file with a heading per item");
+    println!("  syncdoc --convert sections");
+    println!();
+    println!("  # See which docs change when a shared fragment is edited");
+    println!("  syncdoc --fragments");
+    println!();
+    println!("  # Find images the docs no longer use, or use but are missing");
+    println!("  syncdoc --assets");
+    println!();
+    println!("  # See which docs are still to be translated");
+    println!("  syncdoc --locales");
 }
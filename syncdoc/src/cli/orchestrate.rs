@@ -2,7 +2,7 @@ use super::args::Args;
 use super::worker::{sync, ProcessResult};
 use std::path::PathBuf;
 use std::thread::{available_parallelism, scope};
-use syncdoc_migrate::DocsPathMode;
+use syncdoc_migrate::{DocLayout, DocsPathMode};
 
 /// Run `sync` on all files in parallel and collect results.
 pub(crate) fn sync_all(
@@ -10,6 +10,7 @@ pub(crate) fn sync_all(
     args: &Args,
     docs_root: &str,
     docs_mode: DocsPathMode,
+    layout: DocLayout,
 ) -> Vec<ProcessResult> {
     let num_threads = available_parallelism().map_or(1, |n| n.get());
     let oversubscribe = 4;
@@ -23,7 +24,7 @@ pub(crate) fn sync_all(
                 s.spawn(|| {
                     chunk
                         .iter()
-                        .map(|file| sync(file, args, docs_root, docs_mode))
+                        .map(|file| sync(file, args, docs_root, docs_mode, layout))
                         .collect::<Vec<_>>()
                 })
             })
@@ -2,6 +2,7 @@
 
 use super::args::Args;
 use super::worker::ProcessResult;
+use std::path::PathBuf;
 use syncdoc_migrate::DocExtract;
 
 /// Aggregated results of a CLI run.
@@ -12,6 +13,7 @@ pub(crate) struct AggregatedResults {
     pub total_extracts: usize,
     pub parse_errors: Vec<String>,
     pub all_extracts: Vec<DocExtract>,
+    pub replaced: Vec<PathBuf>,
 }
 
 impl AggregatedResults {
@@ -24,6 +26,7 @@ impl AggregatedResults {
             total_extracts: 0,
             parse_errors: Vec::new(),
             all_extracts: Vec::new(),
+            replaced: Vec::new(),
         }
     }
 }
@@ -53,6 +56,12 @@ pub(crate) fn aggregate_results(results: Vec<ProcessResult>) -> AggregatedResult
                     agg.files_rewritten += 1;
                 }
             }
+            ProcessResult::Converted { extracts, replaced } => {
+                agg.files_processed += 1;
+                agg.total_extracts += extracts.len();
+                agg.all_extracts.extend(extracts);
+                agg.replaced.extend(replaced);
+            }
             ProcessResult::NoChange => {
                 agg.files_processed += 1;
             }
@@ -81,6 +90,9 @@ pub(crate) fn print_summary(agg: &AggregatedResults, args: &Args, dry_run: bool,
         eprintln!("Would process {} file(s)", agg.files_processed);
         if args.restore {
             eprintln!("Would restore {} file(s)", agg.files_rewritten);
+        } else if args.convert.is_some() {
+            eprintln!("Would write {} doc file(s)", agg.total_extracts);
+            eprintln!("Would remove {} doc file(s)", agg.replaced.len());
         } else {
             eprintln!("Would extract {} documentation(s)", agg.total_extracts);
             if args.touch {
@@ -94,6 +106,11 @@ pub(crate) fn print_summary(agg: &AggregatedResults, args: &Args, dry_run: bool,
         eprintln!("=== Restore Summary ===");
         eprintln!("Processed {} file(s)", agg.files_processed);
         eprintln!("Restored {} file(s)", agg.files_rewritten);
+    } else if args.convert.is_some() {
+        eprintln!("=== Convert Summary ===");
+        eprintln!("Processed {} file(s)", agg.files_processed);
+        eprintln!("Wrote {} doc file(s)", agg.total_extracts);
+        eprintln!("Removed {} doc file(s)", agg.replaced.len());
     } else {
         eprintln!("=== Migration Summary ===");
         eprintln!("Processed {} file(s)", agg.files_processed);
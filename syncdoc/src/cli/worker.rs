@@ -2,10 +2,14 @@ use super::args::Args;
 use crate::{vlog, vlog_if};
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use syncdoc_migrate::sections::{
+    doc_exists, fold_into_sections, module_base, to_per_item, to_sections,
+};
 use syncdoc_migrate::{
-    extract_all_docs, find_expected_doc_paths, parse_file as syncdoc_parse_file, restore_file,
-    rewrite_file, DocExtract, DocsPathMode, ParsedFile,
+    add_titles, extract_all_docs, find_expected_doc_paths, get_strip_title,
+    parse_file as syncdoc_parse_file, restore_file, rewrite_file, DocExtract, DocLayout,
+    DocsPathMode, ParsedFile,
 };
 
 /// Enum to represent the result of processing a single file
@@ -19,6 +23,10 @@ pub enum ProcessResult {
     Restored {
         dry_run: bool,
     },
+    Converted {
+        extracts: Vec<DocExtract>,
+        replaced: Vec<PathBuf>,
+    },
     NoChange,
     Error(String),
 }
@@ -73,6 +81,32 @@ fn handle_restore(
     }
 }
 
+/// === Step 3b: CONVERT MODE ===
+/// If a layout conversion is requested, we early exit with the files it writes and replaces
+fn handle_convert(
+    parsed: &ParsedFile,
+    args: &Args,
+    docs_root: &str,
+    layout: DocLayout,
+) -> Option<ProcessResult> {
+    args.convert.as_ref()?;
+
+    let (extracts, replaced) = match layout {
+        DocLayout::Sections => {
+            let (extracts, replaced) = to_sections(parsed, docs_root);
+            vlog_if!(args, !replaced.is_empty(), {"  Gathered {} doc file(s) into sections", replaced.len()});
+            (extracts, replaced)
+        }
+        DocLayout::PerItem => {
+            let extracts = to_per_item(parsed, docs_root);
+            vlog_if!(args, !extracts.is_empty(), {"  Split sections into {} doc file(s)", extracts.len()});
+            (extracts, Vec::new())
+        }
+    };
+
+    Some(ProcessResult::Converted { extracts, replaced })
+}
+
 /// === Step 5: Touch missing files if needed ===
 /// === Step 6: Rewrite source file if requested ===
 fn handle_touch_and_rewrite(
@@ -81,6 +115,7 @@ fn handle_touch_and_rewrite(
     args: &Args,
     docs_root: &str,
     docs_mode: DocsPathMode,
+    layout: DocLayout,
     all_extracts: &mut Vec<DocExtract>,
 ) -> Result<(bool, usize), ProcessResult> {
     let mut touched_count = 0;
@@ -94,11 +129,16 @@ fn handle_touch_and_rewrite(
 
         // Filter paths: exclude those already in extracts and those already on disk
         let existing_paths: HashSet<_> = all_extracts.iter().map(|e| &e.markdown_path).collect();
+        let base = module_base(parsed, docs_root);
 
         let missing: Vec<_> = expected_paths
             .into_iter()
             .filter(|extract| {
-                !existing_paths.contains(&extract.markdown_path) && !extract.markdown_path.exists()
+                let on_disk = match layout {
+                    DocLayout::PerItem => extract.markdown_path.exists(),
+                    DocLayout::Sections => doc_exists(&extract.markdown_path, &base),
+                };
+                !existing_paths.contains(&extract.markdown_path) && !on_disk
             })
             .collect();
 
@@ -145,11 +185,14 @@ fn handle_touch_and_rewrite(
 ///
 /// Preserves all steps: parsing, RESTORE mode, MIGRATION mode, touching missing files,
 /// and rewriting the source file if requested. Comments explicitly mirror the sequential version.
+///
+/// The `layout` is the one docs are written in, which when converting is the one to convert to.
 pub fn sync(
     file_path: &Path,
     args: &Args,
     docs_root: &str,
     docs_mode: DocsPathMode,
+    layout: DocLayout,
 ) -> ProcessResult {
     vlog!(args, {"Processing: {}", file_path.display()});
     let parsed = match parse_file_with_error_handling(file_path, args) {
@@ -160,6 +203,10 @@ pub fn sync(
     if let Some(result) = handle_restore(file_path, &parsed, args, docs_root) {
         return result; // early exit
     }
+    // CONVERT MODE: Move existing docs between per-item files and sections files
+    if let Some(result) = handle_convert(&parsed, args, docs_root, layout) {
+        return result; // early exit
+    }
     // MIGRATION MODE: Extract documentation from the parsed file
     let mut extracts = extract_all_docs(&parsed, docs_root);
 
@@ -172,12 +219,21 @@ pub fn sync(
         args,
         docs_root,
         docs_mode,
+        layout,
         &mut extracts,
     ) {
         Ok(result) => result,
         Err(e) => return e,
     };
 
+    // In the sections layout, the module's docs all go into one file, and otherwise each file
+    // can open with a title naming its item
+    if layout == DocLayout::Sections {
+        extracts = fold_into_sections(extracts, &module_base(&parsed, docs_root));
+    } else if get_strip_title(file_path) {
+        add_titles(&mut extracts, &parsed, docs_root);
+    }
+
     // Return full migration result
     ProcessResult::Migrated {
         extracts,
@@ -38,6 +38,15 @@ use proc_macro2::TokenStream as TokenStream2;
 /// }
 /// ```
 ///
+/// Allow items without a markdown file, leaving them undocumented:
+/// ```ignore
+/// # use syncdoc::omnidoc;
+/// #[omnidoc(path = "docs", optional)]
+/// mod drafts {
+///     pub fn not_written_up_yet() {}
+/// }
+/// ```
+///
 /// Document all methods in an impl block:
 /// ```ignore
 /// # use syncdoc::omnidoc;
@@ -104,3 +113,15 @@ pub fn module_doc(input: TokenStream) -> TokenStream {
         Err(error_tokens) => error_tokens.into(),
     }
 }
+
+/// Expands to nothing; `#[omnidoc]` invokes it to warn about a missing doc file.
+///
+/// With `missing = "warn"` configured, an item whose markdown file does not exist is left
+/// undocumented. Where it may be in an impl or trait, so the warning can't be an item of its own,
+/// it gets one of these invocations spanned to it, so the deprecation warning points at the item.
+#[doc(hidden)]
+#[deprecated(note = "no markdown file was found for this item, so it is left undocumented")]
+#[proc_macro]
+pub fn __missing_doc(_input: TokenStream) -> TokenStream {
+    TokenStream::new()
+}
@@ -82,3 +82,301 @@ fn cli_dry_run_does_not_modify_filesystem() {
         "Dry run created docs directory!"
     );
 }
+
+#[test]
+fn cli_convert_moves_docs_between_layouts() {
+    let temp = setup_test_project();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    let files = collect_all_files(temp.path());
+    let refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
+    assert_snapshot!(to_braces(&refs), @"{Cargo.toml,docs/{lib.md,lib/test.md},src/lib.rs}");
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--convert", "sections"])
+        .assert()
+        .success();
+    let files = collect_all_files(temp.path());
+    let refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
+    assert_snapshot!(to_braces(&refs), @"{Cargo.toml,docs/lib.md,src/lib.rs}");
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib.md")).unwrap(),
+        "Module docs\n\n## `test`\n\nFunction docs\n"
+    );
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("layout = \"sections\""), "{}", manifest);
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--convert", "per-item"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib/test.md")).unwrap(),
+        "Function docs\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib.md")).unwrap(),
+        "Module docs\n"
+    );
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("layout = \"per-item\""), "{}", manifest);
+}
+
+#[test]
+fn cli_fragments_lists_each_fragments_users() {
+    let temp = setup_test_project();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    fs::create_dir(temp.path().join("docs/shared")).unwrap();
+    fs::write(temp.path().join("docs/shared/errors.md"), "# Errors\n").unwrap();
+    fs::write(
+        temp.path().join("docs/lib/test.md"),
+        "Function docs\n\n<!-- syncdoc:include shared/errors.md -->\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--fragments"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout), @r"
+    shared/errors.md:
+      lib/test.md
+    ");
+}
+
+#[test]
+fn cli_strip_title_adds_titles_on_migrate_and_removes_them_on_restore() {
+    let temp = setup_test_project();
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        manifest + "\n[package.metadata.syncdoc]\nstrip-title = true\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib.md")).unwrap(),
+        "# test\n\nModule docs\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib/test.md")).unwrap(),
+        "# test\n\nFunction docs\n"
+    );
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--restore"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("src/lib.rs")).unwrap(),
+        "//! Module docs\n\n/// Function docs\npub fn test() {}\n"
+    );
+}
+
+#[test]
+fn cli_feature_docs_go_to_companions_and_back() {
+    let temp = setup_test_project();
+    let source = "//! Module docs\n\n/// Function docs\n\
+        #[cfg_attr(feature = \"serde\", doc = \" Serialises too\")]\npub fn test() {}\n";
+    fs::write(temp.path().join("src/lib.rs"), source).unwrap();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib/test.md")).unwrap(),
+        "Function docs\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib/test.feature-serde.md")).unwrap(),
+        "Serialises too\n"
+    );
+    assert!(!fs::read_to_string(temp.path().join("src/lib.rs"))
+        .unwrap()
+        .contains("cfg_attr"));
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--restore"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("src/lib.rs")).unwrap(),
+        source
+    );
+}
+
+#[test]
+fn cli_cfg_attr_predicates_survive_restore_and_migrate() {
+    let temp = setup_test_project();
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        manifest + "\n[package.metadata.syncdoc]\ncfg-attr = \"doc\"\n",
+    )
+    .unwrap();
+    let source = "#![doc = syncdoc::module_doc!()]\n\n\
+        #[syncdoc::omnidoc(cfg_attr = \"all(doc, not(test))\")]\npub fn test() {}\n\n\
+        #[syncdoc::omnidoc]\npub fn other() {}\n";
+    fs::write(temp.path().join("src/lib.rs"), source).unwrap();
+    fs::create_dir_all(temp.path().join("docs/lib")).unwrap();
+    fs::write(temp.path().join("docs/lib.md"), "Module docs\n").unwrap();
+    fs::write(temp.path().join("docs/lib/test.md"), "Function docs\n").unwrap();
+    fs::write(temp.path().join("docs/lib/other.md"), "Other docs\n").unwrap();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--restore"])
+        .assert()
+        .success();
+    // The item's own predicate is kept, the configured one stays in the config
+    assert_eq!(
+        fs::read_to_string(temp.path().join("src/lib.rs")).unwrap(),
+        "//! Module docs\n\n\
+        #[cfg_attr(all(doc, not(test)), doc = \" Function docs\")]\npub fn test() {}\n\n\
+        /// Other docs\npub fn other() {}\n"
+    );
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("src/lib.rs")).unwrap(),
+        source
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("docs/lib/test.md")).unwrap(),
+        "Function docs\n"
+    );
+}
+
+#[test]
+fn cli_reads_an_inline_config_and_rejects_a_mistaken_one() {
+    let temp = setup_test_project();
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    let inline_config = manifest.clone() + "metadata.syncdoc = { docs-path = \"api-docs\" }\n";
+    fs::write(temp.path().join("Cargo.toml"), &inline_config).unwrap();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    // The docs go where the config says, and no second section is added to the manifest
+    assert_eq!(
+        fs::read_to_string(temp.path().join("api-docs/lib/test.md")).unwrap(),
+        "Function docs\n"
+    );
+    assert_eq!(
+        fs::read_to_string(temp.path().join("Cargo.toml")).unwrap(),
+        inline_config
+    );
+
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        manifest + "\n[package.metadata.syncdoc]\ndocs-path = \"api-docs\"\nstrict = \"yes\"\n",
+    )
+    .unwrap();
+    let output = cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--restore"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: Cargo.toml:8:10: strict should be true or false, found a string\n"
+    );
+}
+
+#[test]
+fn cli_assets_lists_used_unused_and_missing_images() {
+    let temp = setup_test_project();
+
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    fs::create_dir(temp.path().join("docs/img")).unwrap();
+    fs::write(temp.path().join("docs/img/arch.svg"), "<svg/>").unwrap();
+    fs::write(temp.path().join("docs/img/old.svg"), "<svg/>").unwrap();
+    fs::write(
+        temp.path().join("docs/lib/test.md"),
+        "Function docs\n\n![Architecture](../img/arch.svg) ![Flow](flow.svg)\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--assets"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout), @r"
+    Referenced:
+      img/arch.svg
+        lib/test.md
+    Missing:
+      lib/flow.svg
+        lib/test.md
+    Unreferenced:
+      img/old.svg
+    ");
+}
+
+#[test]
+fn cli_locales_shows_each_locales_coverage() {
+    let temp = setup_test_project();
+    let manifest = temp.path().join("Cargo.toml");
+    let mut content = fs::read_to_string(&manifest).unwrap();
+    content.push_str(
+        "\n[package.metadata.syncdoc]\ndocs-path = \"docs/{locale}\"\nlocales = [\"en\", \"ja\"]\n",
+    );
+    fs::write(&manifest, content).unwrap();
+
+    // Migrating writes the default locale's docs
+    cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--migrate"])
+        .assert()
+        .success();
+    fs::create_dir(temp.path().join("docs/ja")).unwrap();
+    fs::write(temp.path().join("docs/ja/lib.md"), "モジュール\n").unwrap();
+
+    let output = cargo_bin_cmd!("syncdoc")
+        .current_dir(temp.path())
+        .args(["--locales"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_snapshot!(String::from_utf8_lossy(&output.stdout), @r"
+    en: 2/2 (100%)
+    ja: 1/2 (50%)
+      lib/test.md
+    ");
+}